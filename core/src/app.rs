@@ -80,6 +80,7 @@ cfg_if! {
             Kng {
                 reset_settings : bool,
                 disable : bool,
+                dump_metrics : Option<std::path::PathBuf>,
             },
             Kaspad { args : Box<NodeArgs> },
         }
@@ -114,6 +115,13 @@ cfg_if! {
                         .action(ArgAction::SetTrue)
                         .help("Reset kaspa-ng settings")
                     )
+                    .arg(
+                        Arg::new("dump-metrics")
+                        .long("dump-metrics")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(std::path::PathBuf))
+                        .help("Write in-memory metrics to FILE on exit (JSON if FILE ends with .json, CSV otherwise)")
+                    )
                     .subcommand(
                         Command::new("i18n").hide(true)
                         .about("kaspa-ng i18n user interface translation")
@@ -154,8 +162,9 @@ cfg_if! {
                     } else {
                         let disable = matches.get_one::<bool>("disable").cloned().unwrap_or(false);
                         let reset_settings = matches.get_one::<bool>("reset-settings").cloned().unwrap_or(false);
+                        let dump_metrics = matches.get_one::<std::path::PathBuf>("dump-metrics").cloned();
 
-                        Args::Kng { reset_settings, disable }
+                        Args::Kng { reset_settings, disable, dump_metrics }
                     }
             }
         }
@@ -210,7 +219,7 @@ cfg_if! {
                     manage_i18n(op)?;
                 }
 
-                Args::Kng { reset_settings, disable } => {
+                Args::Kng { reset_settings, disable, dump_metrics } => {
                     init_graceful_panic_handler();
 
                     workflow_log::set_colors_enabled(true);
@@ -294,6 +303,13 @@ cfg_if! {
                     let runtime = runtime.lock().unwrap().take().unwrap();
                     runtime.shutdown().await;
 
+                    if let Some(path) = dump_metrics {
+                        match runtime.metrics_service().dump(&path) {
+                            Ok(()) => println!("metrics written to '{}'", path.display()),
+                            Err(err) => println!("unable to write metrics to '{}': {err}", path.display()),
+                        }
+                    }
+
                 }
             }
 
@@ -389,9 +389,18 @@ impl Core {
 
     pub fn change_current_network(&mut self, network: Network) {
         if self.settings.node.network != network {
-            self.settings.node.network = network;
+            self.settings.change_network(network);
             self.get_mut::<modules::Settings>()
-                .change_current_network(network);
+                .change_network(network);
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                let storage_root = self
+                    .settings
+                    .node
+                    .kaspad_daemon_storage_folder_enable
+                    .then_some(self.settings.node.kaspad_daemon_storage_folder.as_str());
+                self.storage.track_storage_root(storage_root);
+            }
             self.store_settings();
             self.runtime
                 .kaspa_service()
@@ -680,6 +689,10 @@ impl Core {
             Events::ChangeSection(type_id) => {
                 self.select_with_type_id(type_id);
             }
+            Events::Lookup(target) => {
+                self.get_mut::<modules::BlockLookup>().lookup(target);
+                self.select::<modules::BlockLookup>();
+            }
             Events::NetworkChange(network) => {
                 self.modules.clone().values().for_each(|module| {
                     module.network_change(self, network);
@@ -711,6 +724,9 @@ impl Core {
                     MarketUpdate::Ohlc(ohlc) => {
                         self.market.as_mut().unwrap().ohlc.replace(ohlc);
                     }
+                    MarketUpdate::History(history) => {
+                        self.market.as_mut().unwrap().history.replace(history);
+                    }
                 }
             }
             Events::ThemeChange => {
@@ -788,6 +804,15 @@ impl Core {
             Events::NodeInfo { node_info } => {
                 self.node_info = node_info;
             }
+            Events::NodeMaintenance { status } => {
+                self.state.maintenance = status;
+            }
+            Events::NodePortConflicts { conflicts } => {
+                if !conflicts.is_empty() {
+                    self.state.maintenance = None;
+                }
+                self.state.port_conflicts = conflicts;
+            }
             Events::Close { .. } => {}
             Events::UnlockSuccess => {}
             Events::UnlockFailure { .. } => {}
@@ -854,6 +879,8 @@ impl Core {
                         self.state.is_connected = true;
                         self.state.url = url;
                         self.state.network_id = Some(network_id);
+                        self.state.maintenance = None;
+                        self.state.port_conflicts.clear();
 
                         self.modules.clone().values().for_each(|module| {
                             module.connect(self, Network::from(network_id));
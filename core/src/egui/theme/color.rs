@@ -66,6 +66,8 @@ pub struct ThemeColor {
     pub block_dag_block_stroke_color: Color32,
     pub block_dag_vspc_connect_color: Color32,
     pub block_dag_parent_connect_color: Color32,
+    pub block_dag_blue_block_fill_color: Color32,
+    pub block_dag_red_block_fill_color: Color32,
 }
 
 impl ThemeColor {
@@ -135,6 +137,8 @@ impl ThemeColor {
             block_dag_block_stroke_color: Color32::from_rgb(15, 84, 77),
             block_dag_vspc_connect_color: Color32::from_rgb(23, 150, 137),
             block_dag_parent_connect_color: Color32::from_rgba_premultiplied(173, 216, 230, 220),
+            block_dag_blue_block_fill_color: Color32::from_rgb(52, 120, 224),
+            block_dag_red_block_fill_color: Color32::from_rgb(220, 60, 60),
         }
     }
 
@@ -204,6 +208,8 @@ impl ThemeColor {
             block_dag_block_stroke_color: Color32::from_rgb(42, 51, 50),
             block_dag_vspc_connect_color: Color32::from_rgb(11, 77, 70),
             block_dag_parent_connect_color: Color32::from_rgba_premultiplied(0, 0, 0, 72),
+            block_dag_blue_block_fill_color: Color32::from_rgb(40, 100, 200),
+            block_dag_red_block_fill_color: Color32::from_rgb(200, 50, 50),
         }
     }
 }
@@ -1,5 +1,7 @@
 use crate::imports::*;
 use crate::market::*;
+use crate::modules::block_lookup::LookupTarget;
+use crate::runtime::services::kaspa::{MaintenanceStatus, PortConflict};
 use crate::storage::StorageUpdateOptions;
 use crate::utils::Release;
 use kaspa_metrics_core::MetricsSnapshot;
@@ -48,6 +50,13 @@ pub enum Events {
     NodeInfo {
         node_info: Option<Box<String>>,
     },
+    NodeMaintenance {
+        status: Option<MaintenanceStatus>,
+    },
+    NodePortConflicts {
+        conflicts: Vec<PortConflict>,
+    },
+    Lookup(LookupTarget),
     Close,
     Exit,
 }
@@ -84,14 +84,16 @@ pub use crate::network::{Network, NetworkPressure};
 pub use crate::notifications::{Notifications, UserNotification, UserNotifyKind};
 pub use crate::primitives::{
     Account, AccountCollection, AccountSelectorButtonExtension, BlockDagGraphSettings, DaaBucket,
-    DagBlock, Transaction, TransactionCollection,
+    DagBlock, FiatValuation, Transaction, TransactionCollection,
 };
 pub use crate::result::Result;
 pub use crate::runtime::{runtime, spawn, spawn_with_result, Payload, Runtime, Service};
 pub use crate::settings::{
-    EstimatorMode, EstimatorSettings, KaspadNodeKind, NetworkInterfaceConfig, NetworkInterfaceKind,
-    NodeConnectionConfigKind, NodeMemoryScale, NodeSettings, RpcConfig, RpcOptions, Settings,
-    UserInterfaceSettings,
+    AlertComparison, AlertRule, EstimatorMode, EstimatorSettings, FixedPriceConfig,
+    JsonEndpointConfig, KaspadNodeKind, MarketDataProviderConfig, MarketMonitorSettings,
+    MetricsDashboard, MetricsGraph, NetworkInterfaceConfig, NetworkInterfaceKind,
+    NodeConnectionConfigKind, NodeMemoryScale, NodePeerMode, NodeSettings, PrometheusSettings,
+    RpcConfig, RpcOptions, Settings, UserInterfaceSettings,
 };
 pub use crate::state::State;
 pub use crate::status::Status;
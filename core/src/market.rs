@@ -1,4 +1,12 @@
 use crate::imports::*;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+const MILLIS_PER_DAY: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// Days a historical price may be carried forward when the
+/// history has no price for the requested day.
+const MAX_PRICE_HISTORY_GAP_DAYS: u64 = 3;
 
 #[derive(Debug)]
 pub struct MarketData {
@@ -24,19 +32,287 @@ impl MarketData {
 
 pub type MarketDataMap = AHashMap<String, MarketData>;
 
-#[derive(Default, Debug)]
-pub struct Ohlc {}
+/// Time range of OHLC candles fetched from the market data provider.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OhlcRange {
+    #[default]
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl OhlcRange {
+    pub fn list() -> &'static [OhlcRange] {
+        &[
+            OhlcRange::Day,
+            OhlcRange::Week,
+            OhlcRange::Month,
+            OhlcRange::Year,
+        ]
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            OhlcRange::Day => i18n("24h"),
+            OhlcRange::Week => i18n("7d"),
+            OhlcRange::Month => i18n("30d"),
+            OhlcRange::Year => i18n("1y"),
+        }
+    }
+
+    pub fn days(&self) -> u32 {
+        match self {
+            OhlcRange::Day => 1,
+            OhlcRange::Week => 7,
+            OhlcRange::Month => 30,
+            OhlcRange::Year => 365,
+        }
+    }
+
+    /// Time after which cached candles of this range are refreshed
+    pub fn cache_duration(&self) -> Duration {
+        match self {
+            OhlcRange::Day => Duration::from_secs(5 * 60),
+            OhlcRange::Week => Duration::from_secs(30 * 60),
+            OhlcRange::Month => Duration::from_secs(60 * 60),
+            OhlcRange::Year => Duration::from_secs(6 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Candle {
+    /// Close time of the candle (unix time in milliseconds)
+    pub timestamp: f64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct Ohlc {
+    pub range: OhlcRange,
+    pub candles: Vec<Candle>,
+}
+
+impl Ohlc {
+    pub fn new(range: OhlcRange, candles: Vec<Candle>) -> Self {
+        Self { range, candles }
+    }
+
+    /// Duration of a single candle in milliseconds
+    pub fn candle_duration(&self) -> f64 {
+        match self.candles.as_slice() {
+            [.., a, b] => b.timestamp - a.timestamp,
+            _ => 0.0,
+        }
+    }
+}
 
 pub type OhlcMap = AHashMap<String, Ohlc>;
 
+/// Daily KAS prices per currency, keyed by days since the unix epoch.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct PriceHistory {
+    prices: AHashMap<String, BTreeMap<u64, f64>>,
+}
+
+impl PriceHistory {
+    fn day(timestamp_millis: f64) -> u64 {
+        (timestamp_millis / MILLIS_PER_DAY).floor().max(0.0) as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prices.values().all(BTreeMap::is_empty)
+    }
+
+    pub fn insert(&mut self, currency: &str, timestamp_millis: f64, price: f64) {
+        self.prices
+            .entry(currency.to_lowercase())
+            .or_default()
+            .insert(Self::day(timestamp_millis), price);
+    }
+
+    /// Merge `other` into this history, replacing prices of the same day.
+    pub fn merge(&mut self, other: PriceHistory) {
+        for (currency, prices) in other.prices {
+            self.prices.entry(currency).or_default().extend(prices);
+        }
+    }
+
+    /// Timestamp (unix time in milliseconds) of the most recent price
+    pub fn last_timestamp(&self, currency: &str) -> Option<f64> {
+        self.prices
+            .get(currency)
+            .and_then(|prices| prices.keys().next_back())
+            .map(|day| *day as f64 * MILLIS_PER_DAY)
+    }
+
+    /// Price of the day of `timestamp_millis`, falling back to the
+    /// closest previous day within [`MAX_PRICE_HISTORY_GAP_DAYS`].
+    pub fn price_at(&self, currency: &str, timestamp_millis: f64) -> Option<f64> {
+        let day = Self::day(timestamp_millis);
+        self.prices.get(currency).and_then(|prices| {
+            prices
+                .range(day.saturating_sub(MAX_PRICE_HISTORY_GAP_DAYS)..=day)
+                .next_back()
+                .map(|(_, price)| *price)
+        })
+    }
+
+    /// Parse daily prices from CSV lines of `date,price` or
+    /// `date,currency,price`, where the date is `YYYY-MM-DD` or a unix
+    /// timestamp in seconds or milliseconds. `currency` is used for
+    /// lines without a currency column. A header line is skipped.
+    pub fn from_csv(text: &str, currency: &str) -> Result<Self> {
+        let mut history = Self::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line
+                .split([',', ';'])
+                .map(|field| field.trim().trim_matches('"'))
+                .collect::<Vec<_>>();
+
+            let record = match fields.as_slice() {
+                [date, price] => parse_csv_record(date, currency, price),
+                [date, currency, price] => parse_csv_record(date, currency, price),
+                _ => None,
+            };
+
+            match record {
+                Some((timestamp, currency, price)) => history.insert(&currency, timestamp, price),
+                None if index == 0 => continue,
+                None => {
+                    return Err(Error::custom(format!(
+                        "invalid price history record on line {}: `{line}`",
+                        index + 1
+                    )))
+                }
+            }
+        }
+
+        if history.is_empty() {
+            Err(Error::custom("no prices found in the price history file"))
+        } else {
+            Ok(history)
+        }
+    }
+}
+
+fn parse_csv_record(date: &str, currency: &str, price: &str) -> Option<(f64, String, f64)> {
+    let timestamp = if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis() as f64
+    } else {
+        let timestamp = date.parse::<f64>().ok()?;
+        // timestamps below 10^11 are in seconds
+        if timestamp < 1e11 {
+            timestamp * 1000.0
+        } else {
+            timestamp
+        }
+    };
+
+    let price = price
+        .parse::<f64>()
+        .ok()
+        .filter(|price| price.is_finite())?;
+    (!currency.is_empty()).then(|| (timestamp, currency.to_lowercase(), price))
+}
+
 #[derive(Default, Debug)]
 pub struct Market {
     pub price: Option<Arc<MarketDataMap>>,
     pub ohlc: Option<Arc<OhlcMap>>,
+    pub history: Option<Arc<PriceHistory>>,
+}
+
+impl Market {
+    /// Market data of `currency` (lowercase ticker), if available.
+    pub fn data(&self, currency: &str) -> Option<&MarketData> {
+        self.price
+            .as_ref()
+            .and_then(|price_list| price_list.get(currency))
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum MarketUpdate {
     Price(Arc<MarketDataMap>),
     Ohlc(Arc<OhlcMap>),
+    History(Arc<PriceHistory>),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DAY: f64 = MILLIS_PER_DAY;
+
+    #[test]
+    fn test_parse_csv_record() {
+        let millis = 1_704_153_600_000.0;
+        assert_eq!(
+            parse_csv_record("2024-01-02", "USD", "0.1"),
+            Some((millis, "usd".to_string(), 0.1))
+        );
+        assert_eq!(
+            parse_csv_record("1704153600", "usd", "0.1"),
+            Some((millis, "usd".to_string(), 0.1))
+        );
+        assert_eq!(
+            parse_csv_record("1704153600000", "usd", "0.1"),
+            Some((millis, "usd".to_string(), 0.1))
+        );
+        assert_eq!(parse_csv_record("2024-13-02", "usd", "0.1"), None);
+        assert_eq!(parse_csv_record("date", "usd", "price"), None);
+        assert_eq!(parse_csv_record("2024-01-02", "usd", "NaN"), None);
+        assert_eq!(parse_csv_record("2024-01-02", "", "0.1"), None);
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let csv =
+            "date,price\n2024-01-01,0.1\n# comment\n\n2024-01-02;\"0.2\"\n1704326400,eur,0.3\n";
+        let history = PriceHistory::from_csv(csv, "USD").unwrap();
+        assert_eq!(history.price_at("usd", 1_704_067_200_000.0), Some(0.1));
+        assert_eq!(history.price_at("usd", 1_704_153_600_000.0), Some(0.2));
+        assert_eq!(history.price_at("eur", 1_704_326_400_000.0), Some(0.3));
+        assert_eq!(history.last_timestamp("usd"), Some(1_704_153_600_000.0));
+
+        let err = PriceHistory::from_csv("2024-01-01,0.1\n2024-01-02,abc", "usd").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+        assert!(PriceHistory::from_csv("date,price\n", "usd").is_err());
+        assert!(PriceHistory::from_csv("", "usd").is_err());
+    }
+
+    #[test]
+    fn test_price_at() {
+        let mut history = PriceHistory::default();
+        history.insert("usd", 10.0 * DAY, 1.0);
+        history.insert("usd", 20.0 * DAY + 1000.0, 2.0);
+
+        // prices are looked up by day
+        assert_eq!(history.price_at("usd", 10.0 * DAY + 3_600_000.0), Some(1.0));
+        assert_eq!(history.price_at("usd", 20.0 * DAY), Some(2.0));
+
+        // gaps are bridged with the closest previous price for a limited number of days
+        let gap = MAX_PRICE_HISTORY_GAP_DAYS as f64;
+        assert_eq!(history.price_at("usd", (10.0 + gap) * DAY), Some(1.0));
+        assert_eq!(history.price_at("usd", (11.0 + gap) * DAY), None);
+        assert_eq!(history.price_at("usd", 9.0 * DAY), None);
+        assert_eq!(history.price_at("eur", 10.0 * DAY), None);
+
+        let mut other = PriceHistory::default();
+        other.insert("usd", 10.0 * DAY, 1.5);
+        history.merge(other);
+        assert_eq!(history.price_at("usd", 10.0 * DAY), Some(1.5));
+    }
 }
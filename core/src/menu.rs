@@ -393,6 +393,30 @@ impl<'core> Menu<'core> {
                 self.select::<modules::BlockDag>();
                 ui.close_menu();
             }
+
+            ui.separator();
+            if ui.button(i18n("Mempool")).clicked() {
+                self.select::<modules::Mempool>();
+                ui.close_menu();
+            }
+
+            ui.separator();
+            if ui.button(i18n("Network")).clicked() {
+                self.select::<modules::NetworkStats>();
+                ui.close_menu();
+            }
+        }
+
+        ui.separator();
+        if ui.button(i18n("Lookup")).clicked() {
+            self.select::<modules::BlockLookup>();
+            ui.close_menu();
+        }
+
+        ui.separator();
+        if ui.button(i18n("Tools")).clicked() {
+            self.select::<modules::Tools>();
+            ui.close_menu();
         }
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -422,6 +446,12 @@ impl<'core> Menu<'core> {
                     ui.close_menu();
                 }
             }
+
+            ui.separator();
+            if ui.button(i18n("RPC Console")).clicked() {
+                self.select::<modules::RpcConsole>();
+                ui.close_menu();
+            }
         }
     }
 
@@ -33,12 +33,12 @@ impl<'context> BalancePane<'context> {
 
             if core.settings.market_monitor && (core.settings.node.network == Network::Mainnet || core.settings.developer.market_monitor_on_testnet) {
                 if let Some(market) = core.market.as_ref() {
-                    if let Some(price_list) = market.price.as_ref() {
-                        let mut symbols = price_list.keys().collect::<Vec<_>>();
-                        symbols.sort();
+                    if market.price.is_some() {
+                        // primary currency first, in the user's order
+                        let symbols = core.settings.market.currencies.iter();
                         ui.vertical_centered(|ui| {
-                            let text = symbols.into_iter().filter_map(|symbol| {
-                                    price_list.get(symbol).map(|data| {
+                            let text = symbols.filter_map(|symbol| {
+                                    market.data(symbol).map(|data| {
                                         let symbol = symbol.to_uppercase();
                                         let MarketData { price,  precision, .. } = data;
                                         // let text = 
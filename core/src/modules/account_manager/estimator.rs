@@ -108,10 +108,11 @@ impl<'context> Estimator<'context> {
         // };
 
 
-        let usd_rate = if core.settings.market_monitor {
-            core.market.as_ref().and_then(|market| {
-                market.price.as_ref().and_then(|price_list| {
-                    price_list.get("usd").map(|market_data| market_data.price)
+        // rate of the primary display currency
+        let fiat_rate = if core.settings.market_monitor {
+            core.settings.market.primary_currency().and_then(|currency| {
+                core.market.as_ref().and_then(|market| market.data(currency)).map(|market_data| {
+                    (currency.to_uppercase(), market_data.price, market_data.precision)
                 })
             })
         } else { None };
@@ -147,7 +148,7 @@ impl<'context> Estimator<'context> {
             let seconds = if is_send_amount_zero || error.is_some() { "---".to_string() } else { format_duration_estimate_i18n(seconds) };
             let total_kas = feerate * aggregate_mass as f64 * 1e-8;
             let total_sompi = (feerate * aggregate_mass as f64) as u64;
-            let total_usd = usd_rate.map(|rate| total_kas * rate);
+            let total_fiat = fiat_rate.as_ref().map(|(symbol, rate, precision)| format_currency_with_symbol(total_kas * rate, *precision, symbol));
             fee_selection = fee_selection.add_icon_less(mode, i18n(mode.to_string().as_str()), seconds, move |ui| {
                 // icon
                 let icon = if mode == fee_mode {
@@ -158,9 +159,8 @@ impl<'context> Estimator<'context> {
                 ui.label(icon);
                 
                 ui.label(RichText::new(sompi_to_kaspa_string_with_suffix(total_sompi, &network_type)).strong());
-                if let Some(usd) = total_usd {
-                    let usd = format_currency(usd, 6);
-                    ui.label(RichText::new(format!("~{} USD", usd)).strong());
+                if let Some(fiat) = total_fiat.as_ref() {
+                    ui.label(RichText::new(format!("~{fiat}")).strong());
                 }
                 ui.label(format!("{} SOMPI/g", format_with_precision(feerate)));
             });
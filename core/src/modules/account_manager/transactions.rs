@@ -21,8 +21,9 @@ impl Transactions {
                 });
             } else {
                 let total: u64 = transactions.iter().map(|transaction|transaction.aggregate_input_value()).sum();
+                let valuation = FiatValuation::new(core);
                 transactions.iter().for_each(|transaction| {
-                    transaction.render(ui, *network_type, account.network(), *current_daa_score, true, Some(total));
+                    transaction.render(ui, *network_type, account.network(), *current_daa_score, true, Some(total), valuation.as_ref());
                 });
             }
         });
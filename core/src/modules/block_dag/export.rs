@@ -0,0 +1,216 @@
+use crate::imports::*;
+use egui_plot::PlotBounds;
+use std::fmt::Write;
+
+/// File format of an exported Block DAG viewport.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DagExportFormat {
+    Svg,
+    Png,
+}
+
+impl DagExportFormat {
+    pub fn title(&self) -> &'static str {
+        match self {
+            DagExportFormat::Svg => "SVG",
+            DagExportFormat::Png => "PNG",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DagExportFormat::Svg => "svg",
+            DagExportFormat::Png => "png",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DagExportOptions {
+    pub format: DagExportFormat,
+    /// PNG resolution as a multiple of the viewport size
+    pub scale: u32,
+    pub transparent: bool,
+}
+
+impl Default for DagExportOptions {
+    fn default() -> Self {
+        Self {
+            format: DagExportFormat::Svg,
+            scale: 2,
+            transparent: false,
+        }
+    }
+}
+
+/// Block outline polygon (plot coordinates), as drawn on the graph.
+pub struct SceneBlock {
+    pub outline: Vec<[f64; 2]>,
+    pub fill: Color32,
+}
+
+/// Edge between a block and its parent (plot coordinates).
+pub struct SceneEdge {
+    pub from: PlotPoint,
+    pub to: PlotPoint,
+    /// Control point offset factor of a bezier curve, `None` for a straight line
+    pub bezier: Option<f64>,
+}
+
+/// Vector snapshot of the Block DAG viewport.
+pub struct DagScene {
+    pub bounds: PlotBounds,
+    /// Viewport size in points
+    pub width: f32,
+    pub height: f32,
+    pub background: Color32,
+    pub show_grid: bool,
+    pub show_daa: bool,
+    pub grid_color: Color32,
+    pub parent_color: Color32,
+    pub vspc_color: Color32,
+    pub stroke_color: Color32,
+    pub parent_edges: Vec<SceneEdge>,
+    pub vspc_edges: Vec<SceneEdge>,
+    pub blocks: Vec<SceneBlock>,
+}
+
+fn svg_color(color: Color32) -> (String, f32) {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    (format!("#{r:02x}{g:02x}{b:02x}"), a as f32 / 255.0)
+}
+
+impl DagScene {
+    fn to_screen(&self, x: f64, y: f64) -> (f64, f64) {
+        let [min_x, min_y] = self.bounds.min();
+        let [max_x, max_y] = self.bounds.max();
+        (
+            (x - min_x) / (max_x - min_x) * self.width as f64,
+            (max_y - y) / (max_y - min_y) * self.height as f64,
+        )
+    }
+
+    fn write_edges(&self, svg: &mut String, edges: &[SceneEdge], color: Color32, width: f32) {
+        let (color, opacity) = svg_color(color);
+        for edge in edges {
+            let (x1, y1) = self.to_screen(edge.from.x, edge.from.y);
+            let (x2, y2) = self.to_screen(edge.to.x, edge.to.y);
+            let path = if let Some(offset_factor) = edge.bezier {
+                // same control points as `bezier()`, the mapping to the screen being affine
+                let offset = (edge.to.x - edge.from.x) * offset_factor;
+                let (cx1, cy1) = self.to_screen(edge.from.x + offset, edge.from.y);
+                let (cx2, cy2) = self.to_screen(edge.to.x - offset, edge.to.y);
+                format!("M {x1:.2} {y1:.2} C {cx1:.2} {cy1:.2}, {cx2:.2} {cy2:.2}, {x2:.2} {y2:.2}")
+            } else {
+                format!("M {x1:.2} {y1:.2} L {x2:.2} {y2:.2}")
+            };
+            writeln!(
+                svg,
+                r#"<path d="{path}" fill="none" stroke="{color}" stroke-opacity="{opacity:.3}" stroke-width="{width}"/>"#
+            )
+            .ok();
+        }
+    }
+
+    /// Render the scene as SVG.
+    pub fn to_svg(&self, transparent: bool) -> String {
+        let (width, height) = (self.width, self.height);
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )
+        .ok();
+
+        if !transparent {
+            let (color, _) = svg_color(self.background);
+            writeln!(svg, r#"<rect width="100%" height="100%" fill="{color}"/>"#).ok();
+        }
+
+        if self.show_grid || self.show_daa {
+            // same spacing as the DAA axis of the graph
+            let [min_x, _] = self.bounds.min();
+            let [max_x, _] = self.bounds.max();
+            let mut step = 10.0;
+            while step < (max_x - min_x) / 5.0 {
+                step *= 2.0;
+            }
+
+            let (color, opacity) = svg_color(self.grid_color);
+            let mut daa = (min_x / step).ceil() * step;
+            while daa <= max_x {
+                let (x, _) = self.to_screen(daa, 0.0);
+                if self.show_grid {
+                    writeln!(
+                        svg,
+                        r#"<line x1="{x:.2}" y1="0" x2="{x:.2}" y2="{height}" stroke="{color}" stroke-opacity="{opacity:.3}" stroke-width="0.5"/>"#
+                    )
+                    .ok();
+                }
+                if self.show_daa {
+                    writeln!(
+                        svg,
+                        r#"<text x="{x:.2}" y="{}" fill="{color}" font-family="sans-serif" font-size="10" text-anchor="middle">{} DAA</text>"#,
+                        height - 4.0,
+                        daa.trunc().separated_string()
+                    )
+                    .ok();
+                }
+                daa += step;
+            }
+        }
+
+        self.write_edges(&mut svg, &self.parent_edges, self.parent_color, 1.0);
+        self.write_edges(&mut svg, &self.vspc_edges, self.vspc_color, 3.0);
+
+        let (stroke, stroke_opacity) = svg_color(self.stroke_color);
+        for block in self.blocks.iter() {
+            let points = block
+                .outline
+                .iter()
+                .map(|[x, y]| {
+                    let (x, y) = self.to_screen(*x, *y);
+                    format!("{x:.2},{y:.2}")
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let (fill, fill_opacity) = svg_color(block.fill);
+            writeln!(
+                svg,
+                r#"<polygon points="{points}" fill="{fill}" fill-opacity="{fill_opacity:.3}" stroke="{stroke}" stroke-opacity="{stroke_opacity:.3}" stroke-width="1"/>"#
+            )
+            .ok();
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Write the scene to a file in the given format.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: &std::path::Path, options: &DagExportOptions) -> Result<()> {
+        match options.format {
+            DagExportFormat::Svg => {
+                std::fs::write(path, self.to_svg(options.transparent))?;
+            }
+            DagExportFormat::Png => {
+                let svg = self.to_svg(options.transparent);
+                let width = (self.width * options.scale as f32).round() as u32;
+                let height = (self.height * options.scale as f32).round() as u32;
+                let image =
+                    load_svg_bytes_with_size(svg.as_bytes(), Some(SizeHint::Size(width, height)))
+                        .map_err(Error::custom)?;
+                image::save_buffer(
+                    path,
+                    image.as_raw(),
+                    image.width() as u32,
+                    image.height() as u32,
+                    image::ColorType::Rgba8,
+                )
+                .map_err(|err| Error::custom(err.to_string()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
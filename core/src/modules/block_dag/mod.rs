@@ -0,0 +1,1038 @@
+use crate::imports::*;
+use crate::modules::block_lookup::LookupTarget;
+use crate::runtime::services::blockdag_monitor::{BlockColor, BlockColors};
+use chrono::{DateTime, Local};
+use egui_phosphor::light::{PAUSE, PLAY};
+use kaspa_rpc_core::RpcBlock;
+use egui_plot::{
+    LineStyle,
+    Plot,
+    PlotPoints, Polygon, uniform_grid_spacer, Line, PlotBounds,
+    // Legend,
+    // Corner
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod export;
+#[cfg(not(target_arch = "wasm32"))]
+use export::{DagExportFormat, DagExportOptions, DagScene, SceneBlock, SceneEdge};
+
+/// Control point offset of bezier parent edges, relative to the edge length
+const BEZIER_OFFSET_FACTOR : f64 = 0.6;
+
+/// Block as drawn on the graph: block data, position,
+/// whether it is on the VSPC and whether it has settled.
+type RenderedBlock = (Arc<RpcBlock>, PlotPoint, bool, bool);
+
+#[derive(Clone)]
+pub struct Preset {
+    name : &'static str,
+    daa_range : f64,
+    daa_offset : f64,
+    spread : f64,
+    noise : f64,
+    block_scale : f64,
+}
+
+const PRESETS: &[Preset] = &[
+    Preset {
+        name : "Large (1 BPS)",
+        daa_range : 36.0,
+        daa_offset : 10.0,
+        spread : 10.0,
+        noise : 0.0,
+        block_scale : 1.0,
+    },
+    Preset {
+        name : "Medium Wide",
+        daa_range : 100.0,
+        daa_offset : 16.0,
+        spread : 36.0,
+        noise : 0.0,
+        block_scale : 1.0,
+    },
+    Preset {
+        name : "Medium Narrow",
+        daa_range : 80.0,
+        daa_offset : 16.0,
+        spread : 22.0,
+        noise : 0.0,
+        block_scale : 1.2,
+    },
+    Preset {
+        name : "Small (10 BPS)",
+        daa_range : 180.0,
+        daa_offset : 32.0,
+        spread : 36.0,
+        noise : 1.0,
+        block_scale : 1.4,
+    },
+];
+
+impl From<Network> for Preset {
+    fn from(network: Network) -> Self {
+        match network {
+            Network::Mainnet => PRESETS[0].clone(),
+            Network::Testnet10 => PRESETS[0].clone(),
+            Network::Testnet11 => PRESETS[3].clone(),
+        }
+    }
+}
+
+pub struct BlockDag {
+    #[allow(dead_code)]
+    runtime: Runtime,
+    daa_cursor: f64,
+    last_daa_score : u64,
+    running : bool,
+    plot_bounds : PlotBounds,
+    bezier : bool,
+    parent_levels : usize,
+    parent_threshold : usize,
+    daa_offset : f64,
+    daa_range : f64,
+    block_scale : f64,
+    last_repaint : Instant,
+    settings: BlockDagGraphSettings,
+    background : Arc<AtomicBool>,
+    network : Network,
+    /// Block shown in the inspector panel
+    selected : Option<Arc<RpcBlock>>,
+    /// Block under the pointer during the previous frame
+    hovered : Option<KaspaHash>,
+    /// Blocks, new (tip) blocks and block colors captured when the view was paused
+    frozen : Option<(Vec<RenderedBlock>, AHashSet<KaspaHash>, BlockColors)>,
+    /// Whether a recorded session was being replayed during the previous frame
+    replaying : bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    export_options : DagExportOptions,
+    /// Export of the viewport to be written once the next frame is built
+    #[cfg(not(target_arch = "wasm32"))]
+    export_request : Option<DagExportOptions>,
+}
+
+impl BlockDag {
+    pub fn new(runtime: Runtime) -> Self {
+
+        let preset = Preset::from(Network::Mainnet);
+        let settings = BlockDagGraphSettings::new(preset.spread);
+        runtime.block_dag_monitor_service().update_settings(settings.clone());
+
+        Self {
+            runtime,
+            daa_cursor : 0.0,
+            last_daa_score : 0,
+            running : false,
+            plot_bounds : PlotBounds::NOTHING,
+            bezier : true,
+            daa_offset : preset.daa_offset,
+            daa_range : preset.daa_range,
+            block_scale : preset.block_scale,
+            last_repaint : Instant::now(),
+            parent_levels : 1,
+            parent_threshold : 200,
+            settings,
+            background : Arc::new(AtomicBool::new(false)),
+            network : Network::Mainnet,
+            selected : None,
+            hovered : None,
+            frozen : None,
+            replaying : false,
+            #[cfg(not(target_arch = "wasm32"))]
+            export_options : DagExportOptions::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            export_request : None,
+        }
+    }
+
+    pub fn background(&self) -> bool {
+        self.background.load(Ordering::SeqCst)
+    }
+
+    pub fn background_state(&self) -> Arc<AtomicBool> {
+        self.background.clone()
+    }
+
+    pub fn load_preset(&mut self, preset : &Preset) {
+        self.daa_range = preset.daa_range;
+        self.daa_offset = preset.daa_offset;
+        self.block_scale = preset.block_scale;
+        self.settings.y_dist = preset.spread;
+        self.settings.noise = preset.noise;
+    }
+
+    fn reset_state(&mut self) {
+        self.running = false;
+        self.daa_cursor = 0.0; 
+        self.last_daa_score = 0; 
+        self.frozen = None;
+        self.hovered = None;
+    }
+
+    fn is_paused(&self) -> bool {
+        self.frozen.is_some()
+    }
+
+    /// Freeze the live view, capturing all blocks currently tracked.
+    fn pause(&mut self) {
+        let block_dag_monitor_service = self.runtime.block_dag_monitor_service();
+        let blocks = if let Ok(mut daa_buckets) = block_dag_monitor_service.chain.lock() {
+            daa_buckets.values_mut().flat_map(DaaBucket::render).collect::<Vec<_>>()
+        } else {
+            return;
+        };
+        let new_blocks = block_dag_monitor_service.new_blocks().clone();
+        let block_colors = block_dag_monitor_service.block_colors();
+        self.frozen = Some((blocks, new_blocks, block_colors));
+    }
+
+    fn resume(&mut self) {
+        self.frozen = None;
+    }
+
+    /// Block closest to the plot coordinates, within the block outline.
+    fn block_at<'a>(&self, blocks : &'a [RenderedBlock], x : f64, y : f64) -> Option<&'a RenderedBlock> {
+        let d = 1.5 * self.block_scale;
+        blocks.iter()
+            .filter(|(_, point, _, _)| (point.x - x).abs() <= d && (point.y - y).abs() <= d)
+            .min_by(|(_, a, _, _), (_, b, _, _)| {
+                let da = (a.x - x).powi(2) + (a.y - y).powi(2);
+                let db = (b.x - x).powi(2) + (b.y - y).powi(2);
+                da.total_cmp(&db)
+            })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn toggle_recording(&mut self) {
+        let block_dag_monitor_service = self.runtime.block_dag_monitor_service();
+        if block_dag_monitor_service.recorded_events().is_some() {
+            match block_dag_monitor_service.stop_recording() {
+                Ok(Some((path, events))) => {
+                    runtime().notify(UserNotification::success(i18n_args(
+                        "Recorded {events} events to {path}",
+                        &[("events", events.to_string()), ("path", path.to_string_lossy().to_string())],
+                    )));
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    runtime().notify(UserNotification::error(format!("{}: {err}", i18n("Unable to save the recording"))));
+                }
+            }
+        } else {
+            let Some(path) = rfd::FileDialog::new()
+                .add_filter("JSON Lines", &["jsonl"])
+                .set_file_name("kaspa-ng-block-dag.jsonl")
+                .save_file() else {
+                return;
+            };
+
+            if let Err(err) = block_dag_monitor_service.start_recording(&path) {
+                runtime().notify(UserNotification::error(format!("{}: {err}", i18n("Unable to start recording"))));
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_replay(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON Lines", &["jsonl"])
+            .pick_file() else {
+            return;
+        };
+
+        match crate::runtime::services::BlockDagMonitorService::load_recording(&path) {
+            Ok(records) => {
+                self.resume();
+                self.selected = None;
+                self.runtime.block_dag_monitor_service().start_replay(records);
+            }
+            Err(err) => {
+                runtime().notify(UserNotification::error(format!("{}: {err}", i18n("Unable to load the recording"))));
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn render_export(&mut self, ui : &mut Ui, close : &mut bool) {
+        ui.checkbox(&mut self.export_options.transparent, i18n("Transparent background"));
+        ui.space();
+        ui.horizontal(|ui| {
+            ui.label(i18n("PNG resolution:"));
+            for scale in 1..=4 {
+                ui.selectable_value(&mut self.export_options.scale, scale, format!("{scale}×"));
+            }
+        });
+        ui.space();
+        ui.horizontal(|ui| {
+            for format in [DagExportFormat::Svg, DagExportFormat::Png] {
+                if ui.button(format!("{}...", format.title())).clicked() {
+                    self.export_request = Some(DagExportOptions { format, ..self.export_options });
+                    *close = true;
+                }
+            }
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export(&self, scene : DagScene, options : DagExportOptions) {
+        let format = options.format;
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(format.title(), &[format.extension()])
+            .set_file_name(format!("kaspa-ng-block-dag.{}", format.extension()))
+            .save_file() else {
+            return;
+        };
+
+        match scene.save(&path, &options) {
+            Ok(()) => {
+                runtime().notify(UserNotification::success(i18n_args(
+                    "Block DAG exported to {path}",
+                    &[("path", path.to_string_lossy())],
+                )));
+            }
+            Err(err) => {
+                runtime().notify(UserNotification::error(format!("{}: {err}", i18n("Unable to export the Block DAG"))));
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn render_replay(&mut self, ui : &mut Ui) {
+        let block_dag_monitor_service = self.runtime.block_dag_monitor_service();
+
+        if ui.button(i18n("Open Recording...")).clicked() {
+            self.open_replay();
+        }
+
+        ui.space();
+
+        let mut speed = block_dag_monitor_service.replay_speed();
+        ui.add(
+            Slider::new(&mut speed, 0.1..=20.0)
+                .logarithmic(true)
+                .suffix("×")
+                .text(i18n("Speed"))
+        );
+        if speed != block_dag_monitor_service.replay_speed() {
+            block_dag_monitor_service.set_replay_speed(speed);
+        }
+
+        if let Some(status) = block_dag_monitor_service.replay_status() {
+            ui.space();
+            ui.add(
+                ProgressBar::new(status.events as f32 / status.total.max(1) as f32)
+                    .text(format!(
+                        "{} / {} ({} / {})",
+                        status.events,
+                        status.total,
+                        format_duration(status.position),
+                        format_duration(status.duration),
+                    ))
+            );
+            if status.finished {
+                ui.label(i18n("Replay finished"));
+            }
+            ui.space();
+            if ui.button(i18n("Stop Replay")).clicked() {
+                block_dag_monitor_service.stop_replay();
+            }
+        }
+    }
+
+    fn render_inspector(&mut self, ui : &mut Ui, blocks : &[RenderedBlock], block_colors : &BlockColors) {
+        let Some(block) = self.selected.clone() else {
+            return;
+        };
+
+        let header = &block.header;
+        let hash = header.hash;
+        let vspc = blocks.iter()
+            .find(|(block, _, _, _)| block.header.hash == hash)
+            .map(|(_, _, vspc, _)| *vspc)
+            .or_else(|| block.verbose_data.as_ref().map(|verbose_data| verbose_data.is_chain_block));
+        let parents = header.direct_parents();
+        let children = blocks.iter()
+            .filter(|(block, _, _, _)| block.header.direct_parents().contains(&hash))
+            .map(|(block, _, _, _)| block.header.hash)
+            .collect::<Vec<_>>();
+
+        let mut select = None;
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(i18n("Block")).strong());
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("✖").clicked() {
+                    self.selected = None;
+                }
+            });
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_source("block_dag_inspector")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                ui.label(i18n("Hash:"));
+                ui.add(Label::new(RichText::new(hash.to_string()).monospace()).wrap_mode(TextWrapMode::Wrap));
+                ui.add_space(4.);
+
+                Grid::new("block_dag_inspector_header")
+                    .num_columns(2)
+                    .spacing([16.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label(i18n("Timestamp:"));
+                        let timestamp = DateTime::<chrono::Utc>::from_timestamp_millis(header.timestamp as i64)
+                            .map(|timestamp| timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+                            .unwrap_or_default();
+                        ui.label(timestamp);
+                        ui.end_row();
+
+                        ui.label(i18n("DAA Score:"));
+                        ui.label(header.daa_score.separated_string());
+                        ui.end_row();
+
+                        ui.label(i18n("Blue Score:"));
+                        ui.label(header.blue_score.separated_string());
+                        ui.end_row();
+
+                        ui.label(i18n("VSPC:"));
+                        ui.label(match vspc {
+                            Some(true) => i18n("Yes"),
+                            Some(false) => i18n("No"),
+                            None => i18n("Unknown"),
+                        });
+                        ui.end_row();
+
+                        ui.label(i18n("GHOSTDAG:"));
+                        match block_colors.get(&hash) {
+                            Some(BlockColor::Blue) => ui.colored_label(theme_color().block_dag_blue_block_fill_color, i18n("Blue")),
+                            Some(BlockColor::Red) => ui.colored_label(theme_color().block_dag_red_block_fill_color, i18n("Red")),
+                            None => ui.label(i18n("Not merged")),
+                        };
+                        ui.end_row();
+
+                        ui.label(i18n("Transactions:"));
+                        ui.label(block.transactions.len().separated_string());
+                        ui.end_row();
+                    });
+
+                ui.add_space(4.);
+
+                for (caption, hashes) in [(i18n("Parents"), parents), (i18n("Children"), children.as_slice())] {
+                    CollapsingHeader::new(format!("{caption} ({})", hashes.len()))
+                        .id_source(caption)
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for hash in hashes {
+                                let text = RichText::new(format_partial_string(hash.to_string(), Some(12))).monospace();
+                                if blocks.iter().any(|(block, _, _, _)| block.header.hash == *hash) {
+                                    if ui.link(text).on_hover_text(hash.to_string()).clicked() {
+                                        select = Some(*hash);
+                                    }
+                                } else {
+                                    ui.label(text).on_hover_text(hash.to_string());
+                                }
+                            }
+                        });
+                }
+
+                ui.add_space(8.);
+                if ui.button(i18n("Open in Block Lookup")).clicked() {
+                    runtime().try_send(Events::Lookup(LookupTarget::Block(hash))).ok();
+                }
+            });
+
+        if let Some(hash) = select {
+            self.selected = blocks.iter()
+                .find(|(block, _, _, _)| block.header.hash == hash)
+                .map(|(block, _, _, _)| block.clone());
+        }
+    }
+
+}
+
+impl ModuleT for BlockDag {
+
+    fn style(&self) -> ModuleStyle {
+        ModuleStyle::Default
+    }
+
+    fn status_bar(&self, core: &mut Core, ui : &mut Ui) {
+        ui.separator();
+        if let Some(status) = self.runtime.block_dag_monitor_service().replay_status() {
+            ui.label(RichText::new(format!("{} {} / {}", i18n("Replaying recorded session:"), status.events, status.total)).color(theme_color().warning_color));
+        } else if !core.state().is_connected() {
+            ui.label(RichText::new(i18n("You must be connected to a node...")).color(theme_color().error_color));
+        } else if !core.state().is_synced() {
+            ui.label(RichText::new(i18n("Please wait for the node to sync...")).color(theme_color().warning_color));
+        } else {
+            ui.label(i18n("Double click on the graph to re-center, click on a block to inspect it..."));
+        }
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        _ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+        let theme_color = theme_color();
+
+        let y_dist = self.settings.y_dist;
+        let noise = self.settings.noise;
+        let vspc_center = self.settings.center_vspc;
+
+        if core.settings.node.network != self.network {
+            self.network = core.settings.node.network;
+            self.load_preset(&self.network.into());
+            runtime().block_dag_monitor_service().update_settings(self.settings.clone());
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading(i18n("Block DAG"));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                PopupPanel::new(PopupPanel::id(ui,"block_dag_settings"),|ui|{ ui.add(Label::new(format!("{} ⏷", i18n("Settings"))).sense(Sense::click())) }, |ui, _| {
+
+                    CollapsingHeader::new(i18n("Dimensions"))
+                        .open(Some(true))
+                        // .default_open(true)
+                        .show(ui, |ui| {
+                            ui.space();
+
+                            ui.space();
+                            ui.add(
+                                Slider::new(&mut self.daa_range, 1.0..=self.settings.graph_length_daa as f64)
+                                    .clamp_to_range(true)
+                                    .logarithmic(true)
+                                    .text(i18n("DAA Range"))
+                            );
+                            ui.space();
+                            ui.add(
+                                Slider::new(&mut self.daa_offset, 1.0..=50.0)
+                                    .clamp_to_range(true)
+                                    .text(i18n("DAA Offset"))
+                                    // .step_by(1.0)
+                            );
+                            ui.space();
+                            ui.add(
+                                Slider::new(&mut self.settings.y_dist, 1.0..=100.0)
+                                    .clamp_to_range(true)
+                                    .text(i18n("Spread"))
+                            );
+                            ui.space();
+                            ui.add(
+                                Slider::new(&mut self.settings.noise, 0.0..=10.0)
+                                    .clamp_to_range(true)
+                                    .text(i18n("Noise"))
+                            );
+                            ui.space();
+                            ui.add(
+                                Slider::new(&mut self.block_scale, 0.1..=2.5)
+                                    .clamp_to_range(true)
+                                    .logarithmic(true)
+                                    .text(i18n("Block Scale"))
+                            );
+                            ui.space();
+                        });
+
+                    CollapsingHeader::new(i18n("Parents"))
+                        .open(Some(true))
+                        // .default_open(true)
+                        .show(ui, |ui| {
+
+                            ui.space();
+                            
+                            ui.add(
+                                Slider::new(&mut self.parent_levels, 1..=50)
+                                    .clamp_to_range(true)
+                                    .text(i18n("Levels"))
+                                    .step_by(1.0)
+                            );
+                            ui.space();
+                            ui.add(
+                                Slider::new(&mut self.parent_threshold, 200..=1000)
+                                    .clamp_to_range(true)
+                                    .logarithmic(true)
+                                    .text(i18n("Threshold"))
+                            );
+                            ui.space();
+                        });
+
+                    ui.separator();
+                    ui.space();
+                    ui.horizontal_wrapped(|ui| {
+                        ui.checkbox(&mut self.settings.center_vspc, i18n("Center VSPC"));
+                        ui.space();
+                        ui.checkbox(&mut self.settings.show_vspc, i18n("Show VSPC"));
+                        ui.space();
+                        // ui.checkbox(&mut self.settings.reset_vspc, i18n("Reset VSPC"));
+                        // ui.space();
+                        ui.checkbox(&mut self.settings.show_grid, i18n("Show Grid"));
+                        ui.space();
+                        ui.checkbox(&mut self.settings.show_daa, i18n("Show DAA"));
+                        ui.space();
+                        ui.checkbox(&mut self.settings.show_ghostdag, i18n("GHOSTDAG Colors"));
+                        ui.space();
+                        ui.checkbox(&mut self.bezier, i18n("Bezier Curves"));
+                        ui.space();
+
+                        if core.settings.node.node_kind.is_local() {
+                            let background_flag = self.background.load(Ordering::SeqCst);
+                            let mut background_state = background_flag;
+                            ui.checkbox(&mut background_state, i18n("Track in the background"));
+                            if background_state != background_flag {
+                                self.background.store(background_state, Ordering::SeqCst);
+                            }
+                        }
+                    });
+                })
+                .with_min_width(240.)
+                .with_caption(i18n("Settings"))
+                .with_close_button(true)
+                .build(ui);
+
+                ui.separator();
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let response = ui
+                        .add(Label::new(RichText::new(format!("{} ⏷", i18n("Replay")))).sense(Sense::click()));
+                    PopupPanel::new(
+                        PopupPanel::id(ui,"block_dag_replay_popup"),
+                        |_ui| response,
+                        |ui, _| {
+                            self.render_replay(ui);
+                    })
+                    .with_min_width(240.0)
+                    .with_caption(i18n("Replay"))
+                    .with_close_button(true)
+                    .build(ui);
+
+                    let response = ui
+                        .add(Label::new(RichText::new(format!("{} ⏷", i18n("Export")))).sense(Sense::click()));
+                    PopupPanel::new(
+                        PopupPanel::id(ui,"block_dag_export_popup"),
+                        |_ui| response,
+                        |ui, close| {
+                            self.render_export(ui, close);
+                    })
+                    .with_min_width(240.0)
+                    .with_caption(i18n("Export"))
+                    .with_close_button(true)
+                    .build(ui);
+
+                    let recording = self.runtime.block_dag_monitor_service().recorded_events();
+                    let caption = if let Some(events) = recording {
+                        RichText::new(format!("⏺ {} ({events})", i18n("Stop Recording"))).color(theme_color().error_color)
+                    } else {
+                        RichText::new(format!("⏺ {}", i18n("Record")))
+                    };
+                    if ui.button(caption).clicked() {
+                        self.toggle_recording();
+                    }
+
+                    ui.separator();
+                }
+
+                if self.is_paused() {
+                    if ui.button(format!("{PLAY} {}", i18n("Resume"))).clicked() {
+                        self.resume();
+                    }
+                } else if ui.button(format!("{PAUSE} {}", i18n("Pause"))).clicked() {
+                    self.pause();
+                }
+
+                ui.separator();
+
+                let response = ui
+                        .add(Label::new(RichText::new(format!("{} ⏷", i18n("Presets")))).sense(Sense::click()));
+                PopupPanel::new(
+                    PopupPanel::id(ui,"network_selector_popup"),
+                    |_ui| response,
+                    |ui, close| {
+                        set_menu_style(ui.style_mut());
+                        for preset in PRESETS {
+                            if ui.button(i18n(preset.name)).clicked() {
+                                self.load_preset(preset);
+                                *close = true;
+                            }
+                        }
+                })
+                .with_min_width(100.0)
+                .build(ui);
+
+            });
+        });
+
+        if self.settings.show_ghostdag {
+            let red_blocks = self.runtime.block_dag_monitor_service().red_blocks_per_minute();
+            ui.horizontal(|ui| {
+                ui.colored_label(theme_color.block_dag_blue_block_fill_color, "■");
+                ui.label(i18n("Blue"));
+                ui.colored_label(theme_color.block_dag_red_block_fill_color, "■");
+                ui.label(i18n("Red"));
+                ui.colored_label(theme_color.block_dag_block_fill_color, "■");
+                ui.label(i18n("Not merged"));
+                ui.separator();
+                ui.label(format!("{} {red_blocks}", i18n("Red blocks per minute:")));
+            });
+        }
+
+        ui.separator();
+
+        if y_dist != self.settings.y_dist || noise != self.settings.noise || vspc_center != self.settings.center_vspc {
+            runtime().block_dag_monitor_service().update_settings(self.settings.clone());
+        }
+
+        let mut reset_plot = false;
+        // a replayed session drives the view with its own DAA score
+        let replay_status = self.runtime.block_dag_monitor_service().replay_status();
+        if replay_status.is_some() != self.replaying {
+            self.replaying = replay_status.is_some();
+            self.reset_state();
+        }
+        let current_daa_score = match replay_status {
+            Some(status) => status.daa_score.unwrap_or_default(),
+            None => core.state().current_daa_score().unwrap_or_default(),
+        };
+        if self.last_daa_score != current_daa_score {
+
+            if !self.running {
+                self.running = true;
+                reset_plot = true;
+                self.daa_cursor = current_daa_score as f64 - 1.0;
+            }
+
+            self.last_daa_score = current_daa_score;
+        }
+
+        // let mut reset_plot = false;
+        // let current_daa_score = core.state().current_daa_score().unwrap_or_default();
+        // if self.last_daa_score != current_daa_score || current_daa_score==0 {
+        //    if !self.running || current_daa_score==0{
+        //         if current_daa_score > 0{
+        //             self.running = true;
+        //         }else{
+        //             self.running = false;
+        //         }
+        //         reset_plot = true;
+        //         self.daa_cursor = current_daa_score as f64 - 1.0;
+        //     }
+
+        //     self.last_daa_score = current_daa_score;
+        // }
+
+        let delta = 0.025;
+        // the view does not advance while paused
+        let daa_diff = if self.is_paused() { 0.0 } else { current_daa_score as f64 - self.daa_cursor };
+        let step = daa_diff * delta;
+        let step = (1.0 + step).powf(2.0) - 1.0;
+        self.daa_cursor += step;
+        
+        let daa_range = self.plot_bounds.max()[0] - self.plot_bounds.min()[0];
+        let daa_margin = daa_range.min(128.0).max(32.0);
+        let daa_min = (self.plot_bounds.min()[0] - daa_margin).max(0.0) as u64;
+        let daa_max = (self.plot_bounds.max()[0] + daa_margin).max(0.0) as u64;
+        
+        let (blocks, new_blocks, block_colors) = if let Some((blocks, new_blocks, block_colors)) = self.frozen.as_ref() {
+            let blocks = blocks.iter().filter(|(block, _, _, _)| {
+                block.header.daa_score > daa_min && block.header.daa_score < daa_max
+            }).cloned().collect::<Vec<_>>();
+            (blocks, new_blocks.clone(), block_colors.clone())
+        } else if let Ok(mut daa_buckets) = self.runtime.block_dag_monitor_service().chain.lock() {
+            let blocks = daa_buckets.iter_mut().filter_map(|(daa_score,bucket)| {
+                (*daa_score > daa_min && *daa_score < daa_max).then_some(bucket)
+            }).flat_map(DaaBucket::render).collect::<Vec<_>>();
+            let block_dag_monitor_service = self.runtime.block_dag_monitor_service();
+            let new_blocks = block_dag_monitor_service.new_blocks().clone();
+            let block_colors = block_dag_monitor_service.block_colors();
+            (blocks, new_blocks, block_colors)
+        } else {
+            return;
+        };
+
+        if self.selected.is_some() {
+            SidePanel::right("block_dag_inspector")
+                .default_width(320.)
+                .resizable(true)
+                .show_separator_line(true)
+                .show_inside(ui, |ui| {
+                    self.render_inspector(ui, &blocks, &block_colors);
+                });
+        }
+
+        let graph_width = ui.available_width();
+        let graph_height = ui.available_height();
+        let default_daa_max = self.daa_cursor + self.daa_offset;
+        let default_daa_min = default_daa_max - self.daa_range;
+        let default_daa_range = default_daa_max - default_daa_min;
+        let pixels_per_daa = graph_width as f64 / default_daa_range;
+
+        let mut plot = Plot::new("block_dag")
+            .width(graph_width)
+            .height(graph_height)
+            .include_x(default_daa_max)
+            .include_x(default_daa_min)
+            .include_y(15.)
+            .include_y(-15.)
+            .data_aspect(0.2)
+            .y_axis_min_width(0.0)
+            .show_axes([self.settings.show_daa, false])
+            .show_grid(self.settings.show_grid)
+            .allow_drag([true, true])
+            .allow_scroll(true)
+            .allow_double_click_reset(true)
+            .x_axis_formatter(move |x, _range| {
+                format!("{} DAA", x.value.trunc().separated_string())
+            })
+            .x_grid_spacer(
+                uniform_grid_spacer(move |input| {
+                    let (start,stop) = input.bounds;
+                    let d = (stop - start) / 5.;
+                    let mut v = 10.;
+                    while v < d {
+                        v *= 2.;
+                    }
+                    [v,v*10.,v*100.]
+                })
+            )
+            .label_formatter(move |name, point| {
+                let PlotPoint { x, y: _ } = point;
+                format!("{name}\n{} DAA", x.trunc().separated_string())
+            })                        
+            ;
+
+        // kick it into gear when starting up
+        if reset_plot {
+            plot = plot.auto_bounds([true, true].into());
+            plot = plot.reset();
+        } else {
+            plot = plot.auto_bounds([false, false].into());
+        }
+
+        let mut graph_settled = true;
+        let mut lines_parent = Vec::new();
+        let mut lines_vspc = Vec::new();
+        let mut lines_highlight = Vec::new();
+
+        // edges of the hovered (or selected) block are highlighted
+        let highlighted = self.hovered.or_else(|| self.selected.as_ref().map(|block| block.header.hash));
+        let selected = self.selected.as_ref().map(|block| block.header.hash);
+
+        // let separators = if let Ok(separators) = self.runtime.block_dag_monitor_service().separators.lock() {
+        //     separators.iter().filter_map(|daa_score| {
+        //         (*daa_score > daa_min || *daa_score < daa_max).then_some(*daa_score)
+        //     }).collect::<Vec<_>>()
+        // } else {
+        //     return;
+        // };
+
+        // vector copy of the viewport (without hover and selection) for export
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut scene = self.export_request.is_some().then(|| DagScene {
+            bounds : self.plot_bounds,
+            width : graph_width,
+            height : graph_height,
+            background : ui.visuals().panel_fill,
+            show_grid : self.settings.show_grid,
+            show_daa : self.settings.show_daa,
+            grid_color : theme_color.block_dag_separator_color,
+            parent_color : theme_color.block_dag_parent_connect_color,
+            vspc_color : theme_color.block_dag_vspc_connect_color,
+            stroke_color : theme_color.block_dag_block_stroke_color,
+            parent_edges : vec![],
+            vspc_edges : vec![],
+            blocks : vec![],
+        });
+
+        let parent_levels = self.parent_levels.max(1);
+        let block_map : AHashMap<KaspaHash,(PlotPoint,bool)> = blocks.clone().into_iter().map(|(block, plot_point,vspc, _)|(block.header.hash,(plot_point,vspc))).collect();
+        let polygons = blocks.iter().map(|(block, point, current_vspc, block_settled)| {
+            if !block_settled {
+                graph_settled = false;
+            }
+
+            let PlotPoint { x, y } = *point;
+
+            for (level,parent_level) in block.header.parents_by_level.iter().enumerate() {
+                if level >= parent_levels {
+                    break;
+                }
+                // for parent_hash in block.header.parents_by_level.iter().flatten() {
+                for parent_hash in parent_level.iter() {
+                    if let Some(parent_point) = block_map.get(parent_hash) {
+                        let (PlotPoint { x: parent_x, y: parent_y }, parent_vspc) = *parent_point;
+                        let x_len = (x - parent_x).abs();
+                        
+                        if x_len > self.parent_threshold as f64 {
+                            continue;
+                        }
+
+                        let points = if self.bezier {
+                            // x dist is sufficient... (let's save some cycles)
+                            let line_steps = (x_len * pixels_per_daa * 0.3) as usize;
+                            bezier(x,y,parent_x,parent_y,line_steps,BEZIER_OFFSET_FACTOR) 
+                        } else {
+                            [
+                                [x,y],
+                                [parent_x, parent_y],
+                            ].into_iter().map(|pt|pt.into()).collect::<Vec<_>>()
+                        };
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if let Some(scene) = scene.as_mut() {
+                            let edge = SceneEdge {
+                                from : PlotPoint::new(x, y),
+                                to : PlotPoint::new(parent_x, parent_y),
+                                bezier : self.bezier.then_some(BEZIER_OFFSET_FACTOR),
+                            };
+                            if self.settings.show_vspc && level == 0 && *current_vspc && parent_vspc {
+                                scene.vspc_edges.push(edge);
+                            } else {
+                                scene.parent_edges.push(edge);
+                            }
+                        }
+
+                        if level == 0 && highlighted == Some(block.header.hash) {
+                            // parent edge of the highlighted block
+                            lines_highlight.push(Line::new(PlotPoints::Owned(points)).color(theme_color.kaspa_color).style(LineStyle::Solid).width(2.5));
+                        } else if level == 0 && highlighted == Some(*parent_hash) {
+                            // child edge of the highlighted block
+                            lines_highlight.push(Line::new(PlotPoints::Owned(points)).color(theme_color.hyperlink_color).style(LineStyle::Solid).width(2.5));
+                        } else if self.settings.show_vspc && level == 0 && *current_vspc && parent_vspc {
+                            lines_vspc.push(Line::new(PlotPoints::Owned(points)).color(theme_color.block_dag_vspc_connect_color).style(LineStyle::Solid).width(3.0));
+                        } else {
+                            lines_parent.push(Line::new(PlotPoints::Owned(points)).color(theme_color.block_dag_parent_connect_color).style(LineStyle::Solid));
+                        }
+                    }
+                }
+            }
+
+            let d = 1.5 * self.block_scale;
+            let outline = [
+                [x+d*0.2, y+d],
+                [x-d*0.2, y+d],
+                [x-d*0.2, y-d],
+                [x+d*0.2, y-d],
+            ];
+            let points: PlotPoints = outline.to_vec().into();
+        
+            let block_color = if self.settings.show_ghostdag { block_colors.get(&block.header.hash) } else { None };
+            let fill_color = if let Some(block_color) = block_color {
+                match block_color {
+                    BlockColor::Blue => theme_color.block_dag_blue_block_fill_color,
+                    BlockColor::Red => theme_color.block_dag_red_block_fill_color,
+                }
+            } else if new_blocks.contains(&block.header.hash) {
+                theme_color.block_dag_new_block_fill_color
+            } else {
+                theme_color.block_dag_block_fill_color
+            };
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(scene) = scene.as_mut() {
+                scene.blocks.push(SceneBlock { outline : outline.to_vec(), fill : fill_color });
+            }
+
+            let stroke = if selected == Some(block.header.hash) {
+                Stroke::new(3.0, theme_color.kaspa_color)
+            } else {
+                Stroke::new(1.0, theme_color.block_dag_block_stroke_color)
+            };
+
+            Polygon::new(points)
+                .name(block.header.hash.to_string())
+                .fill_color(fill_color)
+                .stroke(stroke)
+                .style(LineStyle::Solid)
+
+            
+        }).collect::<Vec<_>>();
+
+        // let lines_separators = separators.iter().map(|daa_score| {
+        //     let x = *daa_score as f64;
+        //     let points: PlotPoints = [
+        //         [x, 0.0 - y_dist],
+        //         [x, 0.0 + y_dist],
+        //     ].to_vec().into();
+        //     Line::new(points).color(theme_color.block_dag_separator_color).style(LineStyle::Dotted { spacing: 0.75 })
+        // }).collect::<Vec<_>>();
+
+        let plot_response = plot.show(ui, |plot_ui| {
+            // lines_separators.into_iter().for_each(|line| {
+            //     plot_ui.line(line);
+            // });
+            lines_parent.into_iter().for_each(|line| {
+                plot_ui.line(line);
+            });
+            lines_vspc.into_iter().for_each(|line| {
+                plot_ui.line(line);
+            });
+            lines_highlight.into_iter().for_each(|line| {
+                plot_ui.line(line);
+            });
+            polygons.into_iter().for_each(|polygon| {
+                plot_ui.polygon(polygon);
+            });
+        });
+
+        if daa_diff > 0.001 || !graph_settled {
+            runtime().request_repaint();
+        } 
+
+        let hovered = plot_response.response.hover_pos().and_then(|pointer| {
+            let PlotPoint { x, y } = plot_response.transform.value_from_position(pointer);
+            self.block_at(&blocks, x, y).map(|(block, _, _, _)| block.header.hash)
+        });
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            runtime().request_repaint();
+        }
+
+        // select the clicked block for inspection
+        if plot_response.response.clicked() {
+            if let Some(pointer) = plot_response.response.interact_pointer_pos() {
+                let PlotPoint { x, y } = plot_response.transform.value_from_position(pointer);
+                if let Some((block, _, _, _)) = self.block_at(&blocks, x, y) {
+                    self.selected = Some(block.clone());
+                }
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let (Some(options), Some(scene)) = (self.export_request.take(), scene) {
+            self.export(scene, options);
+        }
+
+        self.plot_bounds = *plot_response.transform.bounds();
+        self.last_repaint = Instant::now();
+
+    }
+
+    fn activate(&mut self, core: &mut Core) {
+        let block_dag_monitor_service = crate::runtime::runtime().block_dag_monitor_service().clone();
+        block_dag_monitor_service.enable(core.state().current_daa_score().map(|score|score - 2));
+        block_dag_monitor_service.activate(true);
+    }
+
+    fn deactivate(&mut self, core: &mut Core) {
+        if !self.background() {
+            let block_dag_monitor_service = crate::runtime::runtime().block_dag_monitor_service().clone();
+            self.running = false;
+            block_dag_monitor_service.disable(core.state().current_daa_score());
+            block_dag_monitor_service.activate(false);
+        }
+    }
+
+    fn disconnect(&mut self, _core: &mut Core) {
+        self.reset_state();
+    }
+
+    fn show(&mut self, _core: &mut Core) {
+        self.reset_state();
+    }
+
+}
+
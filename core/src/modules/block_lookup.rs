@@ -0,0 +1,681 @@
+use crate::imports::*;
+use chrono::{DateTime, Local};
+use kaspa_rpc_core::{RpcBlock, RpcHash, RpcTransaction, RpcTransactionId};
+use kaspa_txscript::standard::extract_script_pub_key_address;
+
+/// Block or transaction to be looked up by the [`BlockLookup`] module.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LookupTarget {
+    Block(RpcHash),
+    Transaction(RpcTransactionId),
+    /// Transaction accepted by the chain block at the given DAA score
+    AcceptedTransaction(RpcTransactionId, u64),
+    /// Hash entered by the user (block hash or transaction id)
+    Hash(RpcHash),
+}
+
+impl LookupTarget {
+    fn hash(&self) -> RpcHash {
+        match self {
+            LookupTarget::Block(hash)
+            | LookupTarget::Transaction(hash)
+            | LookupTarget::AcceptedTransaction(hash, _)
+            | LookupTarget::Hash(hash) => *hash,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum LookupResult {
+    Block(Arc<RpcBlock>),
+    Transaction {
+        transaction: Arc<RpcTransaction>,
+        block_hash: Option<RpcHash>,
+        fee: Option<u64>,
+    },
+}
+
+impl LookupResult {
+    fn target(&self) -> LookupTarget {
+        match self {
+            LookupResult::Block(block) => LookupTarget::Block(block.header.hash),
+            LookupResult::Transaction { transaction, .. } => {
+                LookupTarget::Transaction(transaction_id(transaction).unwrap_or_default())
+            }
+        }
+    }
+}
+
+fn transaction_id(transaction: &RpcTransaction) -> Option<RpcTransactionId> {
+    transaction
+        .verbose_data
+        .as_ref()
+        .map(|data| data.transaction_id)
+}
+
+/// Locate a transaction through the chain block that accepted it. The
+/// virtual chain is requested starting from `start_hash`, a block that
+/// precedes the accepting block, the accepting block is identified from
+/// the accepted transaction ids and the transaction is then searched for
+/// in its mergeset.
+async fn find_accepted_transaction(
+    rpc_api: &Arc<DynRpcApi>,
+    id: RpcTransactionId,
+    start_hash: RpcHash,
+) -> Result<Option<(RpcTransaction, RpcHash)>> {
+    let chain = rpc_api
+        .get_virtual_chain_from_block(start_hash, true)
+        .await?;
+    let Some(accepting_block_hash) = chain
+        .accepted_transaction_ids
+        .iter()
+        .find(|accepted| accepted.accepted_transaction_ids.contains(&id))
+        .map(|accepted| accepted.accepting_block_hash)
+    else {
+        return Ok(None);
+    };
+
+    let accepting_block = rpc_api.get_block(accepting_block_hash, false).await?;
+    let Some(verbose_data) = accepting_block.verbose_data else {
+        return Ok(None);
+    };
+    for hash in verbose_data
+        .merge_set_blues_hashes
+        .iter()
+        .chain(verbose_data.merge_set_reds_hashes.iter())
+    {
+        let block = rpc_api.get_block(*hash, true).await?;
+        if let Some(transaction) = block
+            .transactions
+            .into_iter()
+            .find(|transaction| transaction_id(transaction) == Some(id))
+        {
+            return Ok(Some((transaction, *hash)));
+        }
+    }
+
+    Ok(None)
+}
+
+enum Action {
+    Navigate(LookupTarget),
+    Transaction(Arc<RpcTransaction>, RpcHash),
+}
+
+pub struct BlockLookup {
+    #[allow(dead_code)]
+    runtime: Runtime,
+    search: String,
+    current: Option<LookupResult>,
+    back: Vec<LookupResult>,
+    error: Option<String>,
+}
+
+impl BlockLookup {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            search: String::default(),
+            current: None,
+            back: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Look up the block or transaction identified by `target`.
+    pub fn lookup(&mut self, target: LookupTarget) {
+        self.search = target.hash().to_string();
+        self.error = None;
+
+        let Some(rpc_api) = self.runtime.kaspa_service().rpc_api() else {
+            self.error = Some(i18n("Not connected").to_string());
+            return;
+        };
+
+        let lookup_result = Payload::<Result<LookupResult>>::new("block_lookup_result");
+        if lookup_result.is_pending() {
+            return;
+        }
+
+        let recent_block = match target {
+            LookupTarget::Block(_) => None,
+            LookupTarget::Transaction(id)
+            | LookupTarget::AcceptedTransaction(id, _)
+            | LookupTarget::Hash(id) => self.find_recent_block(&id),
+        };
+
+        let chain_start = match target {
+            LookupTarget::AcceptedTransaction(_, daa_score) => self.find_chain_start(daa_score),
+            _ => None,
+        };
+
+        spawn_with_result(&lookup_result, async move {
+            if !matches!(
+                target,
+                LookupTarget::Transaction(_) | LookupTarget::AcceptedTransaction(..)
+            ) {
+                match rpc_api.get_block(target.hash(), true).await {
+                    Ok(block) => return Ok(LookupResult::Block(Arc::new(block))),
+                    Err(err) if matches!(target, LookupTarget::Block(_)) => return Err(err.into()),
+                    Err(_) => {}
+                }
+            }
+
+            let id = target.hash();
+            if let Ok(entry) = rpc_api.get_mempool_entry(id, true, false).await {
+                return Ok(LookupResult::Transaction {
+                    transaction: Arc::new(entry.transaction),
+                    block_hash: None,
+                    fee: Some(entry.fee),
+                });
+            }
+
+            if let Some(block) = recent_block {
+                if let Some(transaction) = block
+                    .transactions
+                    .iter()
+                    .find(|transaction| transaction_id(transaction) == Some(id))
+                {
+                    return Ok(LookupResult::Transaction {
+                        transaction: Arc::new(transaction.clone()),
+                        block_hash: Some(block.header.hash),
+                        fee: None,
+                    });
+                }
+            }
+
+            if let LookupTarget::AcceptedTransaction(..) = target {
+                let Some(start_hash) = chain_start else {
+                    return Err(Error::custom(i18n(
+                        "Not available. The transaction was accepted before the blocks observed by the Block DAG monitor; please look up the block containing the transaction.",
+                    )));
+                };
+
+                if let Some((transaction, block_hash)) =
+                    find_accepted_transaction(&rpc_api, id, start_hash).await?
+                {
+                    return Ok(LookupResult::Transaction {
+                        transaction: Arc::new(transaction),
+                        block_hash: Some(block_hash),
+                        fee: None,
+                    });
+                }
+            }
+
+            Err(Error::custom(i18n(
+                "Not found. Transactions can only be located while in the mempool or in recently observed blocks; please look up the block containing the transaction.",
+            )))
+        });
+    }
+
+    /// Search blocks received by the Block DAG monitor for a transaction.
+    #[cfg(not(feature = "lean"))]
+    fn find_recent_block(&self, id: &RpcTransactionId) -> Option<Arc<RpcBlock>> {
+        let chain = self.runtime.block_dag_monitor_service().chain.lock().ok()?;
+        chain
+            .values()
+            .flat_map(|bucket| bucket.blocks.iter())
+            .find(|block| {
+                block
+                    .data
+                    .transactions
+                    .iter()
+                    .any(|transaction| transaction_id(transaction).as_ref() == Some(id))
+            })
+            .map(|block| block.data.clone())
+    }
+
+    #[cfg(feature = "lean")]
+    fn find_recent_block(&self, _id: &RpcTransactionId) -> Option<Arc<RpcBlock>> {
+        None
+    }
+
+    /// Find the most recent block received by the Block DAG monitor
+    /// that precedes the chain block accepted at `daa_score`.
+    #[cfg(not(feature = "lean"))]
+    fn find_chain_start(&self, daa_score: u64) -> Option<RpcHash> {
+        let chain = self.runtime.block_dag_monitor_service().chain.lock().ok()?;
+        chain
+            .iter()
+            .filter(|(bucket_daa_score, _)| **bucket_daa_score < daa_score)
+            .max_by_key(|(bucket_daa_score, _)| **bucket_daa_score)
+            .and_then(|(_, bucket)| bucket.blocks.first())
+            .map(|block| block.data.header.hash)
+    }
+
+    #[cfg(feature = "lean")]
+    fn find_chain_start(&self, _daa_score: u64) -> Option<RpcHash> {
+        None
+    }
+
+    fn navigate(&mut self, target: LookupTarget) {
+        if let Some(current) = self.current.take() {
+            self.back.push(current);
+        }
+        self.lookup(target);
+    }
+
+    fn open_transaction(&mut self, transaction: Arc<RpcTransaction>, block_hash: RpcHash) {
+        self.search = transaction_id(&transaction)
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+        if let Some(current) = self.current.take() {
+            self.back.push(current);
+        }
+        self.current = Some(LookupResult::Transaction {
+            transaction,
+            block_hash: Some(block_hash),
+            fee: None,
+        });
+    }
+}
+
+impl ModuleT for BlockLookup {
+    fn style(&self) -> ModuleStyle {
+        ModuleStyle::Default
+    }
+
+    fn network_change(&mut self, _core: &mut Core, _network: Network) {
+        self.current = None;
+        self.back.clear();
+        self.error = None;
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        _ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+        let network = core.network();
+        let network_type = if let Some(network_id) = core.state().network_id() {
+            network_id.network_type()
+        } else {
+            network.into()
+        };
+
+        let lookup_result = Payload::<Result<LookupResult>>::new("block_lookup_result");
+        if let Some(result) = lookup_result.take() {
+            match result {
+                Ok(result) => {
+                    self.current = Some(result);
+                    self.error = None;
+                }
+                Err(err) => {
+                    self.error = Some(err.to_string());
+                }
+            }
+        }
+
+        ui.heading(i18n("Block & Transaction Lookup"));
+        ui.separator();
+
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.back.is_empty(),
+                    Button::new(egui_phosphor::light::ARROW_LEFT),
+                )
+                .clicked()
+            {
+                self.current = self.back.pop();
+                self.search = self
+                    .current
+                    .as_ref()
+                    .map(|current| current.target().hash().to_string())
+                    .unwrap_or_default();
+                self.error = None;
+            }
+
+            let response = ui.add(
+                TextEdit::singleline(&mut self.search)
+                    .hint_text(i18n("Block hash or transaction id..."))
+                    .desired_width(ui.available_width() - 96.),
+            );
+            let submit = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            if ui
+                .add_enabled(
+                    core.state().is_connected() && !lookup_result.is_pending(),
+                    Button::new(i18n("Lookup")),
+                )
+                .clicked()
+                || submit
+            {
+                match RpcHash::from_str(self.search.trim()) {
+                    Ok(hash) => action = Some(Action::Navigate(LookupTarget::Hash(hash))),
+                    Err(_) => self.error = Some(i18n("Invalid hash").to_string()),
+                }
+            }
+        });
+
+        if lookup_result.is_pending() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(i18n("Loading..."));
+            });
+        }
+
+        if let Some(error) = &self.error {
+            ui.colored_label(theme_color().error_color, error);
+        }
+
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_source("block_lookup")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| match &self.current {
+                Some(LookupResult::Block(block)) => {
+                    render_block(ui, block, &mut action);
+                }
+                Some(LookupResult::Transaction {
+                    transaction,
+                    block_hash,
+                    fee,
+                }) => {
+                    render_transaction(
+                        ui,
+                        transaction,
+                        *block_hash,
+                        *fee,
+                        network,
+                        network_type,
+                        &mut action,
+                    );
+                }
+                None => {}
+            });
+
+        match action {
+            Some(Action::Navigate(target)) => self.navigate(target),
+            Some(Action::Transaction(transaction, block_hash)) => {
+                self.open_transaction(transaction, block_hash)
+            }
+            None => {}
+        }
+    }
+}
+
+fn render_hash_link(
+    ui: &mut Ui,
+    hash: &RpcHash,
+    target: LookupTarget,
+    action: &mut Option<Action>,
+) {
+    if ui
+        .link(RichText::new(hash.to_string()).monospace())
+        .clicked()
+    {
+        *action = Some(Action::Navigate(target));
+    }
+}
+
+fn render_hash_list(ui: &mut Ui, caption: &str, hashes: &[RpcHash], action: &mut Option<Action>) {
+    CollapsingHeader::new(format!("{caption} ({})", hashes.len()))
+        .default_open(hashes.len() <= 16)
+        .show(ui, |ui| {
+            hashes.iter().for_each(|hash| {
+                render_hash_link(ui, hash, LookupTarget::Block(*hash), action);
+            });
+        });
+}
+
+fn render_block(ui: &mut Ui, block: &RpcBlock, action: &mut Option<Action>) {
+    let header = &block.header;
+    let verbose_data = block.verbose_data.as_ref();
+
+    ui.label(RichText::new(i18n("Block")).strong());
+
+    Grid::new("block_lookup_header")
+        .num_columns(2)
+        .spacing([16.0, 4.0])
+        .show(ui, |ui| {
+            ui.label(i18n("Hash:"));
+            ui.label(RichText::new(header.hash.to_string()).monospace());
+            ui.end_row();
+
+            ui.label(i18n("Timestamp:"));
+            let timestamp =
+                DateTime::<chrono::Utc>::from_timestamp((header.timestamp / 1000) as i64, 0)
+                    .map(|timestamp| {
+                        timestamp
+                            .with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_default();
+            ui.label(timestamp);
+            ui.end_row();
+
+            ui.label(i18n("Version:"));
+            ui.label(header.version.to_string());
+            ui.end_row();
+
+            ui.label(i18n("DAA Score:"));
+            ui.label(header.daa_score.separated_string());
+            ui.end_row();
+
+            ui.label(i18n("Blue Score:"));
+            ui.label(header.blue_score.separated_string());
+            ui.end_row();
+
+            ui.label(i18n("Blue Work:"));
+            ui.label(RichText::new(format!("{:x}", header.blue_work)).monospace());
+            ui.end_row();
+
+            ui.label(i18n("Bits:"));
+            ui.label(header.bits.to_string());
+            ui.end_row();
+
+            ui.label(i18n("Nonce:"));
+            ui.label(header.nonce.to_string());
+            ui.end_row();
+
+            if let Some(verbose_data) = verbose_data {
+                ui.label(i18n("Difficulty:"));
+                ui.label(format!("{:.2}", verbose_data.difficulty));
+                ui.end_row();
+
+                ui.label(i18n("Chain Block:"));
+                ui.label(if verbose_data.is_chain_block {
+                    i18n("Yes")
+                } else {
+                    i18n("No")
+                });
+                ui.end_row();
+
+                ui.label(i18n("Selected Parent:"));
+                let selected_parent = verbose_data.selected_parent_hash;
+                render_hash_link(
+                    ui,
+                    &selected_parent,
+                    LookupTarget::Block(selected_parent),
+                    action,
+                );
+                ui.end_row();
+            }
+
+            ui.label(i18n("Pruning Point:"));
+            render_hash_link(
+                ui,
+                &header.pruning_point,
+                LookupTarget::Block(header.pruning_point),
+                action,
+            );
+            ui.end_row();
+
+            ui.label(i18n("Merkle Root:"));
+            ui.label(RichText::new(header.hash_merkle_root.to_string()).monospace());
+            ui.end_row();
+        });
+
+    ui.add_space(4.);
+
+    let parents = header.parents_by_level.first().cloned().unwrap_or_default();
+    render_hash_list(ui, i18n("Parents"), &parents, action);
+
+    if let Some(verbose_data) = verbose_data {
+        render_hash_list(ui, i18n("Children"), &verbose_data.children_hashes, action);
+        render_hash_list(
+            ui,
+            i18n("Mergeset Blues"),
+            &verbose_data.merge_set_blues_hashes,
+            action,
+        );
+        render_hash_list(
+            ui,
+            i18n("Mergeset Reds"),
+            &verbose_data.merge_set_reds_hashes,
+            action,
+        );
+    }
+
+    CollapsingHeader::new(format!(
+        "{} ({})",
+        i18n("Transactions"),
+        block.transactions.len()
+    ))
+    .default_open(true)
+    .show(ui, |ui| {
+        block.transactions.iter().for_each(|transaction| {
+            let id = transaction_id(transaction)
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| i18n("unknown").to_string());
+            let value = transaction
+                .outputs
+                .iter()
+                .map(|output| output.value)
+                .sum::<u64>();
+            ui.horizontal(|ui| {
+                if ui.link(RichText::new(id).monospace()).clicked() {
+                    *action = Some(Action::Transaction(
+                        Arc::new(transaction.clone()),
+                        header.hash,
+                    ));
+                }
+                ui.label(format!(
+                    "{} {}  •  {} {}",
+                    transaction.inputs.len(),
+                    i18n("inputs"),
+                    transaction.outputs.len(),
+                    i18n("outputs"),
+                ));
+                ui.label(sompi_to_kaspa_string(value));
+            });
+        });
+    });
+}
+
+fn render_transaction(
+    ui: &mut Ui,
+    transaction: &RpcTransaction,
+    block_hash: Option<RpcHash>,
+    fee: Option<u64>,
+    network: Network,
+    network_type: NetworkType,
+    action: &mut Option<Action>,
+) {
+    let prefix: kaspa_addresses::Prefix = network.into();
+    let verbose_data = transaction.verbose_data.as_ref();
+
+    ui.label(RichText::new(i18n("Transaction")).strong());
+
+    Grid::new("block_lookup_transaction")
+        .num_columns(2)
+        .spacing([16.0, 4.0])
+        .show(ui, |ui| {
+            if let Some(verbose_data) = verbose_data {
+                ui.label(i18n("Transaction id:"));
+                ui.label(RichText::new(verbose_data.transaction_id.to_string()).monospace());
+                ui.end_row();
+            }
+
+            ui.label(i18n("Block:"));
+            if let Some(block_hash) = block_hash {
+                render_hash_link(ui, &block_hash, LookupTarget::Block(block_hash), action);
+            } else {
+                ui.label(i18n("Mempool"));
+            }
+            ui.end_row();
+
+            if let Some(fee) = fee {
+                ui.label(i18n("Fee:"));
+                ui.label(sompi_to_kaspa_string_with_suffix(fee, &network_type));
+                ui.end_row();
+            }
+
+            let mass = if transaction.mass > 0 {
+                transaction.mass
+            } else {
+                verbose_data
+                    .map(|data| data.compute_mass)
+                    .unwrap_or_default()
+            };
+            ui.label(i18n("Mass:"));
+            ui.label(mass.separated_string());
+            ui.end_row();
+
+            ui.label(i18n("Version:"));
+            ui.label(transaction.version.to_string());
+            ui.end_row();
+
+            ui.label(i18n("Lock Time:"));
+            ui.label(transaction.lock_time.to_string());
+            ui.end_row();
+
+            ui.label(i18n("Subnetwork:"));
+            ui.label(RichText::new(transaction.subnetwork_id.to_string()).monospace());
+            ui.end_row();
+        });
+
+    ui.add_space(4.);
+
+    CollapsingHeader::new(format!("{} ({})", i18n("Inputs"), transaction.inputs.len()))
+        .default_open(true)
+        .show(ui, |ui| {
+            transaction.inputs.iter().for_each(|input| {
+                let outpoint = &input.previous_outpoint;
+                ui.horizontal(|ui| {
+                    if ui
+                        .link(
+                            RichText::new(format!(
+                                "{}:{}",
+                                outpoint.transaction_id, outpoint.index
+                            ))
+                            .monospace(),
+                        )
+                        .clicked()
+                    {
+                        *action = Some(Action::Navigate(LookupTarget::Transaction(
+                            outpoint.transaction_id,
+                        )));
+                    }
+                });
+            });
+        });
+
+    CollapsingHeader::new(format!(
+        "{} ({})",
+        i18n("Outputs"),
+        transaction.outputs.len()
+    ))
+    .default_open(true)
+    .show(ui, |ui| {
+        transaction.outputs.iter().for_each(|output| {
+            let address = extract_script_pub_key_address(&output.script_public_key, prefix)
+                .map(|address| address.to_string())
+                .unwrap_or_else(|_| i18n("non-standard script").to_string());
+            ui.label(
+                RichText::new(format!(
+                    "{}  {address}",
+                    sompi_to_kaspa_string_with_suffix(output.value, &network_type)
+                ))
+                .monospace(),
+            );
+        });
+    });
+}
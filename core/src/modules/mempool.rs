@@ -0,0 +1,440 @@
+use crate::imports::*;
+use kaspa_rpc_core::{RpcMempoolEntry, RpcTransactionId};
+use kaspa_txscript::standard::extract_script_pub_key_address;
+
+/// Interval between mempool updates when auto-refresh is enabled.
+const MEMPOOL_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+/// Maximum number of entries rendered at once.
+const MAX_DISPLAYED_ENTRIES: usize = 256;
+
+/// Mempool entry with the data derived for display.
+#[derive(Debug, Clone)]
+struct MempoolEntry {
+    id: Option<RpcTransactionId>,
+    fee: u64,
+    mass: u64,
+    is_orphan: bool,
+    inputs: Vec<(RpcTransactionId, u32)>,
+    outputs: Vec<(Option<Address>, u64)>,
+}
+
+impl MempoolEntry {
+    fn new(entry: RpcMempoolEntry, network: Network) -> Self {
+        let prefix: kaspa_addresses::Prefix = network.into();
+        let transaction = entry.transaction;
+        let verbose_data = transaction.verbose_data.as_ref();
+
+        let mass = if transaction.mass > 0 {
+            transaction.mass
+        } else {
+            verbose_data
+                .map(|data| data.compute_mass)
+                .unwrap_or_default()
+        };
+
+        let inputs = transaction
+            .inputs
+            .iter()
+            .map(|input| {
+                (
+                    input.previous_outpoint.transaction_id,
+                    input.previous_outpoint.index,
+                )
+            })
+            .collect();
+
+        let outputs = transaction
+            .outputs
+            .iter()
+            .map(|output| {
+                let address =
+                    extract_script_pub_key_address(&output.script_public_key, prefix).ok();
+                (address, output.value)
+            })
+            .collect();
+
+        Self {
+            id: verbose_data.map(|data| data.transaction_id),
+            fee: entry.fee,
+            mass,
+            is_orphan: entry.is_orphan,
+            inputs,
+            outputs,
+        }
+    }
+
+    /// Fee rate in SOMPI per gram of mass.
+    fn fee_rate(&self) -> f64 {
+        if self.mass == 0 {
+            0.0
+        } else {
+            self.fee as f64 / self.mass as f64
+        }
+    }
+
+    fn matches_address(&self, address: &str) -> bool {
+        self.outputs.iter().any(|(output, _)| {
+            output
+                .as_ref()
+                .map(|output| output.to_string().contains(address))
+                .unwrap_or(false)
+        })
+    }
+
+    fn is_wallet_transaction(&self, wallet: &WalletFilter) -> bool {
+        self.id
+            .map(|id| wallet.transactions.contains(&id))
+            .unwrap_or(false)
+            || self
+                .inputs
+                .iter()
+                .any(|(id, _)| wallet.transactions.contains(id))
+            || self.outputs.iter().any(|(address, _)| {
+                address
+                    .as_ref()
+                    .map(|address| wallet.addresses.contains(address))
+                    .unwrap_or(false)
+            })
+    }
+}
+
+/// Transactions and addresses known to the accounts of the open wallet.
+#[derive(Default)]
+struct WalletFilter {
+    wallet: Option<String>,
+    transactions: std::collections::HashSet<RpcTransactionId>,
+    addresses: std::collections::HashSet<Address>,
+}
+
+impl WalletFilter {
+    fn new(core: &Core) -> Self {
+        let mut filter = Self {
+            wallet: Self::wallet(core),
+            ..Default::default()
+        };
+        if let Some(accounts) = core.account_collection() {
+            accounts.iter().for_each(|account| {
+                let descriptor = account.descriptor();
+                filter
+                    .addresses
+                    .extend(descriptor.receive_address.iter().cloned());
+                filter
+                    .addresses
+                    .extend(descriptor.change_address.iter().cloned());
+                account.transactions().iter().for_each(|transaction| {
+                    filter.transactions.insert(transaction.id());
+                    filter.addresses.extend(transaction.utxo_addresses());
+                });
+            });
+        }
+        filter
+    }
+
+    fn wallet(core: &Core) -> Option<String> {
+        core.wallet_descriptor
+            .as_ref()
+            .map(|descriptor| descriptor.filename.clone())
+    }
+
+    fn is_current(&self, core: &Core) -> bool {
+        self.wallet == Self::wallet(core)
+    }
+}
+
+pub struct Mempool {
+    #[allow(dead_code)]
+    runtime: Runtime,
+    include_orphan_pool: bool,
+    auto_refresh: bool,
+    wallet_only: bool,
+    address_filter: String,
+    entries: Option<Vec<MempoolEntry>>,
+    wallet: Option<WalletFilter>,
+    last_update: Option<Instant>,
+    error: Option<String>,
+}
+
+impl Mempool {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            include_orphan_pool: false,
+            auto_refresh: true,
+            wallet_only: false,
+            address_filter: String::default(),
+            entries: None,
+            wallet: None,
+            last_update: None,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, network: Network) {
+        let Some(rpc_api) = self.runtime.kaspa_service().rpc_api() else {
+            return;
+        };
+
+        let mempool_result = Payload::<Result<Vec<MempoolEntry>>>::new("mempool_entries_result");
+        if mempool_result.is_pending() {
+            return;
+        }
+
+        self.last_update = Some(Instant::now());
+        let include_orphan_pool = self.include_orphan_pool;
+        spawn_with_result(&mempool_result, async move {
+            let mut entries = rpc_api
+                .get_mempool_entries(include_orphan_pool, false)
+                .await?
+                .into_iter()
+                .map(|entry| MempoolEntry::new(entry, network))
+                .collect::<Vec<_>>();
+            entries.sort_by(|a, b| b.fee_rate().total_cmp(&a.fee_rate()));
+            Ok(entries)
+        });
+    }
+}
+
+impl ModuleT for Mempool {
+    fn style(&self) -> ModuleStyle {
+        ModuleStyle::Default
+    }
+
+    fn network_change(&mut self, _core: &mut Core, _network: Network) {
+        self.entries = None;
+        self.last_update = None;
+    }
+
+    fn disconnect(&mut self, _core: &mut Core) {
+        self.entries = None;
+        self.last_update = None;
+    }
+
+    fn reset(&mut self, _core: &mut Core) {
+        self.wallet = None;
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+        let network = core.network();
+        let network_type = if let Some(network_id) = core.state().network_id() {
+            network_id.network_type()
+        } else {
+            network.into()
+        };
+
+        let mempool_result = Payload::<Result<Vec<MempoolEntry>>>::new("mempool_entries_result");
+        if let Some(result) = mempool_result.take() {
+            match result {
+                Ok(entries) => {
+                    self.entries = Some(entries);
+                    // pick up transactions the wallet has received since the last update
+                    self.wallet = None;
+                    self.error = None;
+                }
+                Err(err) => {
+                    self.error = Some(err.to_string());
+                }
+            }
+        }
+
+        ui.heading(i18n("Mempool"));
+        ui.separator();
+
+        if !core.state().is_connected() {
+            ui.label(i18n("Not connected"));
+            return;
+        }
+
+        let mut refresh = false;
+        ui.horizontal(|ui| {
+            refresh |= ui
+                .checkbox(&mut self.include_orphan_pool, i18n("Include orphans"))
+                .changed();
+            ui.checkbox(&mut self.auto_refresh, i18n("Auto-refresh"));
+            ui.checkbox(&mut self.wallet_only, i18n("Wallet transactions only"));
+            if ui
+                .add_enabled(!mempool_result.is_pending(), Button::new(i18n("Refresh")))
+                .clicked()
+            {
+                refresh = true;
+            }
+            if mempool_result.is_pending() {
+                ui.spinner();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(i18n("Address:"));
+            ui.add(
+                TextEdit::singleline(&mut self.address_filter)
+                    .hint_text(i18n("Filter by address..."))
+                    .desired_width(f32::INFINITY),
+            );
+        });
+
+        let expired = self
+            .last_update
+            .map(|last_update| {
+                self.auto_refresh && last_update.elapsed() > MEMPOOL_REFRESH_INTERVAL
+            })
+            .unwrap_or(true);
+
+        if refresh || expired {
+            self.update(network);
+        }
+
+        if self.auto_refresh {
+            ctx.request_repaint_after(MEMPOOL_REFRESH_INTERVAL);
+        }
+
+        if let Some(error) = &self.error {
+            ui.colored_label(theme_color().error_color, error);
+        }
+
+        ui.separator();
+
+        let Some(entries) = self.entries.as_ref() else {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(i18n("Updating..."));
+            });
+            return;
+        };
+
+        if !self
+            .wallet
+            .as_ref()
+            .map(|wallet| wallet.is_current(core))
+            .unwrap_or(false)
+        {
+            self.wallet = Some(WalletFilter::new(core));
+        }
+        let wallet = self.wallet.as_ref().unwrap();
+        let address_filter = self.address_filter.trim();
+        let entries = entries
+            .iter()
+            .map(|entry| (entry, entry.is_wallet_transaction(wallet)))
+            .filter(|(entry, is_wallet)| {
+                (!self.wallet_only || *is_wallet)
+                    && (address_filter.is_empty() || entry.matches_address(address_filter))
+            })
+            .collect::<Vec<_>>();
+
+        let total_fees = entries.iter().map(|(entry, _)| entry.fee).sum::<u64>();
+        ui.label(format!(
+            "{} {}  •  {} {}",
+            entries.len().separated_string(),
+            i18n("transactions"),
+            sompi_to_kaspa_string_with_suffix(total_fees, &network_type),
+            i18n("in fees"),
+        ));
+
+        if entries.len() > MAX_DISPLAYED_ENTRIES {
+            ui.label(format!(
+                "{} {}",
+                i18n("Showing entries with the highest fee rate:"),
+                MAX_DISPLAYED_ENTRIES
+            ));
+        }
+
+        egui::ScrollArea::vertical()
+            .id_source("mempool_entries")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                entries
+                    .iter()
+                    .take(MAX_DISPLAYED_ENTRIES)
+                    .for_each(|(entry, is_wallet)| {
+                        render_entry(ui, entry, *is_wallet, wallet, network_type);
+                    });
+            });
+    }
+}
+
+fn render_entry(
+    ui: &mut Ui,
+    entry: &MempoolEntry,
+    is_wallet: bool,
+    wallet: &WalletFilter,
+    network_type: NetworkType,
+) {
+    let color = if is_wallet {
+        theme_color().warning_color
+    } else {
+        theme_color().node_data_color
+    };
+
+    let id = entry
+        .id
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| i18n("unknown").to_string());
+
+    let mut caption = format!("{}  {:.2} {}", id, entry.fee_rate(), i18n("SOMPI/g"),);
+    if entry.is_orphan {
+        caption.push_str(&format!("  ({})", i18n("orphan")));
+    }
+
+    CollapsingHeader::new(RichText::new(caption).color(color).monospace())
+        .id_source(&id)
+        .default_open(false)
+        .show(ui, |ui| {
+            Grid::new(format!("mempool_entry_{id}"))
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label(i18n("Fee:"));
+                    ui.label(sompi_to_kaspa_string_with_suffix(entry.fee, &network_type));
+                    ui.end_row();
+                    ui.label(i18n("Mass:"));
+                    ui.label(entry.mass.separated_string());
+                    ui.end_row();
+                    ui.label(i18n("Fee Rate:"));
+                    ui.label(format!("{:.2} {}", entry.fee_rate(), i18n("SOMPI/g")));
+                    ui.end_row();
+                });
+
+            CollapsingHeader::new(format!("{} ({})", i18n("Inputs"), entry.inputs.len()))
+                .id_source(format!("mempool_entry_inputs_{id}"))
+                .default_open(true)
+                .show(ui, |ui| {
+                    entry.inputs.iter().for_each(|(transaction_id, index)| {
+                        let text = RichText::new(format!("{transaction_id}:{index}")).monospace();
+                        if wallet.transactions.contains(transaction_id) {
+                            ui.label(text.color(theme_color().warning_color));
+                        } else {
+                            ui.label(text);
+                        }
+                    });
+                });
+
+            CollapsingHeader::new(format!("{} ({})", i18n("Outputs"), entry.outputs.len()))
+                .id_source(format!("mempool_entry_outputs_{id}"))
+                .default_open(true)
+                .show(ui, |ui| {
+                    entry.outputs.iter().for_each(|(address, value)| {
+                        let address_text = address
+                            .as_ref()
+                            .map(|address| address.to_string())
+                            .unwrap_or_else(|| i18n("non-standard script").to_string());
+                        let text = RichText::new(format!(
+                            "{}  {address_text}",
+                            sompi_to_kaspa_string_with_suffix(*value, &network_type)
+                        ))
+                        .monospace();
+                        let is_wallet_address = address
+                            .as_ref()
+                            .map(|address| wallet.addresses.contains(address))
+                            .unwrap_or(false);
+                        if is_wallet_address {
+                            ui.label(text.color(theme_color().warning_color));
+                        } else {
+                            ui.label(text);
+                        }
+                    });
+                });
+        });
+}
@@ -1,9 +1,11 @@
 use crate::imports::*;
-use crate::runtime::services::metrics_monitor::MAX_METRICS_SAMPLES;
+use crate::runtime::services::metrics_monitor::{split_gaps, AlertState, MetricsExportFormat, MetricsTier, METRICS_HISTORY_SECONDS};
 use egui_extras::{StripBuilder, Size};
 use kaspa_metrics_core::{Metric,MetricGroup, MetricsSnapshot};
 use chrono::DateTime;
 use egui_plot::{
+    AxisHints,
+    HPlacement,
     Legend,
     Line,
     LineStyle,
@@ -11,17 +13,56 @@ use egui_plot::{
     PlotPoints, uniform_grid_spacer, CoordinatesFormatter, Corner,
 };
 
-const METRICS_SAMPLES_START : isize = -(MAX_METRICS_SAMPLES as isize);
+const METRICS_SAMPLES_START : isize = -(METRICS_HISTORY_SECONDS as isize);
 const MIN_RANGE : isize = 15;
 
+/// Line colors of metrics overlaid on the same graph
+const OVERLAY_COLORS : [Color32; 6] = [
+    Color32::from_rgb(0x3a, 0x9d, 0xe0),
+    Color32::from_rgb(0xe0, 0x8a, 0x3a),
+    Color32::from_rgb(0x6a, 0xc4, 0x5a),
+    Color32::from_rgb(0xc4, 0x5a, 0xb4),
+    Color32::from_rgb(0xd8, 0xc8, 0x3c),
+    Color32::from_rgb(0x5a, 0xc4, 0xc0),
+];
+
+/// Maps the value range of an overlaid metric onto `0.0..=1.0`.
+#[derive(Clone, Copy)]
+struct OverlayScale {
+    metric : Metric,
+    min : f64,
+    span : f64,
+}
+
+impl OverlayScale {
+    fn normalize(&self, value : f64) -> f64 {
+        (value - self.min) / self.span
+    }
+
+    fn value(&self, normalized : f64) -> f64 {
+        self.min + normalized * self.span
+    }
+}
+
 pub struct Metrics {
     #[allow(dead_code)]
     runtime: Runtime,
+    /// Rule being composed in the alerts panel
+    alert_rule: AlertRule,
+    /// Name of the dashboard being created
+    dashboard_name: String,
+    /// Metric of the graph being added to a dashboard
+    dashboard_metric: Metric,
 }
 
 impl Metrics {
     pub fn new(runtime: Runtime) -> Self {
-        Self { runtime }
+        Self {
+            runtime,
+            alert_rule: AlertRule::default(),
+            dashboard_name: String::default(),
+            dashboard_metric: Metric::NodeCpuUsage,
+        }
     }
 }
 
@@ -135,6 +176,72 @@ impl ModuleT for Metrics {
                 .with_close_button(true)
                 .build(ui);
 
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.separator();
+
+                    let response = ui
+                        .add(Label::new(RichText::new(format!("{} ⏷", i18n("Export")))).sense(Sense::click()));
+                    PopupPanel::new(
+                        PopupPanel::id(ui,"metrics_export_popup"),
+                        |_ui| response,
+                        |ui, close| {
+                            set_menu_style(ui.style_mut());
+                            for format in MetricsExportFormat::list() {
+                                if ui.button(format.title()).clicked() {
+                                    self.export(core, *format);
+                                    *close = true;
+                                }
+                            }
+                    })
+                    .with_min_width(100.0)
+                    .build(ui);
+                }
+
+                ui.separator();
+
+                let active_alerts = self.runtime.metrics_service().active_alerts();
+                let caption = if active_alerts > 0 {
+                    RichText::new(format!("{} ({active_alerts}) ⏷", i18n("Alerts"))).color(theme_color().warning_color)
+                } else {
+                    RichText::new(format!("{} ⏷", i18n("Alerts")))
+                };
+                let response = ui.add(Label::new(caption).sense(Sense::click()));
+                PopupPanel::new(PopupPanel::id(ui,"metrics_alerts_popup"), |_ui| response, |ui, _| {
+                    self.render_alerts(core, ui);
+                })
+                .with_min_width(360.)
+                .with_max_height(screen_rect_height * 0.8)
+                .with_caption(i18n("Alerts"))
+                .with_close_button(true)
+                .build(ui);
+
+                ui.separator();
+
+                let response = ui.add(Label::new(format!("{} ⏷", i18n("Dashboards"))).sense(Sense::click()));
+                PopupPanel::new(PopupPanel::id(ui,"metrics_dashboards_popup"), |_ui| response, |ui, _| {
+                    self.render_dashboards(core, ui);
+                })
+                .with_min_width(360.)
+                .with_max_height(screen_rect_height * 0.8)
+                .with_caption(i18n("Dashboards"))
+                .with_close_button(true)
+                .build(ui);
+
+                let mut dashboard = core.settings.user_interface.metrics.dashboard.clone();
+                egui::ComboBox::from_id_source("metrics_dashboard_selector")
+                    .selected_text(dashboard.as_deref().unwrap_or(i18n("All Metrics")))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut dashboard, None, i18n("All Metrics"));
+                        for item in core.settings.user_interface.metrics.dashboards.iter() {
+                            ui.selectable_value(&mut dashboard, Some(item.name.clone()), item.name.as_str());
+                        }
+                    });
+                if dashboard != core.settings.user_interface.metrics.dashboard {
+                    core.settings.user_interface.metrics.dashboard = dashboard;
+                    store_settings = true;
+                }
+
                 ui.separator();
 
                 ui.add(
@@ -199,6 +306,8 @@ impl ModuleT for Metrics {
 
         ui.separator();
 
+        let dashboard = core.settings.user_interface.metrics.dashboard().cloned();
+
         if let Some(metrics) = core.metrics().as_ref() {
 
             egui::ScrollArea::vertical()
@@ -214,23 +323,39 @@ impl ModuleT for Metrics {
                         (core.settings.user_interface.metrics.graph_columns, view_width / core.settings.user_interface.metrics.graph_columns as f32)
                     };
 
+                    let range_from = core.settings.user_interface.metrics.graph_range_from;
+                    let range_to = core.settings.user_interface.metrics.graph_range_to;
 
+                    if let Some(dashboard) = dashboard.as_ref() {
+                        if dashboard.graphs.is_empty() {
+                            ui.label(i18n("This dashboard has no graphs, please add them from the Dashboards menu"));
+                        }
+
+                        for (row_index, row) in dashboard.graphs.chunks(columns).enumerate() {
+                            ui.horizontal(|ui| {
+                                for (column, graph) in row.iter().enumerate() {
+                                    // graphs are identified by their position so that
+                                    // graphs showing the same metrics do not collide
+                                    let id = Id::new(("metrics_dashboard_graph", row_index * columns + column));
+                                    self.render_graph(ui, id, graph, metrics, range_from..range_to, graph_width);
+                                }
+                            });
+                        }
+                    } else {
                         let mut metric_iter = Metric::iter().filter(|metric| !core.settings.user_interface.metrics.disabled.contains(metric));
                         let mut draw = true;
                         while draw {
                             ui.horizontal(|ui| {
                                 for _ in 0..columns {
                                     if let Some(metric) = metric_iter.next() {
-                                        let range_from = core.settings.user_interface.metrics.graph_range_from;
-                                        let range_to = core.settings.user_interface.metrics.graph_range_to;
-                                        self.render_metric(ui, *metric, metrics,range_from..range_to,graph_width,graph_height);
+                                        self.render_metric(ui, Id::new(metric.as_str()), *metric, metrics,range_from..range_to,graph_width,graph_height);
                                     } else {
                                         draw = false;
                                     }
                                 }
                             });
                         }
-                    // }
+                    }
                 });
         } else {
             ui.vertical_centered(|ui| {
@@ -255,10 +380,408 @@ impl ModuleT for Metrics {
 
 impl Metrics {
 
+    /// Alert rule editor and the log of raised alerts.
+    fn render_alerts(&mut self, core : &mut Core, ui : &mut Ui) {
+        let mut rules = core.settings.user_interface.metrics.alerts.clone();
+
+        ScrollArea::vertical()
+            .id_source("metrics_alerts_rules")
+            .max_height(240.)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                if rules.is_empty() {
+                    ui.label(i18n("No alert rules defined"));
+                }
+
+                let mut remove = None;
+                for (index, rule) in rules.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut rule.enabled, "");
+                        ui.label(rule.to_string());
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button(egui_phosphor::light::TRASH).clicked() {
+                                remove = Some(index);
+                            }
+                        });
+                    });
+                }
+                if let Some(index) = remove {
+                    rules.remove(index);
+                }
+            });
+
+        ui.separator();
+
+        let rule = &mut self.alert_rule;
+        ui.horizontal(|ui| {
+            if ui.button(i18n("Peers")).clicked() {
+                rule.metric = Metric::NodeActivePeers;
+            }
+            if ui.button(i18n("Mempool")).clicked() {
+                rule.metric = Metric::NetworkMempoolSize;
+            }
+            egui::ComboBox::from_id_source("metrics_alerts_metric")
+                .selected_text(i18n(rule.metric.title().0))
+                .width(180.)
+                .show_ui(ui, |ui| {
+                    for metric in Metric::iter() {
+                        ui.selectable_value(&mut rule.metric, *metric, i18n(metric.title().0));
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            for comparison in AlertComparison::list() {
+                ui.selectable_value(&mut rule.comparison, *comparison, comparison.symbol());
+            }
+            ui.add(egui::DragValue::new(&mut rule.threshold).speed(0.1));
+            ui.label(i18n("for"));
+            ui.add(egui::DragValue::new(&mut rule.duration_secs).clamp_range(0..=86400).suffix("s"));
+            if ui.button(i18n("Add")).clicked() {
+                rules.push(rule.clone());
+            }
+        });
+
+        if rules != core.settings.user_interface.metrics.alerts {
+            self.runtime.metrics_service().set_alert_rules(&rules);
+            core.settings.user_interface.metrics.alerts = rules;
+            core.store_settings();
+        }
+
+        ui.separator();
+
+        let metrics_service = self.runtime.metrics_service();
+        ui.horizontal(|ui| {
+            ui.label(i18n("Recent alerts"));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button(i18n("Clear")).clicked() {
+                    metrics_service.clear_alert_log();
+                }
+            });
+        });
+
+        let alert_log = metrics_service.alert_log();
+
+        ScrollArea::vertical()
+            .id_source("metrics_alerts_log")
+            .max_height(240.)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                if alert_log.is_empty() {
+                    ui.label(i18n("No alerts"));
+                }
+
+                for alert in alert_log.iter().rev() {
+                    let time = DateTime::<chrono::Utc>::from_timestamp((alert.timestamp / 1000.0) as i64, 0)
+                        .map(|datetime| datetime.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default();
+                    let color = match alert.state {
+                        AlertState::Raised => theme_color().warning_color,
+                        AlertState::Resolved => theme_color().default_color,
+                    };
+                    ui.colored_label(color, format!("{time} {}", alert.message()));
+                }
+            });
+    }
+
+    /// Dashboard editor: create and delete dashboards,
+    /// add, arrange and overlay graphs of the selected one.
+    fn render_dashboards(&mut self, core : &mut Core, ui : &mut Ui) {
+        use egui_phosphor::light::{ARROW_DOWN, ARROW_UP, TRASH, X};
+
+        let settings = &core.settings.user_interface.metrics;
+        let mut dashboards = settings.dashboards.clone();
+        let mut selected = settings.dashboard.clone();
+
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut self.dashboard_name).hint_text(i18n("Dashboard name")).desired_width(200.));
+            let name = self.dashboard_name.trim().to_string();
+            let valid = !name.is_empty() && !dashboards.iter().any(|dashboard| dashboard.name == name);
+            if ui.add_enabled(valid, Button::new(i18n("Create"))).clicked() {
+                dashboards.push(MetricsDashboard::new(name.clone()));
+                selected = Some(name);
+                self.dashboard_name.clear();
+            }
+        });
+
+        let mut delete = false;
+        if let Some(dashboard) = selected.as_ref().and_then(|name| dashboards.iter_mut().find(|dashboard| &dashboard.name == name)) {
+            ui.separator();
+            ui.label(RichText::new(dashboard.name.as_str()).strong());
+            ui.separator();
+
+            let graph_count = dashboard.graphs.len();
+            let mut remove = None;
+            let mut swap = None;
+            for (index, graph) in dashboard.graphs.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(graph.metrics.iter().map(|metric| i18n(metric.title().0)).collect::<Vec<_>>().join(" + "));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button(TRASH).clicked() {
+                            remove = Some(index);
+                        }
+                        if ui.add_enabled(index + 1 < graph_count, Button::new(ARROW_DOWN).small()).clicked() {
+                            swap = Some((index, index + 1));
+                        }
+                        if ui.add_enabled(index > 0, Button::new(ARROW_UP).small()).clicked() {
+                            swap = Some((index - 1, index));
+                        }
+                    });
+                });
+
+                ui.add(
+                    Slider::new(&mut graph.height, 40..=1200)
+                        .text(i18n("Height"))
+                        .logarithmic(true)
+                        .suffix("px")
+                );
+
+                ui.horizontal_wrapped(|ui| {
+                    if graph.metrics.len() > 1 {
+                        let mut remove_metric = None;
+                        for (index, metric) in graph.metrics.iter().enumerate() {
+                            if ui.small_button(format!("{X} {}", i18n(metric.title().1))).clicked() {
+                                remove_metric = Some(index);
+                            }
+                        }
+                        if let Some(index) = remove_metric {
+                            graph.metrics.remove(index);
+                        }
+                    }
+
+                    let available = Metric::iter().filter(|metric| !graph.metrics.contains(metric)).cloned().collect::<Vec<_>>();
+                    egui::ComboBox::from_id_source(("metrics_dashboard_overlay", index))
+                        .selected_text(i18n("Overlay..."))
+                        .show_ui(ui, |ui| {
+                            for metric in available {
+                                if ui.selectable_label(false, i18n(metric.title().0)).clicked() {
+                                    graph.metrics.push(metric);
+                                }
+                            }
+                        });
+                });
+
+                ui.separator();
+            }
+
+            if let Some(index) = remove {
+                dashboard.graphs.remove(index);
+            }
+
+            if let Some((a, b)) = swap {
+                dashboard.graphs.swap(a, b);
+            }
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("metrics_dashboard_metric")
+                    .selected_text(i18n(self.dashboard_metric.title().0))
+                    .width(200.)
+                    .show_ui(ui, |ui| {
+                        for metric in Metric::iter() {
+                            ui.selectable_value(&mut self.dashboard_metric, *metric, i18n(metric.title().0));
+                        }
+                    });
+                if ui.button(i18n("Add Graph")).clicked() {
+                    dashboard.graphs.push(MetricsGraph::new(self.dashboard_metric));
+                }
+            });
+
+            ui.separator();
+
+            if ui.button(i18n("Delete Dashboard")).clicked() {
+                delete = true;
+            }
+        }
+
+        if delete {
+            dashboards.retain(|dashboard| Some(&dashboard.name) != selected.as_ref());
+            selected = None;
+        }
+
+        let settings = &mut core.settings.user_interface.metrics;
+        if dashboards != settings.dashboards || selected != settings.dashboard {
+            settings.dashboards = dashboards;
+            settings.dashboard = selected;
+            core.store_settings();
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_graph(
+        &mut self,
+        ui : &mut Ui,
+        id : Id,
+        graph : &MetricsGraph,
+        metrics : &MetricsSnapshot,
+        range : std::ops::Range<isize>,
+        graph_width : f32,
+    ) {
+        let graph_height = graph.height as f32;
+        match graph.metrics.as_slice() {
+            [] => {}
+            [metric] => self.render_metric(ui, id, *metric, metrics, range, graph_width, graph_height),
+            overlay => self.render_overlay(ui, id, overlay, metrics, range, graph_width, graph_height),
+        }
+    }
+
+    /// Overlay several metrics on one plot. Each metric is scaled
+    /// to its own value range and gets its own y axis.
+    #[allow(clippy::too_many_arguments)]
+    fn render_overlay(
+        &mut self,
+        ui : &mut Ui,
+        id : Id,
+        overlay : &[Metric],
+        metrics : &MetricsSnapshot,
+        range : std::ops::Range<isize>,
+        graph_width : f32,
+        graph_height : f32
+    ) {
+        let now = unixtime_as_millis_f64();
+        let start = range.start.clamp(METRICS_SAMPLES_START, 0) as f64 * 1000.0;
+        let end = range.end.clamp(METRICS_SAMPLES_START, 0) as f64 * 1000.0;
+        let tier = MetricsTier::for_age(-start);
+        let time_format = if end - start > 24.0 * 60.0 * 60.0 * 1000.0 { "%m-%d %H:%M" } else { "%H:%M:%S" };
+
+        let series = {
+            let metrics_history = self.runtime.metrics_service().metrics_data();
+            overlay.iter().enumerate().map(|(index, metric)| {
+                let samples = metrics_history.get(metric)
+                    .map(|history| history.samples(tier, (now + start)..(now + end)))
+                    .unwrap_or_default();
+                let (min, max) = samples.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), sample| {
+                    (min.min(sample.avg), max.max(sample.avg))
+                });
+                let (min, max) = if !min.is_finite() || !max.is_finite() {
+                    (0.0, 1.0)
+                } else if max > min {
+                    (min, max)
+                } else {
+                    (min - 1.0, max + 1.0)
+                };
+                let scale = OverlayScale { metric : *metric, min, span : max - min };
+                (scale, OVERLAY_COLORS[index % OVERLAY_COLORS.len()], samples)
+            }).collect::<Vec<_>>()
+        };
+
+        StripBuilder::new(ui)
+            .size(Size::exact(graph_width))
+            .horizontal(|mut strip| {
+
+                strip.cell(|ui| {
+                    ui.vertical(|ui| {
+                        ui.add_space(8.);
+                        ui.horizontal_wrapped(|ui|{
+                            ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                                for (scale, color, _) in series.iter().rev() {
+                                    ui.colored_label(*color, format!("{}: {}", i18n(scale.metric.title().0), scale.metric.format(metrics.get(&scale.metric), true, false)));
+                                }
+                            });
+                        });
+
+                        let axes = series.iter().enumerate().map(|(index, (scale, color, _))| {
+                            let scale = *scale;
+                            let placement = if index % 2 == 0 { HPlacement::Left } else { HPlacement::Right };
+                            AxisHints::new_y()
+                                .label(RichText::new(i18n(scale.metric.title().1)).color(*color))
+                                .placement(placement)
+                                .formatter(move |grid, _range| scale.metric.format(scale.value(grid.value), true, true))
+                        }).collect::<Vec<_>>();
+
+                        let names = series.iter()
+                            .map(|(scale, _, _)| (i18n(scale.metric.title().0).to_string(), *scale))
+                            .collect::<Vec<_>>();
+
+                        let plot = Plot::new(id)
+                            .legend(Legend::default())
+                            .width(graph_width)
+                            .height(graph_height)
+                            .auto_bounds([true, true].into())
+                            .include_y(0.0)
+                            .include_y(1.0)
+                            .set_margin_fraction(vec2(0.0,0.05))
+                            .custom_y_axes(axes)
+                            .show_axes(true)
+                            .show_grid(true)
+                            .allow_drag([false, false])
+                            .allow_scroll(false)
+                            .x_axis_formatter(move |grid, _range| format_time(grid.value, time_format))
+                            .x_grid_spacer(
+                                uniform_grid_spacer(move |input| {
+                                    let (start_time,stop_time) = input.bounds;
+                                    let range = stop_time - start_time;
+                                    let base_step_size = range / graph_width as f64 * 64.;
+                                    calculate_grid_lines(base_step_size)
+                                })
+                            )
+                            .label_formatter(move |name, point| {
+                                let PlotPoint { x, y } = point;
+                                let value = names.iter()
+                                    .find(|(title, _)| title == name)
+                                    .map(|(_, scale)| scale.metric.format(scale.value(*y), true, true))
+                                    .unwrap_or_default();
+                                format!("{name}: {value} @ {}", format_time(*x, time_format))
+                            });
+
+                        plot.show(ui, |plot_ui| {
+                            for (scale, color, samples) in series.iter() {
+                                let name = i18n(scale.metric.title().0);
+                                for run in split_gaps(samples, tier) {
+                                    plot_ui.line(Line::new(PlotPoints::Owned(run.iter().map(|sample| PlotPoint {
+                                            x : sample.timestamp,
+                                            y : scale.normalize(sample.avg),
+                                        }).collect()))
+                                        .color(*color)
+                                        .style(LineStyle::Solid)
+                                        .name(name));
+                                }
+                            }
+                        });
+                    });
+                });
+        });
+    }
+
+    /// Export enabled metrics over the selected graph range to a file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export(&self, core : &Core, format : MetricsExportFormat) {
+        let settings = &core.settings.user_interface.metrics;
+        let metrics = Metric::iter().filter(|metric| !settings.disabled.contains(metric)).cloned().collect::<Vec<_>>();
+        let now = unixtime_as_millis_f64();
+        let start = settings.graph_range_from as f64 * 1000.0;
+        let end = settings.graph_range_to as f64 * 1000.0;
+        let tier = MetricsTier::for_age(-start);
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(format.title(), &[format.extension()])
+            .set_file_name(format!("kaspa-ng-metrics.{}", format.extension()))
+            .save_file() else {
+            return;
+        };
+
+        let result = self.runtime.metrics_service()
+            .export(&metrics, &[tier], (now + start)..(now + end), format)
+            .and_then(|data| Ok(std::fs::write(&path, data)?));
+
+        match result {
+            Ok(()) => {
+                runtime().notify(UserNotification::success(i18n_args(
+                    "Metrics exported to {path}",
+                    &[("path", path.to_string_lossy())],
+                )));
+            }
+            Err(err) => {
+                runtime().notify(UserNotification::error(format!("{}: {err}", i18n("Unable to export metrics"))));
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     #[allow(clippy::too_many_arguments)]
     fn render_metric(
         &mut self, 
         ui : &mut Ui, 
+        id : Id,
         metric : Metric, 
         metrics : &MetricsSnapshot, 
         range : std::ops::Range<isize>,
@@ -283,21 +806,19 @@ impl Metrics {
                         });
 
                         // ---
+                        // pick the finest tier retaining the start of the range
+                        let now = unixtime_as_millis_f64();
+                        let start = range.start.clamp(METRICS_SAMPLES_START, 0) as f64 * 1000.0;
+                        let end = range.end.clamp(METRICS_SAMPLES_START, 0) as f64 * 1000.0;
+                        let tier = MetricsTier::for_age(-start);
                         let graph_data = {
-                            let metrics_data = self.runtime.metrics_service().metrics_data();
-                            let data = metrics_data.get(&metric).unwrap();
-                            let mut start = range.start.clamp(METRICS_SAMPLES_START, 0).unsigned_abs();
-                            let mut end = range.end.clamp(METRICS_SAMPLES_START, 0).unsigned_abs();
-                            if start > data.len() {
-                                start = data.len();
-                            }
-                            if end > data.len() {
-                                end = data.len();
-                            }
-                            data[data.len()-start..data.len()-end].to_vec()
+                            let metrics_history = self.runtime.metrics_service().metrics_data();
+                            let history = metrics_history.get(&metric).unwrap();
+                            history.samples(tier, (now + start)..(now + end))
                         };
+                        let time_format = if end - start > 24.0 * 60.0 * 60.0 * 1000.0 { "%m-%d %H:%M" } else { "%H:%M:%S" };
 
-                        let mut plot = Plot::new(metric.as_str())
+                        let mut plot = Plot::new(id)
                         // .link_axis(id, true, false)
                         // .allow_boxed_zoom(true)
                         // .allow_double_click_reset(true)
@@ -324,7 +845,7 @@ impl Metrics {
                                 DateTime::<chrono::Utc>::from_timestamp((grid.value / 1000.0) as i64, 0)
                                     .expect("could not parse timestamp")
                                     .with_timezone(&chrono::Local)
-                                    .format("%H:%M:%S")
+                                    .format(time_format)
                                     .to_string()
                             })
                             .x_grid_spacer(
@@ -341,7 +862,7 @@ impl Metrics {
                                 format!("{} @ {}", metric.format(*y, true, true), DateTime::<chrono::Utc>::from_timestamp((*x / 1000.0) as i64, 0)
                                     .expect("could not parse timestamp")
                                     .with_timezone(&chrono::Local)
-                                    .format("%H:%M:%S")
+                                    .format(time_format)
                                 )
                             })                                                    
                             .coordinates_formatter(Corner::LeftTop, CoordinatesFormatter::new(move |point,_| {
@@ -365,13 +886,26 @@ impl Metrics {
                             plot = plot.include_y(100.);
                         }
                 
-                        let line = Line::new(PlotPoints::Owned(graph_data))
-                            .color(graph_color)
-                            .style(LineStyle::Solid)
-                            .fill(0.0);
+                        // aggregated tiers also show the min/max envelope
+                        let show_range = tier != MetricsTier::Seconds;
+                        let range_color = graph_color.gamma_multiply(0.5);
                 
                         plot.show(ui, |plot_ui| {
-                            plot_ui.line(line);
+                            for run in split_gaps(&graph_data, tier) {
+                                if show_range {
+                                    plot_ui.line(Line::new(PlotPoints::Owned(run.iter().map(|sample| sample.min_point()).collect()))
+                                        .color(range_color)
+                                        .style(LineStyle::dashed_dense()));
+                                    plot_ui.line(Line::new(PlotPoints::Owned(run.iter().map(|sample| sample.max_point()).collect()))
+                                        .color(range_color)
+                                        .style(LineStyle::dashed_dense()));
+                                }
+
+                                plot_ui.line(Line::new(PlotPoints::Owned(run.iter().map(|sample| sample.avg_point()).collect()))
+                                    .color(graph_color)
+                                    .style(LineStyle::Solid)
+                                    .fill(0.0));
+                            }
                         });
                     });
                 });
@@ -399,6 +933,12 @@ fn calculate_grid_lines(base_step_size : f64) -> [f64; 3] {
     [small_grid, medium_grid, large_grid]
 }
 
+fn format_time(millis : f64, format : &str) -> String {
+    DateTime::<chrono::Utc>::from_timestamp((millis / 1000.0) as i64, 0)
+        .map(|datetime| datetime.with_timezone(&chrono::Local).format(format).to_string())
+        .unwrap_or_default()
+}
+
 fn format_duration(seconds: u64) -> String {
     const SECONDS_IN_MINUTE: u64 = 60;
     const MINUTES_IN_HOUR: u64 = 60;
@@ -7,6 +7,7 @@ kaspa_ng_macros::register_modules!(
     [
         account_create,
         account_manager,
+        block_lookup,
         donations,
         export,
         import,
@@ -16,6 +17,7 @@ kaspa_ng_macros::register_modules!(
         scanner,
         settings,
         testing,
+        tools,
         wallet_create,
         wallet_open,
         wallet_secret,
@@ -24,10 +26,16 @@ kaspa_ng_macros::register_modules!(
 );
 
 #[cfg(not(target_arch = "wasm32"))]
-kaspa_ng_macros::register_modules!(register_native_modules, [changelog, logs, node,]);
+kaspa_ng_macros::register_modules!(
+    register_native_modules,
+    [changelog, logs, node, rpc_console,]
+);
 
 #[cfg(not(feature = "lean"))]
-kaspa_ng_macros::register_modules!(register_advanced_modules, [block_dag, metrics,]);
+kaspa_ng_macros::register_modules!(
+    register_advanced_modules,
+    [block_dag, mempool, metrics, network_stats,]
+);
 
 pub enum ModuleStyle {
     Mobile,
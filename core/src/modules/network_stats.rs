@@ -0,0 +1,253 @@
+use crate::imports::*;
+use crate::runtime::services::network_monitor::{NetworkStat, NetworkStatsSample};
+use chrono::DateTime;
+use egui_plot::{
+    uniform_grid_spacer, CoordinatesFormatter, Corner, Line, LineStyle, Plot, PlotPoints,
+};
+
+const GRAPH_HEIGHT: f32 = 180.;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum StatsRange {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl StatsRange {
+    fn list() -> &'static [StatsRange] {
+        &[
+            StatsRange::Hour,
+            StatsRange::Day,
+            StatsRange::Week,
+            StatsRange::Month,
+        ]
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            StatsRange::Hour => i18n("1 hour"),
+            StatsRange::Day => i18n("24 hours"),
+            StatsRange::Week => i18n("7 days"),
+            StatsRange::Month => i18n("30 days"),
+        }
+    }
+
+    fn millis(&self) -> f64 {
+        const HOUR: f64 = 60.0 * 60.0 * 1000.0;
+        match self {
+            StatsRange::Hour => HOUR,
+            StatsRange::Day => HOUR * 24.0,
+            StatsRange::Week => HOUR * 24.0 * 7.0,
+            StatsRange::Month => HOUR * 24.0 * 30.0,
+        }
+    }
+
+    fn time_format(&self) -> &'static str {
+        match self {
+            StatsRange::Hour | StatsRange::Day => "%H:%M",
+            StatsRange::Week | StatsRange::Month => "%m-%d %H:%M",
+        }
+    }
+}
+
+/// Charts the network statistics history collected
+/// by the network monitor service.
+pub struct NetworkStats {
+    #[allow(dead_code)]
+    runtime: Runtime,
+    range: StatsRange,
+}
+
+impl NetworkStats {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            range: StatsRange::Day,
+        }
+    }
+}
+
+impl ModuleT for NetworkStats {
+    fn style(&self) -> ModuleStyle {
+        ModuleStyle::Default
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        _ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+        ui.horizontal(|ui| {
+            ui.heading(i18n("Network"));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                for range in StatsRange::list().iter().rev() {
+                    ui.selectable_value(&mut self.range, *range, range.title());
+                }
+                if core.device().orientation() != Orientation::Portrait {
+                    ui.label(i18n("Range:"));
+                }
+            });
+        });
+
+        ui.separator();
+
+        let monitor = self.runtime.network_monitor_service();
+        let samples = if monitor.network() == Some(core.network()) {
+            let since = unixtime_as_millis_f64() - self.range.millis();
+            monitor
+                .samples()
+                .iter()
+                .filter(|sample| sample.timestamp >= since)
+                .cloned()
+                .collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+
+        if samples.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(32.);
+                if core.state().is_connected() {
+                    ui.label(i18n("Collecting network statistics..."));
+                    ui.add_space(32.);
+                    ui.add(egui::Spinner::new().size(92.));
+                } else {
+                    ui.label(i18n("Please connect to Kaspa p2p node"));
+                }
+            });
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .id_source("network_stats")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                let view_width = ui.available_width() - 32.;
+                let (columns, graph_width) = if core.device().orientation() == Orientation::Portrait
+                {
+                    (1, view_width)
+                } else {
+                    (2, view_width / 2.)
+                };
+
+                for row in NetworkStat::list().chunks(columns) {
+                    ui.horizontal(|ui| {
+                        for stat in row {
+                            self.render_stat(ui, *stat, &samples, graph_width);
+                        }
+                    });
+                }
+            });
+    }
+}
+
+impl NetworkStats {
+    fn render_stat(
+        &self,
+        ui: &mut Ui,
+        stat: NetworkStat,
+        samples: &[NetworkStatsSample],
+        graph_width: f32,
+    ) {
+        let graph_data = samples
+            .iter()
+            .filter_map(|sample| {
+                sample.get(stat).map(|y| PlotPoint {
+                    x: sample.timestamp,
+                    y,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let time_format = self.range.time_format();
+
+        ui.vertical(|ui| {
+            ui.set_width(graph_width);
+            ui.add_space(8.);
+            ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                let value = graph_data
+                    .last()
+                    .map(|point| stat.format(point.y))
+                    .unwrap_or_else(|| "--".to_string());
+                ui.colored_label(
+                    theme_color().metrics_text_color,
+                    format!("{}: {}", stat.title(), value),
+                );
+            });
+
+            let plot = Plot::new(stat.title())
+                .width(graph_width)
+                .height(GRAPH_HEIGHT)
+                .auto_bounds([true, true].into())
+                .set_margin_fraction(vec2(0.0, 0.1))
+                .y_axis_min_width(4.0 * 12.0)
+                .show_axes(true)
+                .show_grid(true)
+                .allow_drag([false, false])
+                .allow_scroll(false)
+                .y_axis_formatter(move |grid, _range| stat.format(grid.value))
+                .x_axis_formatter(move |grid, _range| format_timestamp(grid.value, time_format))
+                .x_grid_spacer(uniform_grid_spacer(move |input| {
+                    let (start_time, stop_time) = input.bounds;
+                    let range = stop_time - start_time;
+                    let base_step_size = range / graph_width as f64 * 96.;
+                    calculate_grid_lines(base_step_size)
+                }))
+                .label_formatter(move |_name, point| {
+                    let PlotPoint { x, y } = point;
+                    format!(
+                        "{} @ {}",
+                        stat.format(*y),
+                        format_timestamp(*x, "%Y-%m-%d %H:%M")
+                    )
+                })
+                .coordinates_formatter(
+                    Corner::LeftTop,
+                    CoordinatesFormatter::new(move |point, _| stat.format(point.y)),
+                );
+
+            let line = Line::new(PlotPoints::Owned(graph_data))
+                .color(theme_color().node_data_color)
+                .style(LineStyle::Solid);
+
+            plot.show(ui, |plot_ui| {
+                plot_ui.line(line);
+            });
+        });
+    }
+}
+
+fn format_timestamp(millis: f64, format: &str) -> String {
+    DateTime::<chrono::Utc>::from_timestamp((millis / 1000.0) as i64, 0)
+        .map(|datetime| {
+            datetime
+                .with_timezone(&chrono::Local)
+                .format(format)
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn calculate_grid_lines(base_step_size: f64) -> [f64; 3] {
+    let mut small_grid = 60. * 1000_f64;
+    let mut medium_grid = 5. * 60. * 1000_f64;
+    let mut large_grid = 15. * 60. * 1000_f64;
+
+    while small_grid < base_step_size {
+        small_grid *= 2.;
+    }
+
+    while medium_grid < small_grid {
+        medium_grid *= 2.;
+    }
+
+    while large_grid < medium_grid {
+        large_grid *= 2.;
+    }
+
+    [small_grid, medium_grid, large_grid]
+}
@@ -1,6 +1,8 @@
 use kaspa_rpc_core::RpcPeerInfo;
 
 use crate::imports::*;
+use crate::runtime::services::kaspa::NodeStatus;
+use kaspa_metrics_core::data::as_data_size;
 use crate::utils::format_duration;
 
 pub struct Node {
@@ -30,7 +32,22 @@ impl ModuleT for Node {
 
         ui.heading(i18n("Node Status"));
         ui.separator();
-        
+
+        if let Some(system) = runtime().system() {
+            system.render(ui);
+        }
+
+        let nodes = self.runtime.kaspa_service().node_status();
+        if !nodes.is_empty() {
+            CollapsingHeader::new(i18n("Integrated Nodes"))
+                .default_open(true)
+                .show(ui, |ui| {
+                    nodes.iter().for_each(|node| {
+                        render_node_status(ui, node);
+                    });
+                });
+        }
+
         if !core.state().is_connected() {
             ui.label(i18n("Not connected"));
             return;
@@ -135,4 +152,89 @@ fn render_peer(ui : &mut Ui, peer: &RpcPeerInfo) {
                 });
 
         });
-}
\ No newline at end of file
+}
+
+fn render_node_status(ui : &mut Ui, node: &NodeStatus) {
+
+    let color = theme_color().node_data_color;
+
+    Grid::new(format!("node_status_grid_{}", node.network))
+        .num_columns(2)
+        .spacing([16.0,4.0])
+        .show(ui, |ui| {
+
+            ui.label(i18n("Network"));
+            ui.colored_label(color, node.network.name());
+            ui.end_row();
+
+            ui.label(i18n("Mode"));
+            ui.horizontal(|ui| {
+                ui.colored_label(color, node.node_kind.to_string());
+                if node.is_active {
+                    ui.label(i18n("(active)"));
+                } else if node.is_background {
+                    ui.label(i18n("(background)"));
+                }
+            });
+            ui.end_row();
+
+            ui.label(i18n("State"));
+            ui.horizontal(|ui| {
+                if node.is_running {
+                    ui.colored_label(color, i18n("Running"));
+                } else {
+                    ui.colored_label(theme_color().error_color, i18n("Stopped"));
+                }
+                if let Some(pid) = node.pid {
+                    ui.label(i18n("PID:"));
+                    ui.colored_label(color, pid.to_string());
+                }
+                ui.label(i18n("Uptime:"));
+                ui.colored_label(color, format_duration(node.uptime.as_millis() as u64));
+            });
+            ui.end_row();
+
+            if let Some(stats) = node.process_stats.as_ref() {
+                ui.label(i18n("Process"));
+                ui.horizontal(|ui| {
+                    ui.label(i18n("CPU:"));
+                    ui.colored_label(color, format!("{:.1}%", stats.cpu_usage));
+                    ui.label(i18n("RSS:"));
+                    ui.colored_label(color, as_data_size(stats.memory as f64, false));
+                    if let Some(open_files) = stats.open_files {
+                        ui.label(i18n("Open files:"));
+                        ui.colored_label(color, open_files.separated_string());
+                    }
+                });
+                ui.end_row();
+            }
+
+            if !node.is_active {
+                ui.label(i18n("Sync"));
+                ui.horizontal(|ui| {
+                    match node.is_synced {
+                        Some(true) => { ui.colored_label(color, i18n("Synced")); },
+                        Some(false) => { ui.colored_label(color, i18n("Syncing...")); },
+                        None => { ui.label(i18n("Connecting...")); },
+                    }
+                    if let Some(daa_score) = node.virtual_daa_score {
+                        ui.label(i18n("DAA:"));
+                        ui.colored_label(color, daa_score.separated_string());
+                    }
+                    if let Some(version) = node.server_version.as_ref() {
+                        ui.label(i18n("Version:"));
+                        ui.colored_label(color, version);
+                    }
+                });
+                ui.end_row();
+
+                ui.label("");
+                if ui.small_button(i18n("Stop")).clicked() {
+                    runtime().kaspa_service().stop_background_node(node.network);
+                }
+                ui.end_row();
+            }
+        });
+
+    ui.add_space(4.);
+}
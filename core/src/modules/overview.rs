@@ -5,24 +5,48 @@ use egui::load::Bytes;
 #[cfg(not(feature = "lean"))]
 use kaspa_metrics_core::{Metric,MetricGroup};
 #[cfg(not(feature = "lean"))]
+use crate::runtime::services::metrics_monitor::MetricSample;
+#[cfg(not(feature = "lean"))]
+use egui_plot::Legend;
 use egui_plot::{
-    Legend,
+    BoxElem,
+    BoxPlot,
+    BoxSpread,
     Line,
     LineStyle,
     Plot,
     PlotPoints,
 };
+use chrono::DateTime;
 
 use crate::imports::*;
+use crate::market::{Market, OhlcRange};
+
+#[derive(Default, Clone, Copy, Eq, PartialEq)]
+enum PriceChart {
+    #[default]
+    Candles,
+    Line,
+}
+
+impl PriceChart {
+    fn title(&self) -> &'static str {
+        match self {
+            PriceChart::Candles => i18n("Candles"),
+            PriceChart::Line => i18n("Line"),
+        }
+    }
+}
 
 pub struct Overview {
     #[allow(dead_code)]
     runtime: Runtime,
+    price_chart: PriceChart,
 }
 
 impl Overview {
     pub fn new(runtime: Runtime) -> Self {
-        Self { runtime }
+        Self { runtime, price_chart: PriceChart::default() }
     }
 }
 
@@ -137,10 +161,9 @@ impl Overview {
                             .show(ui, |ui| {
 
                                 if let Some(price_list) = market.price.as_ref() {
-                                    let mut symbols = price_list.keys().collect::<Vec<_>>();
-                                    symbols.sort();
-                                    symbols.into_iter().for_each(|symbol| {
+                                    core.settings.market.currencies.iter().for_each(|symbol| {
                                         if let Some(data) = price_list.get(symbol) {
+                                            let currency = symbol.as_str();
                                             let symbol = symbol.to_uppercase();
                                             CollapsingHeader::new(symbol.as_str())
                                                 .default_open(true)
@@ -171,6 +194,8 @@ impl Overview {
                                                             ui.end_row();
                                                         });
 
+                                                    self.render_price_chart(ui, market, currency, data.precision);
+
                                                 });
                                         }
                                     })
@@ -448,6 +473,98 @@ impl Overview {
         }
     }
 
+    fn render_price_chart(&mut self, ui : &mut Ui, market : &Market, currency : &str, precision : usize) {
+
+        let market_monitor = self.runtime.market_monitor_service().clone();
+        let range = market_monitor.ohlc_range();
+
+        ui.add_space(4.);
+        ui.horizontal(|ui| {
+            for item in OhlcRange::list() {
+                if ui.selectable_label(range == *item, item.title()).clicked() && range != *item {
+                    market_monitor.set_ohlc_range(*item);
+                }
+            }
+            ui.separator();
+            for chart in [PriceChart::Candles, PriceChart::Line] {
+                ui.selectable_value(&mut self.price_chart, chart, chart.title());
+            }
+        });
+
+        let Some(ohlc) = market.ohlc.as_ref().and_then(|ohlc| ohlc.get(currency)) else {
+            return;
+        };
+
+        if ohlc.candles.len() < 2 {
+            return;
+        }
+
+        let time_format = match ohlc.range {
+            OhlcRange::Day => "%H:%M",
+            OhlcRange::Week | OhlcRange::Month => "%b %d",
+            OhlcRange::Year => "%b %Y",
+        };
+
+        let symbol = currency.to_uppercase();
+        let plot = Plot::new(format!("market_price_chart_{currency}"))
+            .height(128.)
+            .auto_bounds([true, true].into())
+            .set_margin_fraction(vec2(0.0, 0.1))
+            .y_axis_min_width(4.0 * 12.0)
+            .show_grid(false)
+            .allow_drag([false, false])
+            .allow_zoom([false, false])
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .x_axis_formatter(move |grid, _range| format_time(grid.value, time_format))
+            .y_axis_formatter(move |grid, _range| format!("{:.*}", precision, grid.value))
+            .label_formatter(move |_name, point| {
+                format!("{} @ {}", format_currency_with_symbol(point.y, precision, symbol.as_str()), format_time(point.x, "%Y-%m-%d %H:%M"))
+            });
+
+        let up_color = theme_color().market_up_color;
+        let down_color = theme_color().market_down_color;
+
+        match self.price_chart {
+            PriceChart::Candles => {
+                // candle timestamps mark the close of the period
+                let width = ohlc.candle_duration();
+                let candles = ohlc.candles.iter().map(|candle| {
+                    let color = if candle.close >= candle.open { up_color } else { down_color };
+                    let spread = BoxSpread::new(
+                        candle.low,
+                        candle.open.min(candle.close),
+                        candle.close,
+                        candle.open.max(candle.close),
+                        candle.high,
+                    );
+                    BoxElem::new(candle.timestamp - width * 0.5, spread)
+                        .box_width(width * 0.7)
+                        .whisker_width(0.0)
+                        .fill(color)
+                        .stroke(Stroke::new(1.0, color))
+                        .name(format_time(candle.timestamp, "%Y-%m-%d %H:%M"))
+                }).collect::<Vec<_>>();
+
+                plot.show(ui, |plot_ui| {
+                    plot_ui.box_plot(BoxPlot::new(candles));
+                });
+            }
+            PriceChart::Line => {
+                let (first, last) = (ohlc.candles.first().unwrap(), ohlc.candles.last().unwrap());
+                let color = if last.close >= first.open { up_color } else { down_color };
+                let points = ohlc.candles.iter().map(|candle| PlotPoint::new(candle.timestamp, candle.close)).collect::<Vec<_>>();
+                let line = Line::new(PlotPoints::Owned(points))
+                    .color(color)
+                    .style(LineStyle::Solid);
+
+                plot.show(ui, |plot_ui| {
+                    plot_ui.line(line);
+                });
+            }
+        }
+    }
+
     #[cfg(not(feature = "lean"))]
     fn render_graphs(&mut self, core: &mut Core, ui : &mut Ui) {
 
@@ -487,16 +604,14 @@ impl Overview {
         let graph_color = group.to_color();
 
         let graph_data = {
-            let metrics_data = self.runtime.metrics_service().metrics_data();
-            let data = metrics_data.get(&metric).unwrap();
+            let metrics_history = self.runtime.metrics_service().metrics_data();
+            let history = metrics_history.get(&metric).unwrap();
             let mut duration = 2 * 60;
             let available_samples = runtime().metrics_service().samples_since_connection();
             if available_samples < duration {
                 duration = available_samples;
             }
-            let len = data.len();
-            let samples = len.min(duration);
-            data[len-samples..].to_vec()
+            history.recent(duration as f64 * 1000.0).iter().map(MetricSample::avg_point).collect::<Vec<_>>()
             // let mut data = data[len-samples..].to_vec();
             // if data.len() == 1{
             //     let mut last_clone = data[0].clone();
@@ -618,4 +733,10 @@ const METRICS : &[Metric] = &[
     Metric::NetworkPastMedianTime,
     Metric::NetworkVirtualParentHashesCount,
     Metric::NetworkVirtualDaaScore,
-];
\ No newline at end of file
+];
+
+fn format_time(millis : f64, format : &str) -> String {
+    DateTime::<chrono::Utc>::from_timestamp((millis / 1000.0) as i64, 0)
+        .map(|datetime| datetime.with_timezone(&chrono::Local).format(format).to_string())
+        .unwrap_or_default()
+}
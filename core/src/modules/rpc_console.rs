@@ -0,0 +1,940 @@
+use crate::imports::*;
+use chrono::{DateTime, Local};
+use kaspa_rpc_core::RpcHash;
+use serde_json::{json, Value};
+
+/// Number of calls retained in the console history.
+const MAX_HISTORY_ENTRIES: usize = 64;
+/// Maximum height of the history list.
+const HISTORY_MAX_HEIGHT: f32 = 160.0;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ParamKind {
+    Bool,
+    Hash,
+    OptionalHash,
+    Address,
+    Addresses,
+    U32,
+    U64,
+    U64List,
+    Text,
+    Json,
+    PeerAddress,
+    IpAddress,
+    SubnetworkId,
+}
+
+impl ParamKind {
+    fn hint(&self) -> &'static str {
+        match self {
+            ParamKind::Bool => "",
+            ParamKind::Hash => i18n("Hash"),
+            ParamKind::OptionalHash => i18n("Hash (optional)"),
+            ParamKind::Address => i18n("Address"),
+            ParamKind::Addresses => i18n("Addresses separated by spaces or commas"),
+            ParamKind::U32 | ParamKind::U64 => i18n("Number"),
+            ParamKind::U64List => i18n("Numbers separated by spaces or commas"),
+            ParamKind::Text => i18n("Text (optional)"),
+            ParamKind::Json => i18n("JSON"),
+            ParamKind::PeerAddress => i18n("IP address and port"),
+            ParamKind::IpAddress => i18n("IP address"),
+            ParamKind::SubnetworkId => i18n("Subnetwork id"),
+        }
+    }
+}
+
+struct Param {
+    name: &'static str,
+    kind: ParamKind,
+    default: &'static str,
+}
+
+const fn param(name: &'static str, kind: ParamKind, default: &'static str) -> Param {
+    Param {
+        name,
+        kind,
+        default,
+    }
+}
+
+const NO_PARAMS: &[Param] = &[];
+const MEMPOOL_PARAMS: &[Param] = &[
+    param("include_orphan_pool", ParamKind::Bool, "false"),
+    param("filter_transaction_pool", ParamKind::Bool, "false"),
+];
+const MEMPOOL_ENTRY_PARAMS: &[Param] = &[
+    param("transaction_id", ParamKind::Hash, ""),
+    param("include_orphan_pool", ParamKind::Bool, "false"),
+    param("filter_transaction_pool", ParamKind::Bool, "false"),
+];
+const MEMPOOL_BY_ADDRESSES_PARAMS: &[Param] = &[
+    param("addresses", ParamKind::Addresses, ""),
+    param("include_orphan_pool", ParamKind::Bool, "false"),
+    param("filter_transaction_pool", ParamKind::Bool, "false"),
+];
+const BLOCK_PARAMS: &[Param] = &[
+    param("hash", ParamKind::Hash, ""),
+    param("include_transactions", ParamKind::Bool, "true"),
+];
+const BLOCKS_PARAMS: &[Param] = &[
+    param("low_hash", ParamKind::OptionalHash, ""),
+    param("include_blocks", ParamKind::Bool, "true"),
+    param("include_transactions", ParamKind::Bool, "false"),
+];
+const ADDRESS_PARAMS: &[Param] = &[param("address", ParamKind::Address, "")];
+const ADDRESSES_PARAMS: &[Param] = &[param("addresses", ParamKind::Addresses, "")];
+const HASHRATE_PARAMS: &[Param] = &[
+    param("window_size", ParamKind::U32, "1000"),
+    param("start_hash", ParamKind::OptionalHash, ""),
+];
+const VIRTUAL_CHAIN_PARAMS: &[Param] = &[
+    param("start_hash", ParamKind::Hash, ""),
+    param("include_accepted_transaction_ids", ParamKind::Bool, "false"),
+];
+const DAA_SCORE_PARAMS: &[Param] = &[param("daa_scores", ParamKind::U64List, "")];
+const CONNECTIONS_PARAMS: &[Param] = &[param("include_profile_data", ParamKind::Bool, "false")];
+const METRICS_PARAMS: &[Param] = &[
+    param("include_process_metrics", ParamKind::Bool, "true"),
+    param("include_connection_metrics", ParamKind::Bool, "true"),
+    param("include_bandwidth_metrics", ParamKind::Bool, "true"),
+    param("include_consensus_metrics", ParamKind::Bool, "true"),
+    param("include_storage_metrics", ParamKind::Bool, "true"),
+    param("include_custom_metrics", ParamKind::Bool, "false"),
+];
+const SUBMIT_BLOCK_PARAMS: &[Param] = &[
+    param("block", ParamKind::Json, ""),
+    param("allow_non_daa_blocks", ParamKind::Bool, "false"),
+];
+const BLOCK_TEMPLATE_PARAMS: &[Param] = &[
+    param("pay_address", ParamKind::Address, ""),
+    param("extra_data", ParamKind::Text, ""),
+];
+const ADD_PEER_PARAMS: &[Param] = &[
+    param("peer_address", ParamKind::PeerAddress, ""),
+    param("is_permanent", ParamKind::Bool, "false"),
+];
+const SUBMIT_TRANSACTION_PARAMS: &[Param] = &[
+    param("transaction", ParamKind::Json, ""),
+    param("allow_orphan", ParamKind::Bool, "false"),
+];
+const TRANSACTION_PARAMS: &[Param] = &[param("transaction", ParamKind::Json, "")];
+const SUBNETWORK_PARAMS: &[Param] = &[param("subnetwork_id", ParamKind::SubnetworkId, "")];
+const FINALITY_CONFLICT_PARAMS: &[Param] = &[param("finality_block_hash", ParamKind::Hash, "")];
+const HEADERS_PARAMS: &[Param] = &[
+    param("start_hash", ParamKind::Hash, ""),
+    param("limit", ParamKind::U64, "100"),
+    param("is_ascending", ParamKind::Bool, "true"),
+];
+const IP_ADDRESS_PARAMS: &[Param] = &[param("ip", ParamKind::IpAddress, "")];
+const VERBOSE_PARAMS: &[Param] = &[param("verbose", ParamKind::Bool, "false")];
+const HASH_PARAMS: &[Param] = &[param("hash", ParamKind::Hash, "")];
+
+/// `RpcApi` calls available in the console, in the order
+/// in which they are declared by the `RpcApi` trait.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RpcMethod {
+    Ping,
+    GetSystemInfo,
+    GetConnections,
+    GetMetrics,
+    GetServerInfo,
+    GetSyncStatus,
+    GetCurrentNetwork,
+    SubmitBlock,
+    GetBlockTemplate,
+    GetPeerAddresses,
+    GetSink,
+    GetMempoolEntry,
+    GetMempoolEntries,
+    GetConnectedPeerInfo,
+    AddPeer,
+    SubmitTransaction,
+    SubmitTransactionReplacement,
+    GetBlock,
+    GetSubnetwork,
+    GetVirtualChainFromBlock,
+    GetBlocks,
+    GetBlockCount,
+    GetBlockDagInfo,
+    ResolveFinalityConflict,
+    Shutdown,
+    GetHeaders,
+    GetBalanceByAddress,
+    GetBalancesByAddresses,
+    GetUtxosByAddresses,
+    GetSinkBlueScore,
+    Ban,
+    Unban,
+    GetInfo,
+    EstimateNetworkHashesPerSecond,
+    GetMempoolEntriesByAddresses,
+    GetCoinSupply,
+    GetDaaScoreTimestampEstimate,
+    GetFeeEstimate,
+    GetFeeEstimateExperimental,
+    GetCurrentBlockColor,
+}
+
+impl RpcMethod {
+    pub fn list() -> &'static [RpcMethod] {
+        use RpcMethod::*;
+        &[
+            Ping,
+            GetSystemInfo,
+            GetConnections,
+            GetMetrics,
+            GetServerInfo,
+            GetSyncStatus,
+            GetCurrentNetwork,
+            SubmitBlock,
+            GetBlockTemplate,
+            GetPeerAddresses,
+            GetSink,
+            GetMempoolEntry,
+            GetMempoolEntries,
+            GetConnectedPeerInfo,
+            AddPeer,
+            SubmitTransaction,
+            SubmitTransactionReplacement,
+            GetBlock,
+            GetSubnetwork,
+            GetVirtualChainFromBlock,
+            GetBlocks,
+            GetBlockCount,
+            GetBlockDagInfo,
+            ResolveFinalityConflict,
+            Shutdown,
+            GetHeaders,
+            GetBalanceByAddress,
+            GetBalancesByAddresses,
+            GetUtxosByAddresses,
+            GetSinkBlueScore,
+            Ban,
+            Unban,
+            GetInfo,
+            EstimateNetworkHashesPerSecond,
+            GetMempoolEntriesByAddresses,
+            GetCoinSupply,
+            GetDaaScoreTimestampEstimate,
+            GetFeeEstimate,
+            GetFeeEstimateExperimental,
+            GetCurrentBlockColor,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        use RpcMethod::*;
+        match self {
+            Ping => "ping",
+            GetSystemInfo => "get_system_info",
+            GetConnections => "get_connections",
+            GetMetrics => "get_metrics",
+            GetServerInfo => "get_server_info",
+            GetSyncStatus => "get_sync_status",
+            GetCurrentNetwork => "get_current_network",
+            SubmitBlock => "submit_block",
+            GetBlockTemplate => "get_block_template",
+            GetPeerAddresses => "get_peer_addresses",
+            GetSink => "get_sink",
+            GetMempoolEntry => "get_mempool_entry",
+            GetMempoolEntries => "get_mempool_entries",
+            GetConnectedPeerInfo => "get_connected_peer_info",
+            AddPeer => "add_peer",
+            SubmitTransaction => "submit_transaction",
+            SubmitTransactionReplacement => "submit_transaction_replacement",
+            GetBlock => "get_block",
+            GetSubnetwork => "get_subnetwork",
+            GetVirtualChainFromBlock => "get_virtual_chain_from_block",
+            GetBlocks => "get_blocks",
+            GetBlockCount => "get_block_count",
+            GetBlockDagInfo => "get_block_dag_info",
+            ResolveFinalityConflict => "resolve_finality_conflict",
+            Shutdown => "shutdown",
+            GetHeaders => "get_headers",
+            GetBalanceByAddress => "get_balance_by_address",
+            GetBalancesByAddresses => "get_balances_by_addresses",
+            GetUtxosByAddresses => "get_utxos_by_addresses",
+            GetSinkBlueScore => "get_sink_blue_score",
+            Ban => "ban",
+            Unban => "unban",
+            GetInfo => "get_info",
+            EstimateNetworkHashesPerSecond => "estimate_network_hashes_per_second",
+            GetMempoolEntriesByAddresses => "get_mempool_entries_by_addresses",
+            GetCoinSupply => "get_coin_supply",
+            GetDaaScoreTimestampEstimate => "get_daa_score_timestamp_estimate",
+            GetFeeEstimate => "get_fee_estimate",
+            GetFeeEstimateExperimental => "get_fee_estimate_experimental",
+            GetCurrentBlockColor => "get_current_block_color",
+        }
+    }
+
+    fn params(&self) -> &'static [Param] {
+        use RpcMethod::*;
+        match self {
+            GetConnections => CONNECTIONS_PARAMS,
+            GetMetrics => METRICS_PARAMS,
+            SubmitBlock => SUBMIT_BLOCK_PARAMS,
+            GetBlockTemplate => BLOCK_TEMPLATE_PARAMS,
+            GetMempoolEntry => MEMPOOL_ENTRY_PARAMS,
+            GetMempoolEntries => MEMPOOL_PARAMS,
+            AddPeer => ADD_PEER_PARAMS,
+            SubmitTransaction => SUBMIT_TRANSACTION_PARAMS,
+            SubmitTransactionReplacement => TRANSACTION_PARAMS,
+            GetBlock => BLOCK_PARAMS,
+            GetSubnetwork => SUBNETWORK_PARAMS,
+            GetVirtualChainFromBlock => VIRTUAL_CHAIN_PARAMS,
+            GetBlocks => BLOCKS_PARAMS,
+            ResolveFinalityConflict => FINALITY_CONFLICT_PARAMS,
+            GetHeaders => HEADERS_PARAMS,
+            GetBalanceByAddress => ADDRESS_PARAMS,
+            GetBalancesByAddresses | GetUtxosByAddresses => ADDRESSES_PARAMS,
+            Ban | Unban => IP_ADDRESS_PARAMS,
+            EstimateNetworkHashesPerSecond => HASHRATE_PARAMS,
+            GetMempoolEntriesByAddresses => MEMPOOL_BY_ADDRESSES_PARAMS,
+            GetDaaScoreTimestampEstimate => DAA_SCORE_PARAMS,
+            GetFeeEstimateExperimental => VERBOSE_PARAMS,
+            GetCurrentBlockColor => HASH_PARAMS,
+            _ => NO_PARAMS,
+        }
+    }
+}
+
+impl std::fmt::Display for RpcMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Parameter values of an RPC call, as entered by the user.
+#[derive(Debug, Clone, Default)]
+struct RpcArgs {
+    values: HashMap<&'static str, String>,
+}
+
+impl RpcArgs {
+    fn for_method(&self, method: RpcMethod) -> Self {
+        let values = method
+            .params()
+            .iter()
+            .map(|param| {
+                let value = self
+                    .values
+                    .get(param.name)
+                    .cloned()
+                    .unwrap_or_else(|| param.default.to_string());
+                (param.name, value)
+            })
+            .collect();
+        Self { values }
+    }
+
+    fn value_mut(&mut self, param: &Param) -> &mut String {
+        self.values
+            .entry(param.name)
+            .or_insert_with(|| param.default.to_string())
+    }
+
+    fn get(&self, name: &str) -> &str {
+        self.values
+            .get(name)
+            .map(|value| value.trim())
+            .unwrap_or_default()
+    }
+
+    fn list(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.get(name)
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|item| !item.is_empty())
+    }
+
+    fn bool(&self, name: &str) -> bool {
+        self.get(name) == "true"
+    }
+
+    fn hash(&self, name: &str) -> Result<RpcHash> {
+        let value = self.get(name);
+        RpcHash::from_str(value)
+            .map_err(|_| Error::custom(format!("{} `{name}`", i18n("Invalid hash"))))
+    }
+
+    fn optional_hash(&self, name: &str) -> Result<Option<RpcHash>> {
+        if self.get(name).is_empty() {
+            Ok(None)
+        } else {
+            self.hash(name).map(Some)
+        }
+    }
+
+    fn address(&self, name: &str) -> Result<Address> {
+        Ok(Address::try_from(self.get(name))?)
+    }
+
+    fn addresses(&self, name: &str) -> Result<Vec<Address>> {
+        let addresses = self
+            .list(name)
+            .map(Address::try_from)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        if addresses.is_empty() {
+            return Err(Error::custom(format!(
+                "{} `{name}`",
+                i18n("Missing parameter")
+            )));
+        }
+        Ok(addresses)
+    }
+
+    fn u32(&self, name: &str) -> Result<u32> {
+        Ok(self.get(name).parse()?)
+    }
+
+    fn u64(&self, name: &str) -> Result<u64> {
+        Ok(self.get(name).parse()?)
+    }
+
+    /// Parse a value using its `FromStr` implementation
+    /// (peer addresses, IP addresses, subnetwork ids).
+    fn parse<T: FromStr>(&self, name: &str) -> Result<T> {
+        self.get(name)
+            .parse()
+            .map_err(|_| Error::custom(format!("{} `{name}`", i18n("Invalid parameter"))))
+    }
+
+    /// Deserialize a JSON value (blocks, transactions).
+    fn json<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<T> {
+        serde_json::from_str(self.get(name))
+            .map_err(|err| Error::custom(format!("{} `{name}`: {err}", i18n("Invalid JSON"))))
+    }
+
+    fn u64_list(&self, name: &str) -> Result<Vec<u64>> {
+        Ok(self
+            .list(name)
+            .map(|value| value.parse::<u64>())
+            .collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// JSON representation of the call parameters.
+    fn to_json(&self, method: RpcMethod) -> Value {
+        let params = method
+            .params()
+            .iter()
+            .map(|param| {
+                let value = self.get(param.name);
+                let value = match param.kind {
+                    ParamKind::Bool => json!(self.bool(param.name)),
+                    ParamKind::U32 => value
+                        .parse::<u32>()
+                        .map(Value::from)
+                        .unwrap_or_else(|_| json!(value)),
+                    ParamKind::U64 => value
+                        .parse::<u64>()
+                        .map(Value::from)
+                        .unwrap_or_else(|_| json!(value)),
+                    ParamKind::Json => serde_json::from_str(value).unwrap_or_else(|_| json!(value)),
+                    ParamKind::OptionalHash if value.is_empty() => Value::Null,
+                    ParamKind::Addresses => json!(self.list(param.name).collect::<Vec<_>>()),
+                    ParamKind::U64List => json!(self
+                        .list(param.name)
+                        .map(|value| value
+                            .parse::<u64>()
+                            .map(Value::from)
+                            .unwrap_or_else(|_| json!(value)))
+                        .collect::<Vec<_>>()),
+                    _ => json!(value),
+                };
+                (param.name.to_string(), value)
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        Value::Object(params)
+    }
+}
+
+async fn execute(rpc_api: Arc<DynRpcApi>, method: RpcMethod, args: &RpcArgs) -> Result<Value> {
+    use RpcMethod::*;
+
+    let value = match method {
+        Ping => serde_json::to_value(rpc_api.ping().await?)?,
+        GetSystemInfo => serde_json::to_value(rpc_api.get_system_info().await?)?,
+        GetConnections => serde_json::to_value(
+            rpc_api
+                .get_connections(args.bool("include_profile_data"))
+                .await?,
+        )?,
+        GetMetrics => serde_json::to_value(
+            rpc_api
+                .get_metrics(
+                    args.bool("include_process_metrics"),
+                    args.bool("include_connection_metrics"),
+                    args.bool("include_bandwidth_metrics"),
+                    args.bool("include_consensus_metrics"),
+                    args.bool("include_storage_metrics"),
+                    args.bool("include_custom_metrics"),
+                )
+                .await?,
+        )?,
+        GetCurrentNetwork => serde_json::to_value(rpc_api.get_current_network().await?)?,
+        SubmitBlock => serde_json::to_value(
+            rpc_api
+                .submit_block(args.json("block")?, args.bool("allow_non_daa_blocks"))
+                .await?,
+        )?,
+        GetBlockTemplate => serde_json::to_value(
+            rpc_api
+                .get_block_template(
+                    args.address("pay_address")?,
+                    args.get("extra_data").as_bytes().to_vec(),
+                )
+                .await?,
+        )?,
+        AddPeer => serde_json::to_value(
+            rpc_api
+                .add_peer(args.parse("peer_address")?, args.bool("is_permanent"))
+                .await?,
+        )?,
+        SubmitTransaction => serde_json::to_value(
+            rpc_api
+                .submit_transaction(args.json("transaction")?, args.bool("allow_orphan"))
+                .await?,
+        )?,
+        SubmitTransactionReplacement => serde_json::to_value(
+            rpc_api
+                .submit_transaction_replacement(args.json("transaction")?)
+                .await?,
+        )?,
+        GetSubnetwork => {
+            serde_json::to_value(rpc_api.get_subnetwork(args.parse("subnetwork_id")?).await?)?
+        }
+        ResolveFinalityConflict => serde_json::to_value(
+            rpc_api
+                .resolve_finality_conflict(args.hash("finality_block_hash")?)
+                .await?,
+        )?,
+        Shutdown => serde_json::to_value(rpc_api.shutdown().await?)?,
+        GetHeaders => serde_json::to_value(
+            rpc_api
+                .get_headers(
+                    args.hash("start_hash")?,
+                    args.u64("limit")?,
+                    args.bool("is_ascending"),
+                )
+                .await?,
+        )?,
+        Ban => serde_json::to_value(rpc_api.ban(args.parse("ip")?).await?)?,
+        Unban => serde_json::to_value(rpc_api.unban(args.parse("ip")?).await?)?,
+        GetFeeEstimateExperimental => serde_json::to_value(
+            rpc_api
+                .get_fee_estimate_experimental(args.bool("verbose"))
+                .await?,
+        )?,
+        GetCurrentBlockColor => {
+            serde_json::to_value(rpc_api.get_current_block_color(args.hash("hash")?).await?)?
+        }
+        GetInfo => serde_json::to_value(rpc_api.get_info().await?)?,
+        GetServerInfo => serde_json::to_value(rpc_api.get_server_info().await?)?,
+        GetBlockDagInfo => serde_json::to_value(rpc_api.get_block_dag_info().await?)?,
+        GetBlockCount => serde_json::to_value(rpc_api.get_block_count().await?)?,
+        GetSyncStatus => json!(rpc_api.get_sync_status().await?),
+        GetConnectedPeerInfo => serde_json::to_value(rpc_api.get_connected_peer_info().await?)?,
+        GetPeerAddresses => serde_json::to_value(rpc_api.get_peer_addresses().await?)?,
+        GetCoinSupply => serde_json::to_value(rpc_api.get_coin_supply().await?)?,
+        GetSink => serde_json::to_value(rpc_api.get_sink().await?)?,
+        GetSinkBlueScore => json!(rpc_api.get_sink_blue_score().await?),
+        GetFeeEstimate => serde_json::to_value(rpc_api.get_fee_estimate().await?)?,
+        GetMempoolEntries => serde_json::to_value(
+            rpc_api
+                .get_mempool_entries(
+                    args.bool("include_orphan_pool"),
+                    args.bool("filter_transaction_pool"),
+                )
+                .await?,
+        )?,
+        GetMempoolEntry => serde_json::to_value(
+            rpc_api
+                .get_mempool_entry(
+                    args.hash("transaction_id")?,
+                    args.bool("include_orphan_pool"),
+                    args.bool("filter_transaction_pool"),
+                )
+                .await?,
+        )?,
+        GetMempoolEntriesByAddresses => serde_json::to_value(
+            rpc_api
+                .get_mempool_entries_by_addresses(
+                    args.addresses("addresses")?,
+                    args.bool("include_orphan_pool"),
+                    args.bool("filter_transaction_pool"),
+                )
+                .await?,
+        )?,
+        GetBlock => serde_json::to_value(
+            rpc_api
+                .get_block(args.hash("hash")?, args.bool("include_transactions"))
+                .await?,
+        )?,
+        GetBlocks => serde_json::to_value(
+            rpc_api
+                .get_blocks(
+                    args.optional_hash("low_hash")?,
+                    args.bool("include_blocks"),
+                    args.bool("include_transactions"),
+                )
+                .await?,
+        )?,
+        GetBalanceByAddress => json!(
+            rpc_api
+                .get_balance_by_address(args.address("address")?)
+                .await?
+        ),
+        GetBalancesByAddresses => serde_json::to_value(
+            rpc_api
+                .get_balances_by_addresses(args.addresses("addresses")?)
+                .await?,
+        )?,
+        GetUtxosByAddresses => serde_json::to_value(
+            rpc_api
+                .get_utxos_by_addresses(args.addresses("addresses")?)
+                .await?,
+        )?,
+        EstimateNetworkHashesPerSecond => json!(
+            rpc_api
+                .estimate_network_hashes_per_second(
+                    args.u32("window_size")?,
+                    args.optional_hash("start_hash")?
+                )
+                .await?
+        ),
+        GetVirtualChainFromBlock => serde_json::to_value(
+            rpc_api
+                .get_virtual_chain_from_block(
+                    args.hash("start_hash")?,
+                    args.bool("include_accepted_transaction_ids"),
+                )
+                .await?,
+        )?,
+        GetDaaScoreTimestampEstimate => serde_json::to_value(
+            rpc_api
+                .get_daa_score_timestamp_estimate(args.u64_list("daa_scores")?)
+                .await?,
+        )?,
+    };
+
+    Ok(value)
+}
+
+/// Completed RPC call retained in the console history.
+#[derive(Debug, Clone)]
+struct RpcCall {
+    method: RpcMethod,
+    args: RpcArgs,
+    timestamp: DateTime<Local>,
+    elapsed: Duration,
+    result: std::result::Result<String, String>,
+    /// Byte ranges of the response lines, used to
+    /// render only the lines that are visible.
+    lines: Vec<std::ops::Range<usize>>,
+}
+
+impl RpcCall {
+    /// Request and response of the call as pretty-printed JSON.
+    fn to_json(&self) -> String {
+        let mut call = json!({
+            "method": self.method.name(),
+            "params": self.args.to_json(self.method),
+        });
+
+        match &self.result {
+            Ok(response) => {
+                call["response"] = serde_json::from_str(response).unwrap_or(Value::Null);
+            }
+            Err(error) => {
+                call["error"] = json!(error);
+            }
+        }
+
+        serde_json::to_string_pretty(&call).unwrap_or_default()
+    }
+}
+
+pub struct RpcConsole {
+    #[allow(dead_code)]
+    runtime: Runtime,
+    method: RpcMethod,
+    args: RpcArgs,
+    history: VecDeque<RpcCall>,
+    selected: Option<usize>,
+    pending: bool,
+}
+
+impl RpcConsole {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            method: RpcMethod::GetBlockDagInfo,
+            args: RpcArgs::default(),
+            history: VecDeque::new(),
+            selected: None,
+            pending: false,
+        }
+    }
+
+    fn execute(&mut self, rpc_api: Arc<DynRpcApi>, method: RpcMethod, args: RpcArgs) {
+        let rpc_result = Payload::<Result<RpcCall>>::new("rpc_console_result");
+        if rpc_result.is_pending() {
+            return;
+        }
+
+        self.pending = true;
+        spawn_with_result(&rpc_result, async move {
+            let timestamp = Local::now();
+            let start = Instant::now();
+            let result = execute(rpc_api, method, &args)
+                .await
+                .and_then(|value| Ok(serde_json::to_string_pretty(&value)?))
+                .map_err(|err| err.to_string());
+
+            let lines = result
+                .as_ref()
+                .map(|response| {
+                    let mut start = 0;
+                    response
+                        .split('\n')
+                        .map(|line| {
+                            let range = start..start + line.len();
+                            start = range.end + 1;
+                            range
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Ok(RpcCall {
+                method,
+                args,
+                timestamp,
+                elapsed: start.elapsed(),
+                result,
+                lines,
+            })
+        });
+    }
+
+    fn push(&mut self, call: RpcCall) {
+        if self.history.len() >= MAX_HISTORY_ENTRIES {
+            self.history.pop_front();
+        }
+        self.history.push_back(call);
+        self.selected = Some(self.history.len() - 1);
+    }
+
+    fn copy(ui: &mut Ui, text: String) {
+        ui.output_mut(|o| o.copied_text = text);
+        runtime().notify_clipboard(i18n("Copied to clipboard"));
+    }
+}
+
+impl ModuleT for RpcConsole {
+    fn style(&self) -> ModuleStyle {
+        ModuleStyle::Default
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        _ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+        use egui_phosphor::light::{ARROW_CLOCKWISE, CLIPBOARD_TEXT};
+
+        let rpc_result = Payload::<Result<RpcCall>>::new("rpc_console_result");
+        if let Some(result) = rpc_result.take() {
+            self.pending = false;
+            match result {
+                Ok(call) => self.push(call),
+                Err(err) => runtime().notify(UserNotification::error(err.to_string())),
+            }
+        }
+
+        let rpc_api = self
+            .runtime
+            .kaspa_service()
+            .rpc_api()
+            .filter(|_| core.state().is_connected());
+
+        ui.heading(i18n("RPC Console"));
+        ui.separator();
+
+        let mut execute = None;
+
+        ui.horizontal(|ui| {
+            ui.label(i18n("Method:"));
+            egui::ComboBox::from_id_source("rpc_console_method")
+                .selected_text(self.method.name())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                    RpcMethod::list().iter().for_each(|method| {
+                        ui.selectable_value(&mut self.method, *method, method.name());
+                    });
+                });
+        });
+
+        let params = self.method.params();
+        if !params.is_empty() {
+            Grid::new("rpc_console_params")
+                .num_columns(2)
+                .spacing([16.0, 4.0])
+                .show(ui, |ui| {
+                    params.iter().for_each(|param| {
+                        ui.label(param.name);
+                        let value = self.args.value_mut(param);
+                        match param.kind {
+                            ParamKind::Bool => {
+                                let mut checked = value == "true";
+                                if ui.checkbox(&mut checked, "").changed() {
+                                    *value = checked.to_string();
+                                }
+                            }
+                            ParamKind::Addresses | ParamKind::U64List | ParamKind::Json => {
+                                ui.add(
+                                    TextEdit::multiline(value)
+                                        .hint_text(param.kind.hint())
+                                        .desired_rows(2)
+                                        .desired_width(f32::INFINITY),
+                                );
+                            }
+                            _ => {
+                                ui.add(
+                                    TextEdit::singleline(value)
+                                        .hint_text(param.kind.hint())
+                                        .desired_width(f32::INFINITY),
+                                );
+                            }
+                        }
+                        ui.end_row();
+                    });
+                });
+        }
+
+        ui.add_space(4.);
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    rpc_api.is_some() && !self.pending,
+                    Button::new(i18n("Execute")),
+                )
+                .clicked()
+            {
+                execute = Some((self.method, self.args.for_method(self.method)));
+            }
+
+            if self.pending {
+                ui.spinner();
+            } else if rpc_api.is_none() {
+                ui.colored_label(theme_color().warning_color, i18n("Not connected"));
+            }
+        });
+
+        ui.separator();
+
+        if !self.history.is_empty() {
+            let mut selected = self.selected;
+            let mut rerun = None;
+
+            CollapsingHeader::new(i18n("History"))
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .id_source("rpc_console_history")
+                        .max_height(HISTORY_MAX_HEIGHT)
+                        .auto_shrink([false, true])
+                        .show(ui, |ui| {
+                            for (index, call) in self.history.iter().enumerate().rev() {
+                                ui.horizontal(|ui| {
+                                    let text = format!(
+                                        "{}  {}  ({} ms)",
+                                        call.timestamp.format("%H:%M:%S"),
+                                        call.method.name(),
+                                        call.elapsed.as_millis()
+                                    );
+                                    let text = if call.result.is_ok() {
+                                        RichText::new(text)
+                                    } else {
+                                        RichText::new(text).color(theme_color().error_color)
+                                    };
+
+                                    if ui.selectable_label(selected == Some(index), text).clicked()
+                                    {
+                                        selected = Some(index);
+                                    }
+
+                                    if ui
+                                        .add_enabled(
+                                            rpc_api.is_some() && !self.pending,
+                                            Button::new(ARROW_CLOCKWISE),
+                                        )
+                                        .on_hover_text_at_pointer(i18n("Run again"))
+                                        .clicked()
+                                    {
+                                        rerun = Some((call.method, call.args.clone()));
+                                    }
+
+                                    if ui
+                                        .button(CLIPBOARD_TEXT)
+                                        .on_hover_text_at_pointer(i18n("Copy as JSON"))
+                                        .clicked()
+                                    {
+                                        Self::copy(ui, call.to_json());
+                                    }
+                                });
+                            }
+                        });
+                });
+
+            self.selected = selected;
+            if let Some((method, args)) = rerun {
+                self.method = method;
+                self.args.values.extend(args.values.clone());
+                execute = Some((method, args));
+            }
+        }
+
+        if let Some(call) = self.selected.and_then(|index| self.history.get(index)) {
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(call.method.name()).strong());
+                if let Ok(response) = &call.result {
+                    if ui
+                        .button(format!("{CLIPBOARD_TEXT} {}", i18n("Copy response")))
+                        .clicked()
+                    {
+                        Self::copy(ui, response.clone());
+                    }
+                }
+            });
+
+            match &call.result {
+                Ok(response) => {
+                    // responses can be very large; only the visible lines are laid out
+                    let row_height = ui.text_style_height(&TextStyle::Monospace);
+                    egui::ScrollArea::both()
+                        .id_source("rpc_console_response")
+                        .auto_shrink([false; 2])
+                        .show_rows(ui, row_height, call.lines.len(), |ui, rows| {
+                            ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                            for line in call.lines[rows].iter() {
+                                ui.label(RichText::new(&response[line.clone()]).monospace());
+                            }
+                        });
+                }
+                Err(error) => {
+                    ui.colored_label(theme_color().error_color, error);
+                }
+            }
+        }
+
+        if let (Some((method, args)), Some(rpc_api)) = (execute, rpc_api) {
+            self.execute(rpc_api, method, args);
+        }
+    }
+}
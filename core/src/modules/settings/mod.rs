@@ -1,4 +1,8 @@
 use crate::imports::*;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::settings::{parse_peer_list, MAX_P2P_INBOUND_LIMIT, MAX_P2P_OUTBOUND_TARGET};
+#[cfg(target_os = "linux")]
+use crate::settings::NodeIoPriority;
 
 pub struct Settings {
     #[allow(dead_code)]
@@ -7,6 +11,9 @@ pub struct Settings {
     wrpc_borsh_network_interface : NetworkInterfaceEditor,
     wrpc_json_network_interface : NetworkInterfaceEditor,
     grpc_network_interface : NetworkInterfaceEditor,
+    p2p_network_interface : NetworkInterfaceEditor,
+    p2p_peers : String,
+    fixed_price_currency : String,
     reset_settings : bool,
 }
 
@@ -18,6 +25,9 @@ impl Settings {
             wrpc_borsh_network_interface : NetworkInterfaceEditor::default(),
             wrpc_json_network_interface : NetworkInterfaceEditor::default(),
             grpc_network_interface : NetworkInterfaceEditor::default(),
+            p2p_network_interface : NetworkInterfaceEditor::default(),
+            p2p_peers : String::default(),
+            fixed_price_currency : String::default(),
             reset_settings : false,
         }
     }
@@ -27,11 +37,20 @@ impl Settings {
 
         self.wrpc_borsh_network_interface = NetworkInterfaceEditor::from(&self.settings.node.wrpc_borsh_network_interface);
         self.wrpc_json_network_interface = NetworkInterfaceEditor::from(&self.settings.node.wrpc_json_network_interface);
-        self.grpc_network_interface = NetworkInterfaceEditor::from(&self.settings.node.grpc_network_interface);
+        self.reset_node_editors();
     }
 
-    pub fn change_current_network(&mut self, network : Network) {
-        self.settings.node.network = network;
+    /// Switch the network of the settings being edited without
+    /// discarding unsaved changes in other sections.
+    pub fn change_network(&mut self, network : Network) {
+        self.settings.change_network(network);
+        self.reset_node_editors();
+    }
+
+    fn reset_node_editors(&mut self) {
+        self.grpc_network_interface = NetworkInterfaceEditor::from(&self.settings.node.grpc_network_interface);
+        self.p2p_network_interface = NetworkInterfaceEditor::from(&self.settings.node.p2p_network_interface);
+        self.p2p_peers = self.settings.node.p2p_peers.iter().map(|peer| peer.to_string()).collect::<Vec<_>>().join(" ");
     }
 
     pub fn render_remote_settings(_core: &mut Core, ui: &mut Ui, settings : &mut NodeSettings) -> Option<&'static str> {
@@ -159,9 +178,13 @@ impl Settings {
                     .default_open(true)
                     .show(ui, |ui| {
                         ui.horizontal_wrapped(|ui|{
-                            Network::iter().for_each(|network| {
-                                ui.radio_value(&mut self.settings.node.network, *network, network.name());
+                            let mut network = self.settings.node.network;
+                            Network::iter().for_each(|kind| {
+                                ui.radio_value(&mut network, *kind, kind.name());
                             });
+                            if network != self.settings.node.network {
+                                self.change_network(network);
+                            }
                         });
                     });
 
@@ -213,6 +236,10 @@ impl Settings {
                             _ => { }
                         }
 
+                        if self.settings.node.node_kind.is_background_capable() {
+                            ui.checkbox(&mut self.settings.node.run_in_background, i18n("Keep the node running when switching to a different network"));
+                        }
+
                         #[cfg(not(target_arch = "wasm32"))]
                         if self.settings.node.node_kind.is_config_capable() {
 
@@ -228,6 +255,49 @@ impl Settings {
                                 });
                         }
 
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if self.settings.node.node_kind.is_config_capable() && self.settings.node.node_kind != KaspadNodeKind::IntegratedInProc {
+                            CollapsingHeader::new(i18n("Process Controls"))
+                                .default_open(false)
+                                .show(ui, |ui| {
+                                    let process = &mut self.settings.node.process;
+
+                                    ui.horizontal(|ui| {
+                                        ui.checkbox(&mut process.enable_nice, i18n("Process priority (nice):"));
+                                        ui.add_enabled(process.enable_nice, Slider::new(&mut process.nice, 0..=19));
+                                    });
+
+                                    #[cfg(target_os = "linux")] {
+                                        ui.horizontal_wrapped(|ui| {
+                                            ui.label(i18n("IO priority:"));
+                                            NodeIoPriority::iter().for_each(|priority| {
+                                                ui.radio_value(&mut process.io_priority, *priority, priority.to_string());
+                                            });
+                                        });
+                                        ui.label(process.io_priority.describe());
+
+                                        ui.horizontal(|ui| {
+                                            ui.checkbox(&mut process.enable_cpu_affinity, i18n("CPU cores:"));
+                                            ui.add_enabled(process.enable_cpu_affinity, TextEdit::singleline(&mut process.cpu_affinity).hint_text("0-3,6").desired_width(120.));
+                                        });
+                                        if process.enable_cpu_affinity {
+                                            if let Err(err) = process.cpu_affinity_cores() {
+                                                ui.colored_label(theme_color().error_color, err.to_string());
+                                                node_settings_error = Some(i18n("Invalid CPU core list"));
+                                            }
+                                        }
+
+                                        ui.horizontal(|ui| {
+                                            ui.checkbox(&mut process.enable_memory_limit, i18n("Memory limit (MB):"));
+                                            ui.add_enabled(process.enable_memory_limit, Slider::new(&mut process.memory_limit, 1024..=128*1024).logarithmic(true));
+                                        });
+                                        if process.enable_memory_limit {
+                                            ui.label(i18n("The memory limit is enforced using cgroup v2 and requires the memory controller to be delegated to the user. The node is terminated by the system if it exceeds the limit."));
+                                        }
+                                    }
+                                });
+                        }
+
                         #[cfg(not(target_arch = "wasm32"))]
                         if self.settings.node.node_kind.is_config_capable() {
                             CollapsingHeader::new(i18n("Data Storage"))
@@ -335,6 +405,31 @@ impl Settings {
                     self.settings.node.grpc_network_interface = self.grpc_network_interface.as_ref().try_into().unwrap(); //NetworkInterfaceConfig::try_from(&self.grpc_network_interface).unwrap();
                 }
 
+                #[cfg(not(target_arch = "wasm32"))]
+                if self.settings.node.node_kind.is_config_capable() {
+                    if !self.p2p_network_interface.is_valid() {
+                        node_settings_error = Some(i18n("Invalid p2p network interface configuration"));
+                    } else {
+                        self.settings.node.p2p_network_interface = self.p2p_network_interface.as_ref().try_into().unwrap();
+                    }
+
+                    // the peer list is only shown and used in the peer list modes
+                    if self.settings.node.p2p_peer_mode != NodePeerMode::Default {
+                        match parse_peer_list(self.p2p_peers.as_str()) {
+                            Ok(peers) => {
+                                self.settings.node.p2p_peers = peers;
+                            }
+                            Err(_) => {
+                                node_settings_error = Some(i18n("Invalid peer address"));
+                            }
+                        }
+                    }
+
+                    if node_settings_error.is_none() {
+                        node_settings_error = self.settings.node.p2p_settings_error();
+                    }
+                }
+
                 if self.settings.node.node_kind == KaspadNodeKind::Remote {
                     node_settings_error = Self::render_remote_settings(core, ui, &mut self.settings.node);
                 }
@@ -342,6 +437,35 @@ impl Settings {
                 #[cfg(not(target_arch = "wasm32"))]
                 if self.settings.node.node_kind.is_config_capable() {
 
+                    let port_conflicts = core.state().port_conflicts().to_vec();
+                    if !port_conflicts.is_empty() {
+                        CollapsingHeader::new(i18n("Port Conflicts"))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                ui.vertical(|ui|{
+                                    ui.label(
+                                        RichText::new(i18n("The node can not be started because the following ports are in use:"))
+                                            .color(theme_color().warning_color)
+                                    );
+                                    ui.add_space(4.);
+                                    for conflict in port_conflicts.iter() {
+                                        if let Some(port) = conflict.alternative {
+                                            ui.label(format!("{conflict} - {} {port}", i18n("alternative port:")));
+                                        } else {
+                                            ui.label(format!("{conflict} - {}", i18n("no alternative port available")));
+                                        }
+                                    }
+                                    ui.add_space(4.);
+                                    if port_conflicts.iter().any(|conflict| conflict.alternative.is_some()) && ui.medium_button(i18n("Use Alternative Ports")).clicked() {
+                                        for conflict in port_conflicts.iter() {
+                                            conflict.apply_alternative(&mut self.settings.node);
+                                        }
+                                        self.reset_node_editors();
+                                    }
+                                });
+                            });
+                    }
+
                     CollapsingHeader::new(i18n("Local p2p Node Configuration"))
                         .default_open(true)
                         .show(ui, |ui| {
@@ -352,6 +476,14 @@ impl Settings {
                                         ui.vertical(|ui|{
 
                                             ui.checkbox(&mut self.settings.node.enable_wrpc_borsh, i18n("Public wRPC (Borsh)"));
+                                            if self.settings.node.wrpc_borsh_network_interface.kind == NetworkInterfaceKind::Custom {
+                                                ui.horizontal(|ui| {
+                                                    ui.label(format!("{} {}", i18n("wRPC (Borsh) interface:"), self.settings.node.wrpc_borsh_network_interface));
+                                                    if ui.small_button(i18n("Reset")).clicked() {
+                                                        self.settings.node.wrpc_borsh_network_interface = NetworkInterfaceConfig::default();
+                                                    }
+                                                });
+                                            }
 
                                             // ui.checkbox(&mut self.settings.node.enable_wrpc_json, i18n("Enable wRPC JSON"));
                                             // if self.settings.node.enable_wrpc_json {
@@ -380,6 +512,48 @@ impl Settings {
                                     .show(ui, |ui| {
                                         ui.vertical(|ui|{
                                             ui.checkbox(&mut self.settings.node.enable_upnp, i18n("Enable UPnP"));
+
+                                            CollapsingHeader::new(i18n("p2p Network Interface & Port"))
+                                                .default_open(false)
+                                                .show(ui, |ui| {
+                                                    self.p2p_network_interface.ui(ui);
+                                                });
+
+                                            ui.add_space(4.);
+                                            ui.horizontal(|ui| {
+                                                ui.label(i18n("Outbound peer target:"));
+                                                ui.add(Slider::new(&mut self.settings.node.p2p_outbound_target, 0..=MAX_P2P_OUTBOUND_TARGET));
+                                            });
+                                            ui.horizontal(|ui| {
+                                                ui.label(i18n("Inbound peer limit:"));
+                                                ui.add(Slider::new(&mut self.settings.node.p2p_inbound_limit, 0..=MAX_P2P_INBOUND_LIMIT).logarithmic(true));
+                                            });
+
+                                            ui.add_space(4.);
+                                            ui.horizontal_wrapped(|ui| {
+                                                ui.label(i18n("Peers:"));
+                                                NodePeerMode::iter().for_each(|mode| {
+                                                    ui.radio_value(&mut self.settings.node.p2p_peer_mode, *mode, mode.to_string());
+                                                });
+                                            });
+                                            ui.label(self.settings.node.p2p_peer_mode.describe());
+                                            if self.settings.node.p2p_peer_mode != NodePeerMode::Default {
+                                                ui.add_space(4.);
+                                                ui.add(TextEdit::multiline(&mut self.p2p_peers)
+                                                    .hint_text(i18n("Peer addresses (ip:port), separated by spaces or new lines..."))
+                                                    .desired_rows(3)
+                                                    .desired_width(f32::INFINITY)
+                                                );
+                                            }
+
+                                            ui.add_space(4.);
+                                            ui.checkbox(&mut self.settings.node.archival, i18n("Archival mode"));
+                                            if self.settings.node.archival {
+                                                ui.label(
+                                                    RichText::new(i18n("An archival node keeps the entire block history and requires significantly more disk space."))
+                                                        .color(theme_color().warning_color)
+                                                );
+                                            }
                                         });
                                     });
                                 });
@@ -401,8 +575,8 @@ impl Settings {
 
                 if let Some(response) = ui.confirm_medium_cancel(Align::Max) {
                     if matches!(response, Confirm::Nack) {
-                        self.settings.node = core.settings.node.clone();
-                        self.grpc_network_interface = NetworkInterfaceEditor::from(&self.settings.node.grpc_network_interface);
+                        self.settings = core.settings.clone();
+                        self.reset_node_editors();
                     }
                 }
 
@@ -432,7 +606,7 @@ impl Settings {
                             },
                             Confirm::Nack => {
                                 self.settings = core.settings.clone();
-                                self.grpc_network_interface = NetworkInterfaceEditor::from(&self.settings.node.grpc_network_interface);
+                                self.reset_node_editors();
                             }
                         }
                     }
@@ -585,6 +759,234 @@ impl Settings {
 
     }
 
+    fn render_market_currencies(
+        &mut self,
+        core: &mut Core,
+        ui: &mut egui::Ui,
+    ) {
+        use egui_phosphor::light::{ARROW_LEFT, X};
+
+        let mut currencies = self.settings.market.currencies.clone();
+        let mut promote = None;
+        let mut remove = None;
+        let mut add = None;
+
+        ui.add_space(4.);
+        ui.label(i18n("Display currencies (the first currency is the primary currency):"));
+        ui.horizontal_wrapped(|ui| {
+            for (index, currency) in currencies.iter().enumerate() {
+                if index == 0 {
+                    ui.label(RichText::new(currency.to_uppercase()).strong());
+                } else {
+                    ui.label(currency.to_uppercase());
+                    if ui.small_button(ARROW_LEFT).on_hover_text(i18n("Make primary")).clicked() {
+                        promote = Some(index);
+                    }
+                }
+                if currencies.len() > 1 && ui.small_button(X).on_hover_text(i18n("Remove")).clicked() {
+                    remove = Some(index);
+                }
+                ui.separator();
+            }
+
+            if let Some(available) = self.runtime.market_monitor_service().available_currencies() {
+                egui::ComboBox::from_id_source("market_currency_selector")
+                    .selected_text(i18n("Add currency"))
+                    .show_ui(ui, |ui| {
+                        for descriptor in available.iter().filter(|descriptor| !currencies.contains(&descriptor.id)) {
+                            if ui.selectable_label(false, descriptor.name.as_str()).clicked() {
+                                add = Some(descriptor.id.clone());
+                            }
+                        }
+                    });
+            } else {
+                ui.label(i18n("Loading available currencies..."));
+            }
+        });
+
+        if let Some(index) = promote {
+            let currency = currencies.remove(index);
+            currencies.insert(0, currency);
+        } else if let Some(index) = remove {
+            currencies.remove(index);
+        } else if let Some(currency) = add {
+            currencies.push(currency);
+        }
+
+        if currencies != self.settings.market.currencies {
+            self.settings.market.currencies = currencies.clone();
+            core.settings.market.currencies = currencies.clone();
+            self.runtime.market_monitor_service().set_currencies(currencies);
+            core.store_settings();
+        }
+    }
+
+    fn render_market_providers(
+        &mut self,
+        core: &mut Core,
+        ui: &mut egui::Ui,
+    ) {
+        use egui_phosphor::light::{ARROW_DOWN, ARROW_UP, X};
+
+        let active_provider = self.runtime.market_monitor_service().active_provider();
+        let count = self.settings.market.providers.len();
+        let mut move_up = None;
+        let mut move_down = None;
+        let mut remove = None;
+
+        ui.add_space(8.);
+        ui.label(i18n("Market data providers (in failover order):"));
+
+        for (index, provider) in self.settings.market.providers.iter_mut().enumerate() {
+            let title = provider.title();
+            ui.horizontal(|ui| {
+                let text = RichText::new(format!("{}. {title}", index + 1));
+                if active_provider.as_deref() == Some(provider.id().as_str()) {
+                    ui.label(text.strong()).on_hover_text(i18n("Supplying current market data"));
+                } else {
+                    ui.label(text);
+                }
+                if ui.add_enabled(index > 0, Button::new(ARROW_UP).small()).clicked() {
+                    move_up = Some(index);
+                }
+                if ui.add_enabled(index + 1 < count, Button::new(ARROW_DOWN).small()).clicked() {
+                    move_down = Some(index);
+                }
+                if ui.add_enabled(count > 1, Button::new(X).small()).on_hover_text(i18n("Remove")).clicked() {
+                    remove = Some(index);
+                }
+            });
+
+            match provider {
+                MarketDataProviderConfig::JsonEndpoint(config) => {
+                    Grid::new(format!("market_json_endpoint_{index}"))
+                        .num_columns(2)
+                        .spacing([16.0,4.0])
+                        .show(ui, |ui| {
+                            let fields = [
+                                (i18n("Name"), &mut config.name),
+                                (i18n("URL"), &mut config.url),
+                                (i18n("Price path"), &mut config.price),
+                                (i18n("Market cap path"), &mut config.market_cap),
+                                (i18n("Volume path"), &mut config.volume),
+                                (i18n("24h change path"), &mut config.change),
+                            ];
+                            for (label, value) in fields {
+                                ui.label(label);
+                                ui.add(TextEdit::singleline(value).desired_width(320.));
+                                ui.end_row();
+                            }
+                        });
+                    ui.label(RichText::new(i18n("Use {currency} in the URL and paths for the currency ticker, paths are dot-separated keys or indices (e.g. data.0.price)")).small());
+                }
+                MarketDataProviderConfig::FixedPrice(config) => {
+                    let mut remove_currency = None;
+                    Grid::new(format!("market_fixed_price_{index}"))
+                        .num_columns(3)
+                        .spacing([16.0,4.0])
+                        .show(ui, |ui| {
+                            for (currency, price) in config.prices.iter_mut() {
+                                ui.label(currency.to_uppercase());
+                                ui.add(DragValue::new(price).speed(0.0001).min_decimals(2).max_decimals(8).clamp_range(0.0..=f64::MAX));
+                                if ui.small_button(X).clicked() {
+                                    remove_currency = Some(currency.clone());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    if let Some(currency) = remove_currency {
+                        config.prices.remove(&currency);
+                    }
+                    ui.horizontal(|ui| {
+                        ui.add(TextEdit::singleline(&mut self.fixed_price_currency).hint_text(i18n("Currency")).desired_width(64.));
+                        let currency = self.fixed_price_currency.trim().to_lowercase();
+                        if ui.add_enabled(!currency.is_empty(), Button::new(i18n("Add")).small()).clicked() {
+                            config.prices.entry(currency).or_insert(0.0);
+                            self.fixed_price_currency.clear();
+                        }
+                    });
+                }
+                _ => { }
+            }
+        }
+
+        if let Some(index) = move_up {
+            self.settings.market.providers.swap(index, index - 1);
+        } else if let Some(index) = move_down {
+            self.settings.market.providers.swap(index, index + 1);
+        } else if let Some(index) = remove {
+            self.settings.market.providers.remove(index);
+        }
+
+        ui.add_space(4.);
+        ui.horizontal_wrapped(|ui| {
+            ui.label(i18n("Add provider:"));
+            let providers = &mut self.settings.market.providers;
+            if !providers.contains(&MarketDataProviderConfig::CoinGecko) && ui.small_button("CoinGecko").clicked() {
+                providers.push(MarketDataProviderConfig::CoinGecko);
+            }
+            if !providers.contains(&MarketDataProviderConfig::CoinMarketCap) && ui.small_button("CoinMarketCap").clicked() {
+                providers.push(MarketDataProviderConfig::CoinMarketCap);
+            }
+            if ui.small_button(i18n("JSON Endpoint")).clicked() {
+                providers.push(MarketDataProviderConfig::JsonEndpoint(JsonEndpointConfig::default()));
+            }
+            if ui.small_button(i18n("Fixed Price")).clicked() {
+                providers.push(MarketDataProviderConfig::FixedPrice(FixedPriceConfig::default()));
+            }
+        });
+
+        if self.settings.market.providers != core.settings.market.providers {
+            ui.add_space(8.);
+            if let Some(response) = ui.confirm_medium_apply_cancel(Align::Max) {
+                match response {
+                    Confirm::Ack => {
+                        core.settings.market.providers = self.settings.market.providers.clone();
+                        core.settings.store_sync().unwrap();
+                        self.runtime.market_monitor_service().set_providers(core.settings.market.providers.clone());
+                    },
+                    Confirm::Nack => {
+                        self.settings.market.providers = core.settings.market.providers.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn render_price_history_import(
+        &mut self,
+        core: &mut Core,
+        ui: &mut egui::Ui,
+    ) {
+        ui.add_space(8.);
+        ui.horizontal(|ui| {
+            ui.label(i18n("Price history:"));
+            let response = ui.small_button(i18n("Import CSV..."))
+                .on_hover_text(i18n("Daily prices as 'date,price' or 'date,currency,price' lines, dates as YYYY-MM-DD or unix time"));
+            if !response.clicked() {
+                return;
+            }
+
+            let Some(path) = rfd::FileDialog::new()
+                .add_filter("CSV", &["csv", "txt"])
+                .pick_file() else {
+                return;
+            };
+
+            match std::fs::read_to_string(&path) {
+                Ok(csv) => {
+                    // records without a currency column are in the primary currency
+                    let currency = core.settings.market.primary_currency().unwrap_or("usd").to_string();
+                    self.runtime.market_monitor_service().import_price_history(csv, currency);
+                }
+                Err(err) => {
+                    runtime().notify(UserNotification::error(format!("{}: {err}", i18n("Unable to read the price history"))));
+                }
+            }
+        });
+    }
+
     fn render_settings(
         &mut self,
         core: &mut Core,
@@ -607,6 +1009,13 @@ impl Settings {
                             self.runtime.market_monitor_service().enable(core.settings.market_monitor);
                             core.store_settings();
                         }
+
+                        if self.settings.market_monitor {
+                            self.render_market_currencies(core, ui);
+                            self.render_market_providers(core, ui);
+                            #[cfg(not(target_arch = "wasm32"))]
+                            self.render_price_history_import(core, ui);
+                        }
                     });
 
                 #[cfg(not(target_arch = "wasm32"))]
@@ -619,6 +1028,40 @@ impl Settings {
                             core.store_settings();
                         }
                     });    
+
+                #[cfg(not(target_arch = "wasm32"))]
+                CollapsingHeader::new(i18n("Prometheus Metrics"))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ui.checkbox(&mut self.settings.prometheus.enable, i18n("Enable Prometheus metrics endpoint"));
+
+                        let is_valid = self.settings.prometheus.bind.parse::<std::net::SocketAddr>().is_ok();
+                        if self.settings.prometheus.enable {
+                            ui.horizontal(|ui| {
+                                ui.label(i18n("Listen on:"));
+                                ui.add(TextEdit::singleline(&mut self.settings.prometheus.bind).desired_width(160.));
+                            });
+
+                            if is_valid {
+                                ui.label(format!("http://{}/metrics", self.settings.prometheus.bind));
+                            } else {
+                                ui.colored_label(theme_color().error_color, i18n("Please enter a valid address and port, e.g. 127.0.0.1:9464"));
+                            }
+                        }
+
+                        if self.settings.prometheus != core.settings.prometheus {
+                            ui.horizontal(|ui| {
+                                if ui.add_enabled(is_valid, egui::Button::new(i18n("Apply"))).clicked() {
+                                    core.settings.prometheus = self.settings.prometheus.clone();
+                                    self.runtime.prometheus_service().configure(core.settings.prometheus.clone());
+                                    core.store_settings();
+                                }
+                                if ui.button(i18n("Cancel")).clicked() {
+                                    self.settings.prometheus = core.settings.prometheus.clone();
+                                }
+                            });
+                        }
+                    });
             });
 
         CollapsingHeader::new(i18n("Network Fee Estimator"))
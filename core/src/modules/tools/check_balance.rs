@@ -1,133 +1,285 @@
 use crate::imports::*;
+use kaspa_rpc_core::RpcUtxosByAddressesEntry;
+use kaspa_wallet_core::utxo::NetworkParams;
 
-pub enum State {
-    Select,
-    CheckBalanceStart,
-    CheckBalanceProcess,
-    CheckBalanceResult(Result<u64>),
+/// Balance and UTXO set of an address, as reported by the node.
+#[derive(Debug, Clone)]
+pub struct AddressBalance {
+    pub address: Address,
+    pub balance: u64,
+    pub utxos: Vec<RpcUtxosByAddressesEntry>,
 }
 
-pub struct Tools {
-    #[allow(dead_code)]
-    runtime: Runtime,
+#[derive(Default)]
+enum State {
+    #[default]
+    Start,
+    Process,
+    Result(Arc<AddressBalance>),
+    Error(String),
+}
+
+/// Checks the balance of an arbitrary address through the node
+/// (requires the node to run with the UTXO index enabled).
+#[derive(Default)]
+pub struct CheckBalance {
     state: State,
     address_string: String,
     address: Option<Address>,
+    active: bool,
 }
 
-impl Tools {
-    pub fn new(runtime: Runtime) -> Self {
-        Self {
-            runtime,
-            state: State::Select,
-            address_string: Default::default(),
-            address: None,
+impl CheckBalance {
+    pub fn reset(&mut self) {
+        self.state = State::Start;
+    }
+
+    fn check(&mut self, address: Address) {
+        let Some(rpc_api) = runtime().kaspa_service().rpc_api() else {
+            self.state = State::Error(i18n("Not connected").to_string());
+            return;
+        };
+
+        let balance_result =
+            Payload::<Result<Arc<AddressBalance>>>::new("tools_check_balance_result");
+        if balance_result.is_pending() {
+            return;
         }
+
+        self.state = State::Process;
+        spawn_with_result(&balance_result, async move {
+            let balance = rpc_api
+                .get_balances_by_addresses(vec![address.clone()])
+                .await?
+                .into_iter()
+                .find_map(|entry| entry.balance)
+                .unwrap_or_default();
+
+            let mut utxos = rpc_api
+                .get_utxos_by_addresses(vec![address.clone()])
+                .await?;
+            utxos.sort_by(|a, b| {
+                b.utxo_entry
+                    .block_daa_score
+                    .cmp(&a.utxo_entry.block_daa_score)
+            });
+
+            Ok(Arc::new(AddressBalance {
+                address,
+                balance,
+                utxos,
+            }))
+        });
     }
-}
 
-impl ModuleT for Tools {
-    fn render(
-        &mut self,
-        core: &mut Wallet,
-        _ctx: &egui::Context,
-        _frame: &mut eframe::Frame,
-        ui: &mut egui::Ui,
-    ) {
+    /// Render the tool; returns `false` once the user navigates back.
+    pub fn render(&mut self, core: &mut Core, ui: &mut Ui) -> bool {
+        self.active = true;
+
+        let network = core.network();
+        let network_type = if let Some(network_id) = core.state().network_id() {
+            network_id.network_type()
+        } else {
+            network.into()
+        };
+
         match &self.state {
-            State::Select => {
-                ui.heading("Tools");
-                ui.separator();
-                ui.label("This is the tools page");
-                if ui.button("Check Balance").clicked() {
-                    self.state = State::CheckBalanceStart;
-                }
-            }
-            State::CheckBalanceStart => {
+            State::Start | State::Error(_) => {
+                let submit = Rc::new(RefCell::new(false));
                 Panel::new(self)
-                    .with_caption("Check Balance")
+                    .with_caption(i18n("Check Balance"))
                     .with_back(|this| {
-                        this.state = State::Select;
-                    })
-                    .with_close(|_| {
-                        wallet.back();
+                        this.active = false;
                     })
                     .with_header(|_, ui| {
-                        ui.label("Check Balance for a specific address");
+                        ui.label(i18n("Check the balance and UTXOs of any address"));
                     })
                     .with_body(|this, ui| {
                         let address_string = this.address_string.clone();
-                        ui.add(
+                        let response = ui.add(
                             TextEdit::singleline(&mut this.address_string)
-                                .hint_text("Wallet Name...")
+                                .hint_text(i18n("Address..."))
+                                .desired_width(f32::INFINITY)
                                 .vertical_align(Align::Center),
                         );
 
                         if address_string != this.address_string {
-                            this.address_string = address_string.clone();
-                            this.address = Address::try_from(address_string).ok();
+                            let prefix: kaspa_addresses::Prefix = network.into();
+                            this.address = Address::try_from(this.address_string.trim())
+                                .ok()
+                                .filter(|address| address.prefix == prefix);
+                        }
+
+                        if !this.address_string.is_empty() && this.address.is_none() {
+                            ui.add_space(8.);
+                            ui.colored_label(
+                                theme_color().error_color,
+                                i18n("Please enter a valid address for the current network"),
+                            );
+                        }
+
+                        if let State::Error(error) = &this.state {
+                            ui.add_space(8.);
+                            ui.colored_label(theme_color().error_color, error);
+                        }
+
+                        if response.text_edit_submit(ui) {
+                            *submit.borrow_mut() = true;
                         }
                     })
                     .with_footer(|this, ui| {
+                        let enabled = this.address.is_some() && core.state().is_connected();
                         if ui
-                            .large_button_enabled(this.address.is_some(), "Check Balance")
+                            .large_button_enabled(enabled, i18n("Check Balance"))
                             .clicked()
                         {
-                            this.state = State::CheckBalanceProcess;
+                            *submit.borrow_mut() = true;
                         }
                     })
                     .render(ui);
+
+                if *submit.borrow() {
+                    if let Some(address) = self.address.clone() {
+                        self.check(address);
+                    }
+                }
             }
-            State::CheckBalanceProcess => {
+            State::Process => {
+                let balance_result =
+                    Payload::<Result<Arc<AddressBalance>>>::new("tools_check_balance_result");
+                if let Some(result) = balance_result.take() {
+                    self.state = match result {
+                        Ok(balance) => State::Result(balance),
+                        Err(err) => State::Error(err.to_string()),
+                    };
+                }
+
                 Panel::new(self)
-                    .with_caption("Check Balance")
-                    .with_back(|this| {
-                        this.state = State::Select;
-                    })
-                    .with_close(|_| {
-                        wallet.back();
-                    })
+                    .with_caption(i18n("Check Balance"))
                     .with_header(|_, ui| {
-                        ui.label("Check Balance for a specific address");
+                        ui.label(i18n("Querying the node..."));
                     })
-                    .with_body(|this, _ui| {
-                        let address_balance_result =
-                            Payload::<Result<u64>>::new("tools_check_balance_result");
-                        if !address_balance_result.is_pending() {
-                            let _wallet = this.runtime.wallet().clone();
-                            spawn_with_result(&address_balance_result, async move {
-                                todo!("CheckBalanceProcess")
-                            });
-                        }
-
-                        if let Some(balance_result) = address_balance_result.take() {
-                            this.state = State::CheckBalanceResult(balance_result);
-                        }
+                    .with_body(|_, ui| {
+                        ui.add_space(64.);
+                        ui.add(egui::Spinner::new().size(92.));
                     })
                     .render(ui);
             }
-            State::CheckBalanceResult(_result) => {
+            State::Result(balance) => {
+                let balance = balance.clone();
+                let current_daa_score = core.state().current_daa_score();
+                let params: &NetworkParams = network.into();
+
                 Panel::new(self)
-                    .with_caption("Check Balance")
+                    .with_caption(i18n("Check Balance"))
                     .with_back(|this| {
-                        this.state = State::Select;
-                    })
-                    .with_close(|_| {
-                        wallet.back();
+                        this.state = State::Start;
                     })
                     .with_header(|_, ui| {
-                        ui.label("Check Balance for a specific address");
+                        ui.label(RichText::new(balance.address.to_string()).monospace());
+                        ui.add_space(8.);
+                        ui.label(
+                            RichText::new(sompi_to_kaspa_string_with_suffix(
+                                balance.balance,
+                                &network_type,
+                            ))
+                            .heading(),
+                        );
+                        ui.label(format!(
+                            "{} {}",
+                            balance.utxos.len().separated_string(),
+                            i18n("UTXOs")
+                        ));
                     })
-                    .with_body(|_this, _ui| {
-                        todo!("CheckBalanceResult");
+                    .with_body(|_, ui| {
+                        egui::ScrollArea::vertical()
+                            .id_source("tools_check_balance_utxos")
+                            .auto_shrink([false, true])
+                            .show(ui, |ui| {
+                                Grid::new("tools_check_balance_utxo_grid")
+                                    .num_columns(4)
+                                    .striped(true)
+                                    .spacing([16.0, 4.0])
+                                    .show(ui, |ui| {
+                                        ui.label(RichText::new(i18n("Amount")).strong());
+                                        ui.label(RichText::new(i18n("DAA Score")).strong());
+                                        ui.label(RichText::new(i18n("Maturity")).strong());
+                                        ui.label(RichText::new(i18n("Outpoint")).strong());
+                                        ui.end_row();
+
+                                        balance.utxos.iter().for_each(|utxo| {
+                                            let entry = &utxo.utxo_entry;
+                                            ui.label(sompi_to_kaspa_string_with_suffix(
+                                                entry.amount,
+                                                &network_type,
+                                            ));
+                                            ui.label(entry.block_daa_score.separated_string());
+                                            render_maturity(
+                                                ui,
+                                                entry.block_daa_score,
+                                                entry.is_coinbase,
+                                                current_daa_score,
+                                                params,
+                                            );
+                                            let outpoint = format!(
+                                                "{}:{}",
+                                                utxo.outpoint.transaction_id, utxo.outpoint.index
+                                            );
+                                            ui.label(
+                                                RichText::new(format_partial_string(
+                                                    &outpoint,
+                                                    Some(12),
+                                                ))
+                                                .monospace(),
+                                            )
+                                            .on_hover_text(outpoint);
+                                            ui.end_row();
+                                        });
+                                    });
+                            });
                     })
-                    .with_handler(|_this| {})
                     .render(ui);
             }
         }
 
-        ui.heading("Request");
-        ui.separator();
-        ui.label("This is the payment request page");
+        std::mem::take(&mut self.active)
+    }
+}
+
+fn render_maturity(
+    ui: &mut Ui,
+    block_daa_score: u64,
+    is_coinbase: bool,
+    current_daa_score: Option<u64>,
+    params: &NetworkParams,
+) {
+    let period = if is_coinbase {
+        params.coinbase_transaction_maturity_period_daa()
+    } else {
+        params.user_transaction_maturity_period_daa()
+    };
+
+    let kind = if is_coinbase {
+        i18n("coinbase")
+    } else {
+        i18n("standard")
+    };
+
+    match current_daa_score {
+        Some(current_daa_score) => {
+            let confirmations = current_daa_score.saturating_sub(block_daa_score);
+            if confirmations >= period {
+                ui.label(format!("{} ({kind})", i18n("Mature")));
+            } else {
+                let progress = confirmations as f64 / period.max(1) as f64 * 100.0;
+                ui.colored_label(
+                    theme_color().warning_color,
+                    format!("{} {:.0}% ({kind})", i18n("Maturing"), progress),
+                );
+            }
+        }
+        None => {
+            ui.label(format!("{} ({kind})", i18n("Unknown")));
+        }
     }
 }
@@ -1 +1,64 @@
-pub mod check_balance;
+use crate::imports::*;
+
+mod check_balance;
+use check_balance::CheckBalance;
+
+pub enum State {
+    Select,
+    CheckBalance,
+}
+
+pub struct Tools {
+    #[allow(dead_code)]
+    runtime: Runtime,
+    state: State,
+    check_balance: CheckBalance,
+}
+
+impl Tools {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            state: State::Select,
+            check_balance: CheckBalance::default(),
+        }
+    }
+}
+
+impl ModuleT for Tools {
+    fn network_change(&mut self, _core: &mut Core, _network: Network) {
+        self.check_balance.reset();
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        _ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+        match self.state {
+            State::Select => {
+                let has_stack = core.has_stack();
+                Panel::new(self)
+                    .with_caption(i18n("Tools"))
+                    .with_back_enabled(has_stack, |_| core.back())
+                    .with_header(|_, ui| {
+                        ui.label(i18n("Please select a tool"));
+                    })
+                    .with_body(|this, ui| {
+                        if ui.large_button(i18n("Check Balance")).clicked() {
+                            this.check_balance.reset();
+                            this.state = State::CheckBalance;
+                        }
+                    })
+                    .render(ui);
+            }
+            State::CheckBalance => {
+                if !self.check_balance.render(core, ui) {
+                    self.state = State::Select;
+                }
+            }
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub struct BlockDagGraphSettings {
     pub show_vspc: bool,
     pub show_daa: bool,
     pub show_grid: bool,
+    pub show_ghostdag: bool,
 }
 
 impl Default for BlockDagGraphSettings {
@@ -28,6 +29,7 @@ impl Default for BlockDagGraphSettings {
             show_vspc: true,
             show_daa: true,
             show_grid: true,
+            show_ghostdag: true,
         }
     }
 }
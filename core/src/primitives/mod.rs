@@ -1,7 +1,7 @@
 pub mod account;
 pub use account::{Account, AccountCollection, AccountSelectorButtonExtension};
 pub mod transaction;
-pub use transaction::{Transaction, TransactionCollection};
+pub use transaction::{FiatValuation, Transaction, TransactionCollection};
 pub mod block;
 pub use block::{BlockDagGraphSettings, DaaBucket, DagBlock};
 pub mod descriptor;
@@ -1,4 +1,6 @@
 use crate::imports::*;
+use crate::market::PriceHistory;
+use crate::modules::block_lookup::LookupTarget;
 use egui_phosphor::light::*;
 use kaspa_consensus_core::tx::{TransactionInput, TransactionOutpoint, TransactionOutput};
 use kaspa_txscript::standard::extract_script_pub_key_address;
@@ -27,6 +29,95 @@ impl AsColor for TransactionKind {
     }
 }
 
+/// Fiat value of transactions in the primary display currency,
+/// at the current price and at the price of the day they occurred.
+pub struct FiatValuation {
+    currency: String,
+    precision: usize,
+    price: Option<f64>,
+    history: Option<Arc<PriceHistory>>,
+}
+
+impl FiatValuation {
+    /// Returns `None` if the market monitor is disabled or
+    /// alternate currencies are not shown on the current network.
+    pub fn new(core: &Core) -> Option<Self> {
+        let settings = &core.settings;
+        if !settings.market_monitor
+            || !(settings.node.network == Network::Mainnet
+                || settings.developer.market_monitor_on_testnet)
+        {
+            return None;
+        }
+
+        let currency = settings.market.primary_currency()?.to_string();
+        let market = core.market.as_ref()?;
+        let data = market.data(&currency);
+        Some(Self {
+            precision: data
+                .map(|data| data.precision)
+                .unwrap_or_else(|| precision_from_symbol(&currency)),
+            price: data.map(|data| data.price),
+            history: market.history.clone(),
+            currency,
+        })
+    }
+
+    fn format(&self, sompi: u64, price: f64) -> String {
+        format_currency_with_symbol(
+            sompi_to_kaspa(sompi) * price,
+            self.precision,
+            &self.currency.to_uppercase(),
+        )
+    }
+
+    pub fn current(&self, sompi: u64) -> Option<String> {
+        self.price.map(|price| self.format(sompi, price))
+    }
+
+    pub fn historical(&self, sompi: u64, record: &TransactionRecord) -> Option<String> {
+        let timestamp = record.unixtime_msec()? as f64;
+        let price = self.history.as_ref()?.price_at(&self.currency, timestamp)?;
+        Some(self.format(sompi, price))
+    }
+
+    /// Historical and current value for transaction headers.
+    fn summary(&self, sompi: u64, record: &TransactionRecord) -> Option<String> {
+        match (self.historical(sompi, record), self.current(sompi)) {
+            (Some(historical), Some(current)) => {
+                Some(format!("~{historical} ({} {current})", i18n("now")))
+            }
+            (Some(historical), None) => Some(format!("~{historical}")),
+            (None, Some(current)) => Some(format!("~{current} ({})", i18n("now"))),
+            (None, None) => None,
+        }
+    }
+
+    fn render_details(
+        &self,
+        ui: &mut Ui,
+        content: &LayoutJobBuilderSettings,
+        sompi: u64,
+        record: &TransactionRecord,
+        color: Color32,
+    ) {
+        let historical = self
+            .historical(sompi, record)
+            .unwrap_or_else(|| i18n("n/a").to_string());
+        ljb(content)
+            .padded(15, "Historic value:", color)
+            .text(&historical, color)
+            .label(ui);
+
+        if let Some(current) = self.current(sompi) {
+            ljb(content)
+                .padded(15, "Current value:", color)
+                .text(&current, color)
+                .label(ui);
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Context {
     record: Arc<TransactionRecord>,
@@ -88,6 +179,24 @@ impl Transaction {
     pub fn aggregate_input_value(&self) -> u64 {
         self.context().record.aggregate_input_value()
     }
+
+    /// Addresses of the wallet UTXOs received or spent by this transaction.
+    pub fn utxo_addresses(&self) -> Vec<Address> {
+        match self.context().record.transaction_data() {
+            TransactionData::Reorg { utxo_entries, .. }
+            | TransactionData::Stasis { utxo_entries, .. }
+            | TransactionData::Incoming { utxo_entries, .. }
+            | TransactionData::TransferIncoming { utxo_entries, .. }
+            | TransactionData::External { utxo_entries, .. }
+            | TransactionData::Outgoing { utxo_entries, .. }
+            | TransactionData::TransferOutgoing { utxo_entries, .. }
+            | TransactionData::Batch { utxo_entries, .. }
+            | TransactionData::Change { utxo_entries, .. } => utxo_entries
+                .iter()
+                .filter_map(|utxo_entry| utxo_entry.address.clone())
+                .collect(),
+        }
+    }
 }
 
 impl IdT for Transaction {
@@ -131,6 +240,7 @@ impl Transaction {
         current_daa_score: Option<u64>,
         _include_utxos: bool,
         largest: Option<u64>,
+        valuation: Option<&FiatValuation>,
     ) {
         let width = ui.available_width() / ui.ctx().pixels_per_point();
         let w_min = 250.0;
@@ -219,6 +329,12 @@ impl Transaction {
                     .text(timestamp.as_str(), default_color)
                     .text(&value, TransactionKind::Incoming.as_color());
 
+                if let Some(fiat) =
+                    valuation.and_then(|valuation| valuation.summary(record.value(), record))
+                {
+                    job = job.text(&fiat, default_color);
+                }
+
                 // ui.LayoutJobBuilder::new(width,8.0(&transaction_id, false, |ui,state| {
                 //     ui.horizontal( |ui| {
 
@@ -277,11 +393,23 @@ impl Transaction {
                             padding_range,
                         );
 
+                    render_lookup_link(ui, record, Some(record.block_daa_score()), default_color);
+
                     ljb(&content)
                         .padded(15, "Received at:", default_color)
                         .text(&format!("{} DAA", block_daa_score), default_color)
                         .label(ui);
 
+                    if let Some(valuation) = valuation {
+                        valuation.render_details(
+                            ui,
+                            &content,
+                            record.value(),
+                            record,
+                            default_color,
+                        );
+                    }
+
                     utxo_entries.iter().for_each(|utxo_entry| {
                         let UtxoRecord {
                             index: _,
@@ -361,6 +489,12 @@ impl Transaction {
                         TransactionKind::Outgoing.as_color(),
                     );
 
+                    if let Some(fiat) = valuation
+                        .and_then(|valuation| valuation.summary(*payment_value + *fees, record))
+                    {
+                        job = job.text(&fiat, default_color);
+                    }
+
                     if !maturity.unwrap_or(true) {
                         job = job.text("Submitting...", strong_color);
                     }
@@ -454,6 +588,8 @@ impl Transaction {
                             padding_range,
                         );
 
+                    render_lookup_link(ui, record, *accepted_daa_score, default_color);
+
                     ljb(&content)
                         .padded(15, "Submitted at:", default_color)
                         .text(&format!("{} DAA", block_daa_score), default_color)
@@ -474,6 +610,16 @@ impl Transaction {
                             .padded(15, "Amount:", default_color)
                             .text(&ps2k(*payment_value), TransactionKind::Outgoing.as_color())
                             .label(ui);
+
+                        if let Some(valuation) = valuation {
+                            valuation.render_details(
+                                ui,
+                                &content,
+                                *payment_value + *fees,
+                                record,
+                                default_color,
+                            );
+                        }
                     }
 
                     ljb(&content)
@@ -619,6 +765,31 @@ impl Transaction {
     }
 }
 
+/// Link opening the transaction in the block lookup module. Confirmed
+/// transactions are located through the chain block that accepted them
+/// at `accepted_daa_score`.
+fn render_lookup_link(
+    ui: &mut Ui,
+    record: &TransactionRecord,
+    accepted_daa_score: Option<u64>,
+    color: Color32,
+) {
+    let text = RichText::new(format!("{MAGNIFYING_GLASS} {}", i18n("Look up in node")))
+        .font(FontId::monospace(15.0))
+        .color(color);
+    if ui
+        .add(Label::new(text).sense(Sense::click()))
+        .on_hover_cursor(CursorIcon::PointingHand)
+        .clicked()
+    {
+        let target = match accepted_daa_score {
+            Some(daa_score) => LookupTarget::AcceptedTransaction(*record.id(), daa_score),
+            None => LookupTarget::Transaction(*record.id()),
+        };
+        runtime().try_send(Events::Lookup(target)).ok();
+    }
+}
+
 #[inline]
 pub fn sompi_to_kaspa(sompi: u64) -> f64 {
     sompi as f64 / SOMPI_PER_KASPA as f64
@@ -43,6 +43,10 @@ pub struct Inner {
     metrics_service: Arc<MetricsService>,
     #[cfg(not(feature = "lean"))]
     block_dag_monitor_service: Arc<BlockDagMonitorService>,
+    #[cfg(not(feature = "lean"))]
+    network_monitor_service: Arc<NetworkMonitorService>,
+    #[cfg(not(target_arch = "wasm32"))]
+    prometheus_service: Arc<PrometheusService>,
 }
 
 /// Runtime is a core component of the Kaspa NG application responsible for
@@ -97,8 +101,15 @@ impl Runtime {
                     application_events.clone(),
                     settings,
                 ));
+                let network_monitor_service = Arc::new(NetworkMonitorService::new(
+                    application_events.clone(),
+                    settings,
+                ));
             }
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        let prometheus_service =
+            Arc::new(PrometheusService::new(application_events.clone(), settings));
         // let metrics_service = Arc::new(MetricsService::new(application_events.clone(), settings));
         // let block_dag_monitor_service = Arc::new(BlockDagMonitorService::new(
         //     application_events.clone(),
@@ -116,6 +127,10 @@ impl Runtime {
             metrics_service.clone(),
             #[cfg(not(feature = "lean"))]
             block_dag_monitor_service.clone(),
+            #[cfg(not(feature = "lean"))]
+            network_monitor_service.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            prometheus_service.clone(),
         ]);
 
         let runtime = Self {
@@ -137,6 +152,10 @@ impl Runtime {
                 metrics_service,
                 #[cfg(not(feature = "lean"))]
                 block_dag_monitor_service,
+                #[cfg(not(feature = "lean"))]
+                network_monitor_service,
+                #[cfg(not(target_arch = "wasm32"))]
+                prometheus_service,
             }),
         };
 
@@ -252,9 +271,18 @@ impl Runtime {
             pub fn block_dag_monitor_service(&self) -> &Arc<BlockDagMonitorService> {
                 &self.inner.block_dag_monitor_service
             }
+
+            pub fn network_monitor_service(&self) -> &Arc<NetworkMonitorService> {
+                &self.inner.network_monitor_service
+            }
         }
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn prometheus_service(&self) -> &Arc<PrometheusService> {
+        &self.inner.prometheus_service
+    }
+
     pub fn market_monitor_service(&self) -> &Arc<MarketMonitorService> {
         &self.inner.market_monitor_service
     }
@@ -2,13 +2,138 @@ use crate::imports::*;
 use kaspa_notify::{listener::ListenerId, scope::*};
 use kaspa_rpc_core::api::notifications::Notification;
 use kaspa_rpc_core::notify::connection::{ChannelConnection, ChannelType};
-use kaspa_rpc_core::{RpcBlock, VirtualChainChangedNotification};
+use kaspa_rpc_core::{RpcBlock, RpcHash, VirtualChainChangedNotification};
+
+/// Interval at which recorded events are fed into the graph during replay
+const REPLAY_TICK_MILLIS: u64 = 50;
+/// Maximum time allowed for the node to return a chain block being classified
+const CLASSIFY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Maximum number of pending chain block batches awaiting classification
+const CLASSIFY_QUEUE_CAPACITY: usize = 32;
+
+/// GHOSTDAG classification of a block within the mergeset
+/// of the chain block that merged it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlockColor {
+    Blue,
+    Red,
+}
+
+/// GHOSTDAG classification of blocks, keyed by block hash. Entries
+/// carry the DAA score of the merging chain block so that they are
+/// pruned together with the graph.
+#[derive(Debug, Clone, Default)]
+pub struct BlockColors {
+    colors: AHashMap<KaspaHash, (BlockColor, u64)>,
+}
+
+impl BlockColors {
+    pub fn get(&self, hash: &KaspaHash) -> Option<BlockColor> {
+        self.colors.get(hash).map(|(color, _)| *color)
+    }
+
+    fn insert(&mut self, hash: KaspaHash, color: BlockColor, daa_score: u64) {
+        self.colors.insert(hash, (color, daa_score));
+    }
+
+    fn remove(&mut self, hash: &KaspaHash) {
+        self.colors.remove(hash);
+    }
+
+    fn clear(&mut self) {
+        self.colors.clear();
+    }
+
+    /// Remove classifications made by chain blocks at or below `daa_score`.
+    fn prune(&mut self, daa_score: u64) {
+        self.colors
+            .retain(|_, (_, merged_at)| *merged_at > daa_score);
+    }
+}
+
+/// Block DAG notification, as processed by the monitor
+/// and stored in session recordings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DagEvent {
+    BlockAdded {
+        block: RpcBlock,
+    },
+    VirtualChainChanged {
+        removed: Vec<RpcHash>,
+        added: Vec<RpcHash>,
+    },
+}
+
+impl DagEvent {
+    fn from_notification(notification: &Notification) -> Option<Self> {
+        match notification {
+            Notification::BlockAdded(notification) => Some(DagEvent::BlockAdded {
+                block: (*notification.block).clone(),
+            }),
+            Notification::VirtualChainChanged(notification) => {
+                let VirtualChainChangedNotification {
+                    removed_chain_block_hashes,
+                    added_chain_block_hashes,
+                    ..
+                } = notification;
+                Some(DagEvent::VirtualChainChanged {
+                    removed: removed_chain_block_hashes.to_vec(),
+                    added: added_chain_block_hashes.to_vec(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Entry of a Block DAG session recording (stored as JSON lines).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DagRecord {
+    /// Time since the start of the recording in milliseconds
+    pub timestamp: u64,
+    pub event: DagEvent,
+}
+
+/// Session recorder. Records are serialized by the monitor
+/// and written to the file by a dedicated writer thread.
+#[cfg(not(target_arch = "wasm32"))]
+struct Recorder {
+    path: std::path::PathBuf,
+    sender: std::sync::mpsc::Sender<String>,
+    writer: std::thread::JoinHandle<std::io::Result<()>>,
+    start: Instant,
+    events: usize,
+}
+
+/// Progress of an ongoing replay.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayStatus {
+    /// Replay position in milliseconds of recording time
+    pub position: u64,
+    pub duration: u64,
+    pub events: usize,
+    pub total: usize,
+    /// DAA score of the most recently replayed block
+    pub daa_score: Option<u64>,
+    pub finished: bool,
+}
+
+struct Replay {
+    records: Vec<DagRecord>,
+    index: usize,
+    position: f64,
+    last_tick: Instant,
+}
 
 pub enum BlockDagMonitorEvents {
     Enable,
     Disable,
     Settings(Arc<BlockDagGraphSettings>),
     Reset,
+    Replay(Vec<DagRecord>),
+    StopReplay,
     Exit,
 }
 
@@ -26,6 +151,15 @@ pub struct BlockDagMonitorService {
     pub separators: Mutex<Vec<u64>>,
     pub new_blocks: Arc<Mutex<AHashSet<kaspa_consensus_core::Hash>>>,
     pub settings: Mutex<Arc<BlockDagGraphSettings>>,
+    pub block_colors: Mutex<BlockColors>,
+    /// Chain blocks queued for classification, tagged with the chain generation
+    classify_queue: Channel<(u64, Vec<KaspaHash>)>,
+    /// Incremented each time the chain is reset, invalidating queued classifications
+    chain_generation: AtomicU64,
+    #[cfg(not(target_arch = "wasm32"))]
+    recorder: Mutex<Option<Recorder>>,
+    replay_status: Mutex<Option<ReplayStatus>>,
+    replay_speed: Mutex<f64>,
 }
 
 impl BlockDagMonitorService {
@@ -44,6 +178,13 @@ impl BlockDagMonitorService {
             is_active: Arc::new(AtomicBool::new(false)),
             is_connected: Arc::new(AtomicBool::new(false)),
             settings: Mutex::new(Arc::new(BlockDagGraphSettings::default())),
+            block_colors: Mutex::new(BlockColors::default()),
+            classify_queue: Channel::bounded(CLASSIFY_QUEUE_CAPACITY),
+            chain_generation: AtomicU64::new(0),
+            #[cfg(not(target_arch = "wasm32"))]
+            recorder: Mutex::new(None),
+            replay_status: Mutex::new(None),
+            replay_speed: Mutex::new(1.0),
         }
     }
 
@@ -146,6 +287,308 @@ impl BlockDagMonitorService {
         self.new_blocks.lock().unwrap()
     }
 
+    /// Start recording received notifications to a file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_recording(&self, path: &std::path::Path) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        let (sender, receiver) = std::sync::mpsc::channel::<String>();
+        let writer = std::thread::Builder::new()
+            .name("block-dag-recorder".to_string())
+            .spawn(move || -> std::io::Result<()> {
+                use std::io::Write;
+
+                let mut writer = std::io::BufWriter::new(file);
+                while let Ok(line) = receiver.recv() {
+                    writeln!(writer, "{line}")?;
+                }
+                writer.flush()
+            })?;
+
+        self.recorder.lock().unwrap().replace(Recorder {
+            path: path.to_path_buf(),
+            sender,
+            writer,
+            start: Instant::now(),
+            events: 0,
+        });
+        Ok(())
+    }
+
+    /// Stop recording, returning the recording file and the number of recorded events.
+    /// Returns once all recorded events have been written to the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stop_recording(&self) -> Result<Option<(std::path::PathBuf, usize)>> {
+        let recorder = self.recorder.lock().unwrap().take();
+        if let Some(recorder) = recorder {
+            let Recorder {
+                path,
+                sender,
+                writer,
+                events,
+                ..
+            } = recorder;
+            // closing the channel terminates the writer thread
+            drop(sender);
+            writer
+                .join()
+                .map_err(|_| Error::custom("Block DAG recorder has panicked"))??;
+            Ok(Some((path, events)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Number of events recorded so far, `None` if not recording.
+    pub fn recorded_events(&self) -> Option<usize> {
+        cfg_if! {
+            if #[cfg(not(target_arch = "wasm32"))] {
+                self.recorder.lock().unwrap().as_ref().map(|recorder| recorder.events)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn record(&self, event: &DagEvent) {
+        let mut recorder = self.recorder.lock().unwrap();
+        if let Some(recorder) = recorder.as_mut() {
+            let record = DagRecord {
+                timestamp: recorder.start.elapsed().as_millis() as u64,
+                event: event.clone(),
+            };
+            match serde_json::to_string(&record) {
+                Ok(line) => {
+                    // the writer thread exits on a write error, reported by `stop_recording()`
+                    if recorder.sender.send(line).is_ok() {
+                        recorder.events += 1;
+                    }
+                }
+                Err(err) => log_error!("Block DAG: unable to record event: {err}"),
+            }
+        }
+    }
+
+    /// Load a session recording.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_recording(path: &std::path::Path) -> Result<Vec<DagRecord>> {
+        let data = std::fs::read_to_string(path)?;
+        let records = data
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str::<DagRecord>)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    /// Replay recorded events into the graph. Live notifications
+    /// are ignored until the replay is stopped.
+    pub fn start_replay(&self, records: Vec<DagRecord>) {
+        self.service_events
+            .sender
+            .try_send(BlockDagMonitorEvents::Replay(records))
+            .unwrap();
+    }
+
+    pub fn stop_replay(&self) {
+        self.service_events
+            .sender
+            .try_send(BlockDagMonitorEvents::StopReplay)
+            .unwrap();
+    }
+
+    pub fn replay_status(&self) -> Option<ReplayStatus> {
+        self.replay_status.lock().unwrap().clone()
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay_status.lock().unwrap().is_some()
+    }
+
+    pub fn replay_speed(&self) -> f64 {
+        *self.replay_speed.lock().unwrap()
+    }
+
+    pub fn set_replay_speed(&self, speed: f64) {
+        *self.replay_speed.lock().unwrap() = speed;
+    }
+
+    /// Snapshot of the current block classification.
+    pub fn block_colors(&self) -> BlockColors {
+        self.block_colors.lock().unwrap().clone()
+    }
+
+    /// Number of red blocks with timestamps within a minute
+    /// of the most recent block in the graph.
+    pub fn red_blocks_per_minute(&self) -> usize {
+        let chain = self.chain.lock().unwrap();
+        let block_colors = self.block_colors.lock().unwrap();
+        let blocks = || chain.values().flat_map(|bucket| bucket.blocks.iter());
+        let Some(latest) = blocks().map(|block| block.data.header.timestamp).max() else {
+            return 0;
+        };
+        blocks()
+            .filter(|block| block.data.header.timestamp + 60_000 > latest)
+            .filter(|block| block_colors.get(&block.data.header.hash) == Some(BlockColor::Red))
+            .count()
+    }
+
+    /// Apply (or revert) the mergeset classification of a chain block,
+    /// returning `false` if the block carries no verbose data.
+    fn classify(&self, chain_block: &RpcBlock, merged: bool) -> bool {
+        let Some(verbose_data) = chain_block.verbose_data.as_ref() else {
+            return false;
+        };
+
+        let daa_score = chain_block.header.daa_score;
+        let mut block_colors = self.block_colors.lock().unwrap();
+        for (hashes, color) in [
+            (&verbose_data.merge_set_blues_hashes, BlockColor::Blue),
+            (&verbose_data.merge_set_reds_hashes, BlockColor::Red),
+        ] {
+            for hash in hashes.iter() {
+                if merged {
+                    block_colors.insert(*hash, color, daa_score);
+                } else {
+                    block_colors.remove(hash);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Fetch chain blocks lacking verbose data from the node and classify their mergesets.
+    /// Blocks are discarded if the chain has been reset since they were queued.
+    async fn classify_from_node(&self, generation: u64, hashes: Vec<KaspaHash>) -> Result<()> {
+        let Some(rpc_api) = self.rpc_api() else {
+            return Ok(());
+        };
+
+        for hash in hashes {
+            let block = select! {
+                block = rpc_api.get_block(hash, false).fuse() => block?,
+                _ = task::sleep(CLASSIFY_TIMEOUT).fuse() => {
+                    return Err(Error::custom("timeout fetching a chain block"));
+                }
+            };
+            if self.chain_generation.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            self.classify(&block, true);
+        }
+
+        Ok(())
+    }
+
+    fn reset_chain(&self, blocks_by_hash: &mut AHashMap<KaspaHash, Arc<RpcBlock>>) {
+        self.chain_generation.fetch_add(1, Ordering::SeqCst);
+        self.chain.lock().unwrap().clear();
+        self.new_blocks.lock().unwrap().clear();
+        self.block_colors.lock().unwrap().clear();
+        blocks_by_hash.clear();
+    }
+
+    /// Apply a live or replayed event to the graph, returning
+    /// chain blocks whose mergesets could not be classified.
+    fn process_event(
+        &self,
+        event: DagEvent,
+        blocks_by_hash: &mut AHashMap<KaspaHash, Arc<RpcBlock>>,
+        settings: &BlockDagGraphSettings,
+    ) -> Vec<KaspaHash> {
+        let mut unclassified = Vec::new();
+        match event {
+            DagEvent::BlockAdded { block } => {
+                let block = Arc::new(block);
+
+                self.update_new_blocks(&block);
+
+                blocks_by_hash.insert(block.header.hash, block.clone());
+
+                let daa_score = block.header.daa_score;
+                let mut chain = self.chain.lock().unwrap();
+                if let Some(bucket) = chain.get_mut(&daa_score) {
+                    bucket.push(DagBlock::new(block, settings), settings);
+                } else {
+                    let mut bucket =
+                        DaaBucket::new(daa_score as f64, DagBlock::new(block, settings));
+                    bucket.update(settings);
+                    chain.insert(daa_score, bucket);
+                }
+
+                let last_daa = daa_score.saturating_sub(settings.graph_length_daa as u64);
+                chain.retain(|daa_score, bucket| {
+                    if *daa_score > last_daa {
+                        true
+                    } else {
+                        bucket.blocks.iter().for_each(|block| {
+                            blocks_by_hash.remove(&block.data.header.hash);
+                        });
+                        false
+                    }
+                });
+                // mergesets may reference blocks that were never added to the graph
+                self.block_colors.lock().unwrap().prune(last_daa);
+            }
+            DagEvent::VirtualChainChanged { removed, added } => {
+                let mut chain = self.chain.lock().unwrap();
+                for (hashes, flag) in [(removed, false), (added, true)] {
+                    hashes.iter().for_each(|hash| {
+                        if let Some(block) = blocks_by_hash.get(hash) {
+                            let daa_score = block.header.daa_score;
+                            if let Some(bucket) = chain.get_mut(&daa_score) {
+                                bucket.update_vspc(*hash, flag, settings);
+                            }
+                            if !self.classify(block, flag) && flag {
+                                unclassified.push(*hash);
+                            }
+                        }
+                    });
+                }
+            }
+        }
+
+        unclassified
+    }
+
+    /// Feed the recorded events that are due into the graph.
+    fn advance_replay(
+        &self,
+        replay: &mut Replay,
+        blocks_by_hash: &mut AHashMap<KaspaHash, Arc<RpcBlock>>,
+        settings: &BlockDagGraphSettings,
+    ) {
+        let now = Instant::now();
+        replay.position +=
+            now.duration_since(replay.last_tick).as_millis() as f64 * self.replay_speed();
+        replay.last_tick = now;
+
+        let mut daa_score = None;
+        while let Some(record) = replay.records.get(replay.index) {
+            if record.timestamp as f64 > replay.position {
+                break;
+            }
+            if let DagEvent::BlockAdded { block } = &record.event {
+                daa_score = Some(block.header.daa_score);
+            }
+            // recordings carry the verbose data of the blocks, there is no node to query
+            self.process_event(record.event.clone(), blocks_by_hash, settings);
+            replay.index += 1;
+        }
+
+        if let Some(status) = self.replay_status.lock().unwrap().as_mut() {
+            status.position = (replay.position as u64).min(status.duration);
+            status.events = replay.index;
+            status.finished = replay.index >= replay.records.len();
+            if daa_score.is_some() {
+                status.daa_score = daa_score.max(status.daa_score);
+            }
+        }
+
+        runtime().request_repaint();
+    }
+
     fn update_new_blocks(&self, block: &Arc<RpcBlock>) {
         let mut new_blocks = self.new_blocks.lock().unwrap();
         new_blocks.insert(block.header.hash);
@@ -207,75 +650,61 @@ impl Service for BlockDagMonitorService {
         let mut blocks_by_hash: AHashMap<kaspa_consensus_core::Hash, Arc<RpcBlock>> =
             AHashMap::default();
 
+        let mut replay: Option<Replay> = None;
+
+        // chain blocks are fetched and classified one batch at a time
+        let this = self.clone();
+        spawn(async move {
+            while let Ok((generation, hashes)) = this.classify_queue.receiver.recv().await {
+                if let Err(err) = this.classify_from_node(generation, hashes).await {
+                    log_warn!("Block DAG: unable to classify mergeset: {err}");
+                }
+                runtime().request_repaint();
+            }
+            Ok(())
+        });
+
         let mut settings = (*self.settings.lock().unwrap()).clone();
         loop {
+            // the replay tick is only scheduled while recorded events are pending
+            let is_replaying = replay
+                .as_ref()
+                .is_some_and(|replay| replay.index < replay.records.len());
+            let replay_tick = async move {
+                if is_replaying {
+                    task::sleep(Duration::from_millis(REPLAY_TICK_MILLIS)).await;
+                } else {
+                    futures::future::pending::<()>().await;
+                }
+            };
+
             select! {
 
+                _ = replay_tick.fuse() => {
+                    if let Some(replay) = replay.as_mut() {
+                        self.advance_replay(replay, &mut blocks_by_hash, &settings);
+                    }
+                },
+
                 msg = self.notification_channel.receiver.recv().fuse() => {
                     if let Ok(notification) = msg {
-                        match notification {
-                            Notification::BlockAdded(block_added_notification) => {
-                                let block = block_added_notification.block.clone();
-
-                                self.update_new_blocks(&block);
-
-                                blocks_by_hash.insert(block.header.hash, block.clone());
-
-                                let daa_score = block.header.daa_score;
-                                let mut chain = self.chain.lock().unwrap();
-                                if let Some(bucket) = chain.get_mut(&daa_score) {
-                                    bucket.push(DagBlock::new(block, &settings), &settings);
-                                } else {
-                                    let mut bucket = DaaBucket::new(daa_score as f64, DagBlock::new(block, &settings));
-                                    bucket.update(&settings);
-                                    chain.insert(daa_score, bucket);
-                                }
-
-                                let last_daa = daa_score - settings.graph_length_daa as u64;
-                                chain.retain(|daa_score, bucket| {
-                                    if *daa_score > last_daa {
-                                        true
-                                    } else {
-                                        bucket.blocks.iter().for_each(|block| {
-                                            blocks_by_hash.remove(&block.data.header.hash);
-                                        });
-                                        false
+                        if let Some(event) = DagEvent::from_notification(&notification) {
+                            #[cfg(not(target_arch = "wasm32"))]
+                            self.record(&event);
+
+                            // live notifications are not shown during replay
+                            if replay.is_none() {
+                                let unclassified = self.process_event(event, &mut blocks_by_hash, &settings);
+                                if !unclassified.is_empty() {
+                                    // chain blocks are fetched off the notification loop
+                                    let generation = self.chain_generation.load(Ordering::SeqCst);
+                                    if self.classify_queue.sender.try_send((generation, unclassified)).is_err() {
+                                        log_warn!("Block DAG: classification queue is full, skipping chain blocks");
                                     }
-                                });
-                            },
-                            Notification::VirtualChainChanged(virtual_chain_changed_notification) => {
-                                let VirtualChainChangedNotification {
-                                    removed_chain_block_hashes,
-                                    added_chain_block_hashes,
-                                    ..
-                                } = virtual_chain_changed_notification;
-
-                                removed_chain_block_hashes.iter().for_each(|hash| {
-                                    if let Some(block) = blocks_by_hash.get(hash) {
-                                        let daa_score = block.header.daa_score;
-                                        let mut chain = self.chain.lock().unwrap();
-                                        if let Some(bucket) = chain.get_mut(&daa_score) {
-                                            bucket.update_vspc(*hash, false, &settings);
-                                        }
-                                    }
-                                });
-                                added_chain_block_hashes.iter().for_each(|hash| {
-                                    if let Some(block) = blocks_by_hash.get(hash) {
-                                        let daa_score = block.header.daa_score;
-                                        let mut chain = self.chain.lock().unwrap();
-                                        if let Some(bucket) = chain.get_mut(&daa_score) {
-                                            bucket.update_vspc(*hash, true, &settings);
-                                        }
-                                    }
-                                });
-                                // println!("VirtualChainChanged: {:?}", virtual_chain_changed_notification);
-                            },
-                            _ => {
-                                // println!("notification: {:?}", notification);
+                                }
+                                runtime().request_repaint();
                             }
                         }
-
-                        runtime().request_repaint();
                     } else {
                         break;
                     }
@@ -307,8 +736,30 @@ impl Service for BlockDagMonitorService {
                                 break;
                             }
                             BlockDagMonitorEvents::Reset => {
-                                self.chain.lock().unwrap().clear();
-                                blocks_by_hash.clear();
+                                if replay.is_none() {
+                                    self.reset_chain(&mut blocks_by_hash);
+                                }
+                            }
+                            BlockDagMonitorEvents::Replay(records) => {
+                                self.reset_chain(&mut blocks_by_hash);
+                                self.replay_status.lock().unwrap().replace(ReplayStatus {
+                                    duration: records.last().map(|record| record.timestamp).unwrap_or_default(),
+                                    total: records.len(),
+                                    ..Default::default()
+                                });
+                                replay = Some(Replay {
+                                    records,
+                                    index: 0,
+                                    position: 0.0,
+                                    last_tick: Instant::now(),
+                                });
+                            }
+                            BlockDagMonitorEvents::StopReplay => {
+                                if replay.take().is_some() {
+                                    self.replay_status.lock().unwrap().take();
+                                    self.reset_chain(&mut blocks_by_hash);
+                                    runtime().request_repaint();
+                                }
                             }
                             BlockDagMonitorEvents::Settings(new_settings) => {
                                 *self.settings.lock().unwrap() = new_settings.clone();
@@ -326,6 +777,13 @@ impl Service for BlockDagMonitorService {
             }
         }
 
+        self.classify_queue.sender.close();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(err) = self.stop_recording() {
+            log_error!("Block DAG: unable to finalize recording: {err}");
+        }
+
         self.task_ctl.send(()).await.unwrap();
         Ok(())
     }
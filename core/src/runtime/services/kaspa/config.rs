@@ -1,6 +1,9 @@
 use crate::app::{GIT_DESCRIBE, VERSION};
 use crate::imports::*;
-use crate::settings::NodeMemoryScale;
+use crate::settings::{
+    NodeMemoryScale, NodePeerMode, NodeProcessSettings, DEFAULT_P2P_INBOUND_LIMIT,
+    DEFAULT_P2P_OUTBOUND_TARGET,
+};
 use crate::utils::Arglist;
 use kaspa_core::kaspad_env;
 #[cfg(not(target_arch = "wasm32"))]
@@ -21,20 +24,31 @@ fn user_agent() -> String {
     )
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Config {
     network: Network,
     enable_upnp: bool,
     enable_wrpc_borsh: bool,
-    #[allow(dead_code)]
+    wrpc_borsh_network_interface: NetworkInterfaceConfig,
     enable_wrpc_json: bool,
+    wrpc_json_network_interface: NetworkInterfaceConfig,
     enable_grpc: bool,
     grpc_network_interface: NetworkInterfaceConfig,
+    p2p_network_interface: NetworkInterfaceConfig,
+    p2p_outbound_target: usize,
+    p2p_inbound_limit: usize,
+    p2p_peer_mode: NodePeerMode,
+    p2p_peers: Vec<ContextualNetAddress>,
+    archival: bool,
     kaspad_daemon_args_enable: bool,
     kaspad_daemon_args: String,
     kaspad_daemon_storage_folder_enable: bool,
     kaspad_daemon_storage_folder: String,
     memory_scale: NodeMemoryScale,
+    reset_db: bool,
+    reset_utxoindex: bool,
+    background: bool,
+    process: NodeProcessSettings,
 }
 
 impl From<NodeSettings> for Config {
@@ -43,14 +57,189 @@ impl From<NodeSettings> for Config {
             network: node_settings.network,
             enable_upnp: node_settings.enable_upnp,
             enable_wrpc_borsh: node_settings.enable_wrpc_borsh,
+            wrpc_borsh_network_interface: node_settings.wrpc_borsh_network_interface,
             enable_wrpc_json: node_settings.enable_wrpc_json,
+            wrpc_json_network_interface: node_settings.wrpc_json_network_interface,
             enable_grpc: node_settings.enable_grpc,
             grpc_network_interface: node_settings.grpc_network_interface,
+            p2p_network_interface: node_settings.p2p_network_interface,
+            p2p_outbound_target: node_settings.p2p_outbound_target,
+            p2p_inbound_limit: node_settings.p2p_inbound_limit,
+            p2p_peer_mode: node_settings.p2p_peer_mode,
+            p2p_peers: node_settings.p2p_peers,
+            archival: node_settings.archival,
             kaspad_daemon_args_enable: node_settings.kaspad_daemon_args_enable,
             kaspad_daemon_args: node_settings.kaspad_daemon_args,
             kaspad_daemon_storage_folder_enable: node_settings.kaspad_daemon_storage_folder_enable,
             kaspad_daemon_storage_folder: node_settings.kaspad_daemon_storage_folder,
             memory_scale: node_settings.memory_scale,
+            reset_db: false,
+            reset_utxoindex: false,
+            background: node_settings.run_in_background
+                && node_settings.node_kind.is_background_capable(),
+            process: node_settings.process,
+        }
+    }
+}
+
+impl Config {
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Indicates that the node should remain running
+    /// when the user switches to a different network.
+    pub fn is_background(&self) -> bool {
+        self.background
+    }
+
+    /// Resource controls applied to the node process.
+    pub fn process(&self) -> &NodeProcessSettings {
+        &self.process
+    }
+
+    /// Compares node configurations, ignoring one-time maintenance
+    /// flags and the background flag, to determine if a running
+    /// node can be reused.
+    pub fn is_equivalent(&self, other: &Config) -> bool {
+        let normalize = |config: &Config| Config {
+            reset_db: false,
+            reset_utxoindex: false,
+            background: false,
+            ..config.clone()
+        };
+        normalize(self) == normalize(other)
+    }
+
+    /// Interface on which the node listens for wRPC Borsh connections.
+    /// A custom interface (set when resolving port conflicts) takes
+    /// precedence over the public/local selection.
+    pub fn wrpc_borsh_listen(&self) -> ContextualNetAddress {
+        if self.wrpc_borsh_network_interface.kind == NetworkInterfaceKind::Custom {
+            self.wrpc_borsh_network_interface.custom.clone()
+        } else if self.enable_wrpc_borsh {
+            ContextualNetAddress::unspecified()
+        } else {
+            ContextualNetAddress::loopback()
+        }
+    }
+
+    /// URL used by the wallet to connect to the wRPC Borsh listener
+    /// of the integrated node. A wildcard listen address is reached
+    /// through the loopback interface.
+    pub fn wrpc_borsh_url(&self) -> String {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+        let network_id = NetworkId::from(self.network);
+        let address: SocketAddr = self
+            .wrpc_borsh_listen()
+            .normalize(network_id.default_borsh_rpc_port())
+            .into();
+        let ip = match address.ip() {
+            IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            ip => ip,
+        };
+        SocketAddr::new(ip, address.port()).to_string()
+    }
+
+    /// Instructs the node to erase its databases on startup (`--reset-db`).
+    pub fn with_reset_db(mut self, reset_db: bool) -> Self {
+        self.reset_db = reset_db;
+        self
+    }
+
+    /// Instructs the node to rebuild the UTXO index on startup.
+    pub fn with_reset_utxoindex(mut self, reset_utxoindex: bool) -> Self {
+        self.reset_utxoindex = reset_utxoindex;
+        self
+    }
+}
+
+cfg_if! {
+    if #[cfg(not(target_arch = "wasm32"))] {
+        use crate::runtime::services::kaspa::maintenance::{DATA_DIR, UTXOINDEX_DB};
+        use crate::runtime::services::kaspa::ports::{NodePort, PortBinding};
+
+        impl Config {
+            /// Socket addresses the node will bind. wRPC listeners are
+            /// included only if `include_wrpc` is set, as the in-process
+            /// node is accessed directly and does not start them.
+            pub fn bindings(&self, include_wrpc: bool) -> Vec<PortBinding> {
+                let network_id = NetworkId::from(self.network);
+                let mut bindings = vec![PortBinding::new(
+                    NodePort::P2p,
+                    ContextualNetAddress::from(self.p2p_network_interface.clone())
+                        .normalize(network_id.default_p2p_port())
+                        .into(),
+                )];
+
+                if self.enable_grpc {
+                    bindings.push(PortBinding::new(
+                        NodePort::Grpc,
+                        ContextualNetAddress::from(self.grpc_network_interface.clone())
+                            .normalize(network_id.default_rpc_port())
+                            .into(),
+                    ));
+                }
+
+                if include_wrpc {
+                    bindings.push(PortBinding::new(
+                        NodePort::WrpcBorsh,
+                        self.wrpc_borsh_listen()
+                            .normalize(network_id.default_borsh_rpc_port())
+                            .into(),
+                    ));
+
+                    if self.enable_wrpc_json {
+                        bindings.push(PortBinding::new(
+                            NodePort::WrpcJson,
+                            ContextualNetAddress::from(self.wrpc_json_network_interface.clone())
+                                .normalize(network_id.default_json_rpc_port())
+                                .into(),
+                        ));
+                    }
+                }
+
+                bindings
+            }
+
+            /// Application folder used by the node, taking into account
+            /// the custom storage folder and the `--appdir` daemon argument.
+            pub fn app_dir(&self) -> PathBuf {
+                let appdir = self.kaspad_daemon_args_enable.then(|| {
+                    self.kaspad_daemon_args
+                        .split(' ')
+                        .find_map(|arg| arg.trim().strip_prefix("--appdir="))
+                        .map(PathBuf::from)
+                }).flatten();
+
+                if let Some(appdir) = appdir {
+                    appdir
+                } else if self.kaspad_daemon_storage_folder_enable && !self.kaspad_daemon_storage_folder.trim().is_empty() {
+                    PathBuf::from(self.kaspad_daemon_storage_folder.trim())
+                } else {
+                    kaspad_lib::daemon::get_app_dir()
+                }
+            }
+
+            /// Database folder of the node for the configured network.
+            pub fn data_dir(&self) -> PathBuf {
+                self.app_dir().join(format!("kaspa-{}", self.network)).join(DATA_DIR)
+            }
+
+            /// Applies maintenance flags that must be handled before
+            /// the node is started.
+            pub fn prepare(&self) -> Result<()> {
+                if self.reset_utxoindex && !self.reset_db {
+                    let utxoindex = self.data_dir().join(UTXOINDEX_DB);
+                    if utxoindex.exists() {
+                        log_info!("Removing UTXO index: {}", utxoindex.display());
+                        std::fs::remove_dir_all(&utxoindex)?;
+                    }
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -79,11 +268,26 @@ cfg_if! {
                 args.yes = true;
                 args.utxoindex = true;
                 args.disable_upnp = !config.enable_upnp;
+                args.reset_db = config.reset_db;
 
                 if config.enable_grpc {
                     args.rpclisten = Some(config.grpc_network_interface.into());
                 }
 
+                args.listen = Some(config.p2p_network_interface.into());
+                args.outbound_target = config.p2p_outbound_target;
+                args.inbound_limit = config.p2p_inbound_limit;
+                match config.p2p_peer_mode {
+                    NodePeerMode::Default => {}
+                    NodePeerMode::AddPeers => {
+                        args.add_peers = config.p2p_peers;
+                    }
+                    NodePeerMode::ConnectOnly => {
+                        args.connect_peers = config.p2p_peers;
+                    }
+                }
+                args.archival = config.archival;
+
                 args.user_agent_comments = vec![user_agent_comment()];
 
                 // TODO - parse custom args and overlap on top of the defaults
@@ -113,6 +317,10 @@ cfg_if! {
                 args.push("--yes");
                 args.push("--utxoindex");
 
+                if config.reset_db {
+                    args.push("--reset-db");
+                }
+
                 match config.memory_scale {
                     NodeMemoryScale::Default => {},
                     _ => {
@@ -130,10 +338,40 @@ cfg_if! {
                     args.push("--nogrpc");
                 }
 
-                if config.enable_wrpc_borsh {
-                    args.push("--rpclisten-borsh=0.0.0.0");
-                } else {
-                    args.push("--rpclisten-borsh=127.0.0.1");
+                if config.p2p_network_interface.kind != NetworkInterfaceKind::Any {
+                    args.push(format!("--listen={}", config.p2p_network_interface));
+                }
+
+                if config.p2p_outbound_target != DEFAULT_P2P_OUTBOUND_TARGET {
+                    args.push(format!("--outpeers={}", config.p2p_outbound_target));
+                }
+
+                if config.p2p_inbound_limit != DEFAULT_P2P_INBOUND_LIMIT {
+                    args.push(format!("--maxinpeers={}", config.p2p_inbound_limit));
+                }
+
+                match config.p2p_peer_mode {
+                    NodePeerMode::Default => {}
+                    NodePeerMode::AddPeers => {
+                        config.p2p_peers.iter().for_each(|peer| {
+                            args.push(format!("--addpeer={peer}"));
+                        });
+                    }
+                    NodePeerMode::ConnectOnly => {
+                        config.p2p_peers.iter().for_each(|peer| {
+                            args.push(format!("--connect={peer}"));
+                        });
+                    }
+                }
+
+                if config.archival {
+                    args.push("--archival");
+                }
+
+                args.push(format!("--rpclisten-borsh={}", config.wrpc_borsh_listen()));
+
+                if config.enable_wrpc_json {
+                    args.push(format!("--rpclisten-json={}", config.wrpc_json_network_interface));
                 }
 
                 args.push(format!("--uacomment={}", user_agent_comment()));
@@ -1,5 +1,5 @@
 use crate::imports::*;
-use crate::runtime::services::kaspa::{Config, KaspadServiceEvents};
+use crate::runtime::services::kaspa::{process, Config, KaspadServiceEvents};
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
@@ -64,6 +64,10 @@ impl Daemon {
 #[async_trait]
 impl super::Kaspad for Daemon {
     async fn start(self: Arc<Self>, config: Config) -> Result<()> {
+        config.prepare()?;
+        let network = config.network();
+        let process_settings = config.process().clone();
+
         let mut cmd = if let Some(path) = self.inner().path.clone() {
             Command::new(path)
         } else {
@@ -75,6 +79,7 @@ impl super::Kaspad for Daemon {
             .args(config)
             .env("KASPA_NG_DAEMON", "1")
             .stdout(Stdio::piped());
+        let mut issues = process::configure_process_controls(cmd, &process_settings);
 
         let is_running = self.inner().is_running.clone();
         is_running.store(true, Ordering::SeqCst);
@@ -82,6 +87,18 @@ impl super::Kaspad for Daemon {
         let stdout = child.stdout.take().ok_or(Error::NodeStdoutHandleError)?;
         *self.inner.pid.lock().unwrap() = child.id();
 
+        if let Some(pid) = child.id() {
+            issues.extend(process::apply_process_controls(
+                pid,
+                network,
+                &process_settings,
+            ));
+        }
+        if !issues.is_empty() {
+            issues.iter().for_each(|issue| log_warn!("kaspad: {issue}"));
+            runtime().notify(UserNotification::warning(issues.join("; ")));
+        }
+
         let mut reader = BufReader::new(stdout).lines();
         let stdout_relay_sender = self.inner.service_events.sender.clone();
         let task_ctl = self.inner.task_ctl.clone();
@@ -120,13 +137,14 @@ impl super::Kaspad for Daemon {
                             }
                         }
                         is_running.store(false,Ordering::SeqCst);
+                        process::release_process_controls(network);
                         break;
                     }
 
                     line = reader.next_line().fuse() => {
                         if let Ok(Some(line)) = line {
                             // println!("kaspad: {}", line);
-                            stdout_relay_sender.send(KaspadServiceEvents::Stdout { line }).await.unwrap();
+                            stdout_relay_sender.send(KaspadServiceEvents::Stdout { network, line }).await.unwrap();
                         }
                     }
                 }
@@ -144,4 +162,12 @@ impl super::Kaspad for Daemon {
         }
         Ok(())
     }
+
+    fn is_running(&self) -> bool {
+        self.inner().is_running.load(Ordering::SeqCst)
+    }
+
+    fn pid(&self) -> Option<u32> {
+        *self.inner().pid.lock().unwrap()
+    }
 }
@@ -31,6 +31,7 @@ impl InProc {
 #[async_trait]
 impl super::Kaspad for InProc {
     async fn start(self: Arc<Self>, config: Config) -> Result<()> {
+        config.prepare()?;
         let args = Args::try_from(config)?;
 
         let fd_total_budget = fd_budget::limit()
@@ -69,4 +70,8 @@ impl super::Kaspad for InProc {
         }
         Ok(())
     }
+
+    fn is_running(&self) -> bool {
+        self.inner.lock().unwrap().is_some()
+    }
 }
@@ -0,0 +1,141 @@
+use crate::imports::*;
+
+/// Name of the node database folder within the network folder
+pub const DATA_DIR: &str = "datadir";
+/// Name of the consensus database folder within the database folder
+pub const CONSENSUS_DB: &str = "consensus";
+/// Name of the meta database folder within the database folder
+pub const META_DB: &str = "meta";
+/// Name of the UTXO index database folder within the database folder
+pub const UTXOINDEX_DB: &str = "utxoindex";
+
+/// Guided maintenance operations that can be performed
+/// on the database of the integrated node.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NodeMaintenance {
+    Resync,
+    RebuildUtxoIndex,
+    IntegrityCheck,
+}
+
+const NODE_MAINTENANCE_OPERATIONS: [NodeMaintenance; 3] = [
+    NodeMaintenance::Resync,
+    NodeMaintenance::RebuildUtxoIndex,
+    NodeMaintenance::IntegrityCheck,
+];
+
+impl std::fmt::Display for NodeMaintenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeMaintenance::Resync => write!(f, "{}", i18n("Full Resync")),
+            NodeMaintenance::RebuildUtxoIndex => write!(f, "{}", i18n("Rebuild UTXO Index")),
+            NodeMaintenance::IntegrityCheck => write!(f, "{}", i18n("Check Integrity")),
+        }
+    }
+}
+
+impl NodeMaintenance {
+    pub fn iter() -> impl Iterator<Item = &'static NodeMaintenance> {
+        NODE_MAINTENANCE_OPERATIONS.iter()
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            NodeMaintenance::Resync => i18n("This action will erase the Kaspa database (logs are preserved) and restart the node, which will then resynchronize from the network."),
+            NodeMaintenance::RebuildUtxoIndex => i18n("This action will erase the UTXO index and restart the node, which will then rebuild the index from the consensus database."),
+            NodeMaintenance::IntegrityCheck => i18n("This action will stop the node, verify the Kaspa database files and restart the node."),
+        }
+    }
+}
+
+/// Progress of a maintenance operation, reported
+/// to the user through the sync status UI.
+#[derive(Debug, Clone)]
+pub struct MaintenanceStatus {
+    pub operation: NodeMaintenance,
+    pub network: Network,
+    pub caption: String,
+    pub progress: Option<f32>,
+}
+
+impl MaintenanceStatus {
+    pub fn new(operation: NodeMaintenance, network: Network, caption: impl Into<String>) -> Self {
+        Self {
+            operation,
+            network,
+            caption: caption.into(),
+            progress: None,
+        }
+    }
+
+    pub fn with_progress(mut self, progress: f32) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+cfg_if! {
+    if #[cfg(not(target_arch = "wasm32"))] {
+
+        /// Verify the contents of all RocksDB databases found in the
+        /// node database folder. Returns a list of detected issues.
+        /// The `progress` callback receives the number of processed
+        /// databases and the total number of databases.
+        pub fn check_integrity(data_dir: &Path, progress: impl Fn(usize, usize)) -> Vec<String> {
+            let mut issues = Vec::new();
+
+            if !data_dir.exists() {
+                issues.push(format!("{} {}", i18n("Database folder not found:"), data_dir.display()));
+                return issues;
+            }
+
+            for database in [CONSENSUS_DB, META_DB, UTXOINDEX_DB] {
+                if !data_dir.join(database).exists() {
+                    issues.push(format!("{} '{database}'", i18n("Missing database:")));
+                }
+            }
+
+            let databases = walkdir::WalkDir::new(data_dir)
+                .into_iter()
+                .flatten()
+                .filter(|entry| entry.file_type().is_dir() && entry.path().join("CURRENT").is_file())
+                .map(|entry| entry.into_path())
+                .collect::<Vec<_>>();
+
+            let total = databases.len();
+            for (index, path) in databases.iter().enumerate() {
+                progress(index, total);
+                if let Err(err) = check_database(path) {
+                    let name = path.strip_prefix(data_dir).unwrap_or(path);
+                    issues.push(format!("{}: {err}", name.display()));
+                }
+            }
+            progress(total, total);
+
+            issues
+        }
+
+        /// Open the database read-only and read every entry of every
+        /// column family with checksum verification enabled.
+        fn check_database(path: &Path) -> Result<()> {
+            let options = rocksdb::Options::default();
+            let column_families = rocksdb::DB::list_cf(&options, path).map_err(|err| Error::custom(err.to_string()))?;
+            let db = rocksdb::DB::open_cf_for_read_only(&options, path, &column_families, false)
+                .map_err(|err| Error::custom(err.to_string()))?;
+
+            for name in column_families.iter() {
+                let column_family = db
+                    .cf_handle(name)
+                    .ok_or_else(|| Error::custom(format!("column family '{name}' is missing")))?;
+                let mut read_options = rocksdb::ReadOptions::default();
+                read_options.set_verify_checksums(true);
+                read_options.fill_cache(false);
+                for entry in db.iterator_cf_opt(column_family, read_options, rocksdb::IteratorMode::Start) {
+                    entry.map_err(|err| Error::custom(format!("column family '{name}': {err}")))?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
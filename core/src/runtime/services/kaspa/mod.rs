@@ -11,6 +11,12 @@ use kaspa_wrpc_client::Resolver;
 use workflow_core::runtime;
 
 const ENABLE_PREEMPTIVE_DISCONNECT: bool = true;
+const NODE_STATUS_POLLING_INTERVAL_SECONDS: u64 = 5;
+
+pub mod maintenance;
+pub use maintenance::{MaintenanceStatus, NodeMaintenance};
+pub mod ports;
+pub use ports::{NodePort, PortConflict};
 
 cfg_if! {
     if #[cfg(not(target_arch = "wasm32"))] {
@@ -32,12 +38,19 @@ cfg_if! {
         pub mod inproc;
         pub mod logs;
         use logs::Log;
+        pub mod nodes;
+        pub use nodes::{NodeInstance, NodeStatus};
+        pub mod process;
+        use process::{ProcessMonitor, ProcessStats};
+        use kaspa_rpc_core::GetServerInfoResponse;
         pub use kaspad_lib::args::Args;
 
         #[async_trait]
         pub trait Kaspad {
             async fn start(self : Arc<Self>, config : Config) -> Result<()>;
             async fn stop(self : Arc<Self>) -> Result<()>;
+            fn is_running(&self) -> bool;
+            fn pid(&self) -> Option<u32> { None }
         }
 
         #[derive(Debug, Clone)]
@@ -47,7 +60,10 @@ cfg_if! {
             StartInternalAsPassiveSync { config: Config, network : Network },
             StartExternalAsDaemon { path: PathBuf, config: Config, network : Network },
             StartRemoteConnection { rpc_config : RpcConfig, network : Network },
-            Stdout { line : String },
+            Maintenance { operation : NodeMaintenance, node_settings : Box<NodeSettings> },
+            NodeStatus { network : Network, process_stats : Option<ProcessStats>, server_info : Option<Box<GetServerInfoResponse>> },
+            Stdout { network : Network, line : String },
+            StopNode { network : Network },
             Disable { network : Network },
             Exit,
         }
@@ -85,7 +101,11 @@ pub struct KaspaService {
     pub wallet: Arc<dyn WalletApi>,
     pub services_start_instant: Mutex<Option<Instant>>,
     #[cfg(not(target_arch = "wasm32"))]
-    pub kaspad: Mutex<Option<Arc<dyn Kaspad + Send + Sync + 'static>>>,
+    pub nodes: Mutex<HashMap<Network, Arc<NodeInstance>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    process_monitor: Arc<ProcessMonitor>,
+    #[cfg(not(target_arch = "wasm32"))]
+    node_status_polling: Arc<AtomicBool>,
     #[cfg(not(target_arch = "wasm32"))]
     pub logs: Mutex<Vec<Log>>,
     pub connect_on_startup: Option<NodeSettings>,
@@ -128,7 +148,11 @@ impl KaspaService {
             wallet,
             services_start_instant: Mutex::new(None),
             #[cfg(not(target_arch = "wasm32"))]
-            kaspad: Mutex::new(None),
+            nodes: Mutex::new(HashMap::new()),
+            #[cfg(not(target_arch = "wasm32"))]
+            process_monitor: Arc::new(ProcessMonitor::default()),
+            #[cfg(not(target_arch = "wasm32"))]
+            node_status_polling: Arc::new(AtomicBool::new(false)),
             #[cfg(not(target_arch = "wasm32"))]
             logs: Mutex::new(Vec::new()),
         }
@@ -152,9 +176,289 @@ impl KaspaService {
         Ok(())
     }
 
+    /// Stop the integrated node, perform the maintenance `operation`
+    /// and restart the node using the supplied `node_settings`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn maintenance(&self, operation: NodeMaintenance, node_settings: &NodeSettings) {
+        self.service_events
+            .sender
+            .try_send(KaspadServiceEvents::Maintenance {
+                operation,
+                node_settings: Box::new(node_settings.clone()),
+            })
+            .unwrap_or_else(|err| {
+                log_error!("KaspadService error: {}", err);
+            });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_maintenance(
+        &self,
+        operation: NodeMaintenance,
+        node_settings: &NodeSettings,
+    ) -> Result<KaspadServiceEvents> {
+        let network = node_settings.network;
+
+        self.update_maintenance_status(Some(MaintenanceStatus::new(
+            operation,
+            network,
+            i18n("Stopping node..."),
+        )));
+        self.stop_all_services().await?;
+        self.stop_node(network).await;
+
+        let config = Config::from(node_settings.clone());
+        let config = match operation {
+            NodeMaintenance::Resync => config.with_reset_db(true),
+            NodeMaintenance::RebuildUtxoIndex => config.with_reset_utxoindex(true),
+            NodeMaintenance::IntegrityCheck => {
+                let data_dir = config.data_dir();
+                let sender = self.application_events.sender.clone();
+                // filesystem traversal is performed off the service task
+                let issues = tokio::task::spawn_blocking(move || {
+                    maintenance::check_integrity(&data_dir, |processed, total| {
+                        let progress = if total > 0 {
+                            processed as f32 / total as f32
+                        } else {
+                            1.0
+                        };
+                        let status = MaintenanceStatus::new(
+                            operation,
+                            network,
+                            i18n("Checking database integrity..."),
+                        )
+                        .with_progress(progress);
+                        sender
+                            .try_send(Events::NodeMaintenance {
+                                status: Some(status),
+                            })
+                            .ok();
+                    })
+                })
+                .await
+                .map_err(|err| Error::custom(err.to_string()))?;
+
+                if issues.is_empty() {
+                    runtime().notify(UserNotification::success(i18n(
+                        "Database integrity check completed successfully",
+                    )));
+                } else {
+                    for issue in issues.iter() {
+                        log_error!("Database integrity check: {issue}");
+                    }
+                    runtime().notify(UserNotification::error(format!(
+                        "{} {}",
+                        i18n("Database integrity check has detected problems:"),
+                        issues.join("; ")
+                    )));
+                }
+
+                config
+            }
+        };
+
+        self.update_maintenance_status(Some(MaintenanceStatus::new(
+            operation,
+            network,
+            i18n("Restarting node..."),
+        )));
+
+        KaspadServiceEvents::from_node_settings_with_config(node_settings, config, None)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_maintenance_status(&self, status: Option<MaintenanceStatus>) {
+        self.application_events
+            .sender
+            .try_send(Events::NodeMaintenance { status })
+            .ok();
+    }
+
+    /// Probe ports the node is configured to bind. If conflicts are
+    /// detected, the user is notified and `false` is returned.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn probe_ports(&self, config: &Config, include_wrpc: bool) -> bool {
+        let network = config.network();
+        let conflicts = ports::probe(network, &config.bindings(include_wrpc));
+        let is_available = conflicts.is_empty();
+
+        if !is_available {
+            let conflicts = conflicts
+                .iter()
+                .map(|conflict| conflict.to_string())
+                .collect::<Vec<_>>()
+                .join("; ");
+            log_error!("Unable to start the node: {conflicts}");
+            runtime().notify(UserNotification::error(format!(
+                "{} {conflicts}",
+                i18n("Unable to start the node, ports are in use:")
+            )));
+        }
+
+        self.application_events
+            .sender
+            .try_send(Events::NodePortConflicts { conflicts })
+            .ok();
+
+        is_available
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn retain(&self, node: NodeInstance) {
+        self.nodes
+            .lock()
+            .unwrap()
+            .insert(node.network, Arc::new(node));
+    }
+
+    /// Status of all integrated nodes managed by the service.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn node_status(&self) -> Vec<NodeStatus> {
+        let network = self.network();
+        let mut status = self
+            .nodes
+            .lock()
+            .unwrap()
+            .values()
+            .map(|node| node.status(node.network == network))
+            .collect::<Vec<_>>();
+        status.sort_by_key(|status| status.network);
+        status
+    }
+
+    /// Stop a node running in the background.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stop_background_node(&self, network: Network) {
+        self.service_events
+            .sender
+            .try_send(KaspadServiceEvents::StopNode { network })
+            .unwrap_or_else(|err| {
+                log_error!("KaspadService error: {}", err);
+            });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn stop_node(&self, network: Network) {
+        let node = self.nodes.lock().unwrap().remove(&network);
+        if let Some(node) = node {
+            if let Err(err) = node.stop().await {
+                println!("error shutting down kaspad: {}", err);
+            }
+        }
+    }
+
+    /// Stop integrated nodes. Nodes configured to run in
+    /// the background are kept if `retain_background` is set.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn stop_nodes(&self, retain_background: bool) {
+        let networks = self
+            .nodes
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|node| !(retain_background && node.is_background()))
+            .map(|node| node.network)
+            .collect::<Vec<_>>();
+
+        for network in networks {
+            self.stop_node(network).await;
+        }
+    }
+
+    /// Start a daemon for `network` unless a node started with an
+    /// equivalent configuration is already running (in the background).
+    /// Returns `false` if the node could not be started.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn retain(&self, kaspad: Arc<dyn Kaspad + Send + Sync + 'static>) {
-        self.kaspad.lock().unwrap().replace(kaspad);
+    async fn start_daemon(
+        &self,
+        path: Option<PathBuf>,
+        node_kind: KaspadNodeKind,
+        config: Config,
+        network: Network,
+    ) -> Result<bool> {
+        let node = self.nodes.lock().unwrap().get(&network).cloned();
+        if let Some(node) = node {
+            if node.is_equivalent(node_kind, &config) {
+                node.set_background(config.is_background());
+                node.close_monitor().await;
+                return Ok(true);
+            }
+        }
+
+        self.stop_node(network).await;
+
+        if !self.probe_ports(&config, true) {
+            return Ok(false);
+        }
+
+        let kaspad = Arc::new(daemon::Daemon::new(path, &self.service_events));
+        kaspad.clone().start(config.clone()).await.unwrap();
+        let background = config.is_background();
+        self.retain(NodeInstance::new(
+            network, node_kind, config, kaspad, background,
+        ));
+
+        Ok(true)
+    }
+
+    /// Sample resource usage of node processes and poll the server
+    /// info of nodes running in the background. Polling is performed
+    /// in a separate task that posts the results back to the service
+    /// as `KaspadServiceEvents::NodeStatus` events.
+    fn poll_node_status(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // skip the poll if the previous one is still in progress
+            if self.node_status_polling.swap(true, Ordering::SeqCst) {
+                return;
+            }
+
+            let network = self.network();
+            let nodes = self
+                .nodes
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|node| node.kaspad.is_running())
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let process_monitor = self.process_monitor.clone();
+            let node_status_polling = self.node_status_polling.clone();
+            let sender = self.service_events.sender.clone();
+            spawn(async move {
+                for node in nodes {
+                    let process_stats = node
+                        .kaspad
+                        .pid()
+                        .and_then(|pid| process_monitor.sample(pid));
+
+                    let server_info = if node.network != network {
+                        match node.query_server_info().await {
+                            Ok(server_info) => Some(Box::new(server_info)),
+                            Err(err) => {
+                                log_warn!("Unable to query {} node: {err}", node.network);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
+                    sender
+                        .send(KaspadServiceEvents::NodeStatus {
+                            network: node.network,
+                            process_stats,
+                            server_info,
+                        })
+                        .await
+                        .ok();
+                }
+
+                node_status_polling.store(false, Ordering::SeqCst);
+                Ok(())
+            });
+        }
     }
 
     pub fn create_rpc_client(config: &RpcConfig, network: Network) -> Result<Rpc> {
@@ -278,6 +582,13 @@ impl KaspaService {
         }
     }
 
+    /// RPC interface of the node the wallet is currently connected to.
+    pub fn rpc_api(&self) -> Option<Arc<DynRpcApi>> {
+        self.core_wallet()
+            .filter(|wallet| wallet.has_rpc())
+            .map(|wallet| wallet.rpc_api().clone())
+    }
+
     fn is_wrpc_client(&self) -> bool {
         if let Some(wallet) = self.core_wallet() {
             wallet.has_rpc()
@@ -336,14 +647,7 @@ impl KaspaService {
             wallet.bind_rpc(None).await?;
 
             #[cfg(not(target_arch = "wasm32"))]
-            {
-                let kaspad = self.kaspad.lock().unwrap().take();
-                if let Some(kaspad) = kaspad {
-                    if let Err(err) = kaspad.stop().await {
-                        println!("error shutting down kaspad: {}", err);
-                    }
-                }
-            }
+            self.stop_nodes(true).await;
         } else {
             self.wallet().disconnect().await?;
         }
@@ -455,7 +759,13 @@ impl KaspaService {
     async fn handle_event(self: &Arc<Self>, event: KaspadServiceEvents) -> Result<bool> {
         match event {
             #[cfg(not(target_arch = "wasm32"))]
-            KaspadServiceEvents::Stdout { line } => {
+            KaspadServiceEvents::Stdout { network, line } => {
+                // output of nodes running in the background
+                // is not relayed to the wallet or logs
+                if network != self.network() {
+                    return Ok(false);
+                }
+
                 let wallet = self.core_wallet().ok_or(Error::WalletIsNotLocal)?;
                 if !wallet.utxo_processor().is_synced() {
                     wallet
@@ -474,10 +784,21 @@ impl KaspaService {
 
                 self.handle_network_change(network).await?;
 
-                let kaspad = Arc::new(inproc::InProc::default());
-                self.retain(kaspad.clone());
+                self.stop_node(network).await;
 
-                kaspad.clone().start(config).await.unwrap();
+                if !self.probe_ports(&config, false) {
+                    return Ok(false);
+                }
+
+                let kaspad = Arc::new(inproc::InProc::default());
+                kaspad.clone().start(config.clone()).await.unwrap();
+                self.retain(NodeInstance::new(
+                    network,
+                    KaspadNodeKind::IntegratedInProc,
+                    config,
+                    kaspad.clone(),
+                    false,
+                ));
 
                 let rpc_api = kaspad
                     .rpc_core_services()
@@ -496,12 +817,16 @@ impl KaspaService {
 
                 self.handle_network_change(network).await?;
 
-                let kaspad = Arc::new(daemon::Daemon::new(None, &self.service_events));
-                self.retain(kaspad.clone());
-                kaspad.clone().start(config).await.unwrap();
+                let url = config.wrpc_borsh_url();
+                if !self
+                    .start_daemon(None, KaspadNodeKind::IntegratedAsDaemon, config, network)
+                    .await?
+                {
+                    return Ok(false);
+                }
 
                 let rpc_config = RpcConfig::Wrpc {
-                    url: Some("127.0.0.1".to_string()),
+                    url: Some(url),
                     encoding: WrpcEncoding::Borsh,
                     resolver_urls: None,
                 };
@@ -519,9 +844,17 @@ impl KaspaService {
 
                 self.handle_network_change(network).await?;
 
-                let kaspad = Arc::new(daemon::Daemon::new(None, &self.service_events));
-                self.retain(kaspad.clone());
-                kaspad.clone().start(config).await.unwrap();
+                if !self
+                    .start_daemon(
+                        None,
+                        KaspadNodeKind::IntegratedAsPassiveSync,
+                        config,
+                        network,
+                    )
+                    .await?
+                {
+                    return Ok(false);
+                }
 
                 let rpc_config = RpcConfig::Wrpc {
                     url: None,
@@ -546,10 +879,17 @@ impl KaspaService {
 
                 self.handle_network_change(network).await?;
 
-                let kaspad = Arc::new(daemon::Daemon::new(Some(path), &self.service_events));
-                self.retain(kaspad.clone());
-
-                kaspad.clone().start(config).await.unwrap();
+                if !self
+                    .start_daemon(
+                        Some(path),
+                        KaspadNodeKind::ExternalAsDaemon,
+                        config,
+                        network,
+                    )
+                    .await?
+                {
+                    return Ok(false);
+                }
 
                 let rpc_config = RpcConfig::Wrpc {
                     url: None,
@@ -564,6 +904,50 @@ impl KaspaService {
 
                 self.update_storage();
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            KaspadServiceEvents::Maintenance {
+                operation,
+                node_settings,
+            } => {
+                // the node is restarted in place so that the maintenance
+                // status remains visible until the restart completes or fails
+                let result = match self.perform_maintenance(operation, &node_settings).await {
+                    Ok(event) => Box::pin(self.handle_event(event)).await.map(|_| ()),
+                    Err(err) => Err(err),
+                };
+                self.update_maintenance_status(None);
+                if let Err(err) = result {
+                    log_error!("Node maintenance ({operation}): {err}");
+                    runtime().notify(UserNotification::error(format!(
+                        "{} ({operation}): {err}",
+                        i18n("Node maintenance has failed")
+                    )));
+                }
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            KaspadServiceEvents::NodeStatus {
+                network,
+                process_stats,
+                server_info,
+            } => {
+                let node = self.nodes.lock().unwrap().get(&network).cloned();
+                if let Some(node) = node {
+                    node.update_process_stats(process_stats);
+                    // the active node is monitored through the wallet connection
+                    if network != self.network() {
+                        node.update_server_info(server_info.map(|server_info| *server_info));
+                    }
+                }
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            KaspadServiceEvents::StopNode { network } => {
+                if network != self.network() {
+                    self.stop_node(network).await;
+                }
+            }
+
             KaspadServiceEvents::StartRemoteConnection {
                 rpc_config,
                 network,
@@ -578,6 +962,8 @@ impl KaspaService {
                     self.connect_rpc_client().await?;
                 } else {
                     self.stop_all_services().await?;
+                    #[cfg(not(target_arch = "wasm32"))]
+                    self.stop_node(network).await;
 
                     self.handle_network_change(network).await?;
 
@@ -591,6 +977,8 @@ impl KaspaService {
             KaspadServiceEvents::Disable { network } => {
                 if let Some(wallet) = self.core_wallet() {
                     self.stop_all_services().await?;
+                    #[cfg(not(target_arch = "wasm32"))]
+                    self.stop_node(network).await;
 
                     self.handle_network_change(network).await?;
 
@@ -800,8 +1188,16 @@ impl Service for KaspaService {
             // wallet.multiplexer().channel()
             let wallet_events = wallet.multiplexer().channel();
 
+            let interval =
+                task::interval(Duration::from_secs(NODE_STATUS_POLLING_INTERVAL_SECONDS));
+            pin_mut!(interval);
+
             loop {
                 select! {
+                    _ = interval.next().fuse() => {
+                        self.poll_node_status();
+                    }
+
                     msg = wallet_events.recv().fuse() => {
                     // msg = wallet.multiplexer().channel().recv().fuse() => {
                         if let Ok(event) = msg {
@@ -850,6 +1246,8 @@ impl Service for KaspaService {
         };
 
         self.stop_all_services().await?;
+        #[cfg(not(target_arch = "wasm32"))]
+        self.stop_nodes(false).await;
         self.task_ctl.send(()).await.unwrap();
 
         Ok(())
@@ -875,30 +1273,7 @@ impl KaspadServiceEvents {
     ) -> Result<Self> {
         cfg_if! {
             if #[cfg(not(target_arch = "wasm32"))] {
-
-                match &node_settings.node_kind {
-                    KaspadNodeKind::Disable => {
-                        Ok(KaspadServiceEvents::Disable { network : node_settings.network })
-                    }
-                    KaspadNodeKind::IntegratedInProc => {
-                        // let config = ;
-                        Ok(KaspadServiceEvents::StartInternalInProc { config : Config::from(node_settings.clone()), network : node_settings.network })
-                    }
-                    KaspadNodeKind::IntegratedAsDaemon => {
-                        Ok(KaspadServiceEvents::StartInternalAsDaemon { config : Config::from(node_settings.clone()), network : node_settings.network })
-                    }
-                    KaspadNodeKind::IntegratedAsPassiveSync => {
-                        Ok(KaspadServiceEvents::StartInternalAsPassiveSync { config : Config::from(node_settings.clone()), network : node_settings.network })
-                    }
-                    KaspadNodeKind::ExternalAsDaemon => {
-                        let path = node_settings.kaspad_daemon_binary.clone();
-                        Ok(KaspadServiceEvents::StartExternalAsDaemon { path : PathBuf::from(path), config : Config::from(node_settings.clone()), network : node_settings.network })
-                    }
-                    KaspadNodeKind::Remote => {
-                        Ok(KaspadServiceEvents::StartRemoteConnection { rpc_config : RpcConfig::from_node_settings(node_settings,options), network : node_settings.network })
-                    }
-                }
-
+                Self::from_node_settings_with_config(node_settings, Config::from(node_settings.clone()), options)
             } else {
 
                 match &node_settings.node_kind {
@@ -912,4 +1287,45 @@ impl KaspadServiceEvents {
             }
         }
     }
+
+    /// Create a service event from `node_settings`, using the supplied
+    /// node `config` (used to pass maintenance flags to the node).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_node_settings_with_config(
+        node_settings: &NodeSettings,
+        config: Config,
+        options: Option<RpcOptions>,
+    ) -> Result<Self> {
+        match &node_settings.node_kind {
+            KaspadNodeKind::Disable => Ok(KaspadServiceEvents::Disable {
+                network: node_settings.network,
+            }),
+            KaspadNodeKind::IntegratedInProc => Ok(KaspadServiceEvents::StartInternalInProc {
+                config,
+                network: node_settings.network,
+            }),
+            KaspadNodeKind::IntegratedAsDaemon => Ok(KaspadServiceEvents::StartInternalAsDaemon {
+                config,
+                network: node_settings.network,
+            }),
+            KaspadNodeKind::IntegratedAsPassiveSync => {
+                Ok(KaspadServiceEvents::StartInternalAsPassiveSync {
+                    config,
+                    network: node_settings.network,
+                })
+            }
+            KaspadNodeKind::ExternalAsDaemon => {
+                let path = node_settings.kaspad_daemon_binary.clone();
+                Ok(KaspadServiceEvents::StartExternalAsDaemon {
+                    path: PathBuf::from(path),
+                    config,
+                    network: node_settings.network,
+                })
+            }
+            KaspadNodeKind::Remote => Ok(KaspadServiceEvents::StartRemoteConnection {
+                rpc_config: RpcConfig::from_node_settings(node_settings, options),
+                network: node_settings.network,
+            }),
+        }
+    }
 }
@@ -0,0 +1,162 @@
+use crate::imports::*;
+use crate::runtime::services::kaspa::process::ProcessStats;
+use crate::runtime::services::kaspa::{Config, Kaspad};
+use kaspa_rpc_core::GetServerInfoResponse;
+use kaspa_wallet_core::rpc::{ConnectOptions, ConnectStrategy, Rpc, WrpcEncoding};
+
+/// Maximum time allowed for a background node to
+/// accept the monitoring connection or respond to a query.
+const MONITOR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Integrated node managed by the `KaspaService`. Nodes flagged
+/// as `background` are kept running when the user switches to
+/// a different network.
+pub struct NodeInstance {
+    pub network: Network,
+    pub node_kind: KaspadNodeKind,
+    pub config: Config,
+    pub kaspad: Arc<dyn Kaspad + Send + Sync + 'static>,
+    pub background: AtomicBool,
+    pub started: Instant,
+    monitor: Mutex<Option<Rpc>>,
+    server_info: Mutex<Option<GetServerInfoResponse>>,
+    process_stats: Mutex<Option<ProcessStats>>,
+}
+
+impl NodeInstance {
+    pub fn new(
+        network: Network,
+        node_kind: KaspadNodeKind,
+        config: Config,
+        kaspad: Arc<dyn Kaspad + Send + Sync + 'static>,
+        background: bool,
+    ) -> Self {
+        Self {
+            network,
+            node_kind,
+            config,
+            kaspad,
+            background: AtomicBool::new(background),
+            started: Instant::now(),
+            monitor: Mutex::new(None),
+            server_info: Mutex::new(None),
+            process_stats: Mutex::new(None),
+        }
+    }
+
+    pub fn is_background(&self) -> bool {
+        self.background.load(Ordering::Relaxed)
+    }
+
+    pub fn set_background(&self, background: bool) {
+        self.background.store(background, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the node has been started with the
+    /// same node kind and an equivalent configuration.
+    pub fn is_equivalent(&self, node_kind: KaspadNodeKind, config: &Config) -> bool {
+        self.node_kind == node_kind && self.config.is_equivalent(config) && self.kaspad.is_running()
+    }
+
+    /// Query the server info of a background node through a dedicated
+    /// wRPC connection. The connection is established on first use.
+    pub async fn query_server_info(&self) -> Result<GetServerInfoResponse> {
+        let (rpc_api, connect) = {
+            let mut monitor = self.monitor.lock().unwrap();
+            if let Some(rpc) = monitor.as_ref() {
+                (rpc.rpc_api().clone(), None)
+            } else {
+                let rpc_config = RpcConfig::Wrpc {
+                    url: Some(self.config.wrpc_borsh_url()),
+                    encoding: WrpcEncoding::Borsh,
+                    resolver_urls: None,
+                };
+                let rpc = super::KaspaService::create_rpc_client(&rpc_config, self.network)?;
+                let rpc_api = rpc.rpc_api().clone();
+                monitor.replace(rpc);
+                (
+                    rpc_api.clone(),
+                    rpc_api.downcast_arc::<KaspaRpcClient>().ok(),
+                )
+            }
+        };
+
+        if let Some(wrpc_client) = connect {
+            let options = ConnectOptions {
+                block_async_connect: false,
+                strategy: ConnectStrategy::Retry,
+                url: None,
+                connect_timeout: None,
+                retry_interval: Some(Duration::from_millis(3000)),
+            };
+            tokio::time::timeout(MONITOR_TIMEOUT, wrpc_client.connect(Some(options)))
+                .await
+                .map_err(|_| Error::custom("timeout connecting to the node"))??;
+        }
+
+        Ok(
+            tokio::time::timeout(MONITOR_TIMEOUT, rpc_api.get_server_info())
+                .await
+                .map_err(|_| Error::custom("timeout querying the node"))??,
+        )
+    }
+
+    pub fn update_server_info(&self, server_info: Option<GetServerInfoResponse>) {
+        *self.server_info.lock().unwrap() = server_info;
+    }
+
+    pub fn update_process_stats(&self, stats: Option<ProcessStats>) {
+        *self.process_stats.lock().unwrap() = stats;
+    }
+
+    /// Close the monitoring connection (used when the node
+    /// becomes the active node of the wallet).
+    pub async fn close_monitor(&self) {
+        let monitor = self.monitor.lock().unwrap().take();
+        if let Some(rpc) = monitor {
+            if let Ok(wrpc_client) = rpc.rpc_api().clone().downcast_arc::<KaspaRpcClient>() {
+                wrpc_client.disconnect().await.ok();
+            }
+        }
+        self.server_info.lock().unwrap().take();
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        self.close_monitor().await;
+        self.kaspad.clone().stop().await
+    }
+
+    pub fn status(&self, is_active: bool) -> NodeStatus {
+        let server_info = self.server_info.lock().unwrap().clone();
+        NodeStatus {
+            network: self.network,
+            node_kind: self.node_kind,
+            is_active,
+            is_background: self.is_background(),
+            is_running: self.kaspad.is_running(),
+            pid: self.kaspad.pid(),
+            uptime: self.started.elapsed(),
+            is_synced: server_info.as_ref().map(|info| info.is_synced),
+            virtual_daa_score: server_info.as_ref().map(|info| info.virtual_daa_score),
+            server_version: server_info.map(|info| info.server_version),
+            process_stats: self.process_stats.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// Snapshot of the state of an integrated node,
+/// displayed by the Node module.
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+    pub network: Network,
+    pub node_kind: KaspadNodeKind,
+    pub is_active: bool,
+    pub is_background: bool,
+    pub is_running: bool,
+    pub pid: Option<u32>,
+    pub uptime: Duration,
+    pub is_synced: Option<bool>,
+    pub virtual_daa_score: Option<u64>,
+    pub server_version: Option<String>,
+    pub process_stats: Option<ProcessStats>,
+}
@@ -0,0 +1,195 @@
+use crate::imports::*;
+use std::net::SocketAddr;
+
+/// Number of ports scanned above a conflicting port
+/// when searching for an available alternative.
+pub const ALTERNATIVE_PORT_SEARCH_RANGE: u16 = 100;
+
+/// Listeners bound by the integrated node.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum NodePort {
+    P2p,
+    Grpc,
+    WrpcBorsh,
+    WrpcJson,
+}
+
+impl std::fmt::Display for NodePort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodePort::P2p => write!(f, "{}", i18n("p2p")),
+            NodePort::Grpc => write!(f, "{}", i18n("gRPC")),
+            NodePort::WrpcBorsh => write!(f, "{}", i18n("wRPC (Borsh)")),
+            NodePort::WrpcJson => write!(f, "{}", i18n("wRPC (JSON)")),
+        }
+    }
+}
+
+/// Socket address the node is configured to listen on.
+#[derive(Debug, Clone)]
+pub struct PortBinding {
+    pub kind: NodePort,
+    pub address: SocketAddr,
+}
+
+impl PortBinding {
+    pub fn new(kind: NodePort, address: SocketAddr) -> Self {
+        Self { kind, address }
+    }
+}
+
+/// Process holding a port, if it can be determined.
+#[derive(Debug, Clone)]
+pub struct PortOwner {
+    pub pid: u32,
+    pub name: String,
+}
+
+impl std::fmt::Display for PortOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (pid {})", self.name, self.pid)
+    }
+}
+
+/// A node listener whose port is already in use.
+#[derive(Debug, Clone)]
+pub struct PortConflict {
+    pub network: Network,
+    pub kind: NodePort,
+    pub address: SocketAddr,
+    pub owner: Option<PortOwner>,
+    pub alternative: Option<u16>,
+}
+
+impl std::fmt::Display for PortConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.kind, i18n("port"), self.address)?;
+        if let Some(owner) = &self.owner {
+            write!(f, " {} {owner}", i18n("is used by"))?;
+        } else {
+            write!(f, " {}", i18n("is in use"))?;
+        }
+        Ok(())
+    }
+}
+
+impl PortConflict {
+    /// Update `node_settings` so that the conflicting listener
+    /// uses the alternative port. Returns `false` if no
+    /// alternative port is available.
+    pub fn apply_alternative(&self, node_settings: &mut NodeSettings) -> bool {
+        let Some(port) = self.alternative else {
+            return false;
+        };
+
+        let interface = NetworkInterfaceConfig {
+            kind: NetworkInterfaceKind::Custom,
+            custom: ContextualNetAddress::new(self.address.ip().into(), Some(port)),
+        };
+
+        match self.kind {
+            NodePort::P2p => node_settings.p2p_network_interface = interface,
+            NodePort::Grpc => node_settings.grpc_network_interface = interface,
+            NodePort::WrpcBorsh => node_settings.wrpc_borsh_network_interface = interface,
+            NodePort::WrpcJson => node_settings.wrpc_json_network_interface = interface,
+        }
+
+        true
+    }
+}
+
+cfg_if! {
+    if #[cfg(not(target_arch = "wasm32"))] {
+        use std::collections::HashSet;
+        use std::net::TcpListener;
+
+        fn is_available(address: &SocketAddr) -> bool {
+            TcpListener::bind(address).is_ok()
+        }
+
+        /// Attempt to bind each of the supplied addresses, returning
+        /// a list of conflicts for addresses that are already in use.
+        pub fn probe(network: Network, bindings: &[PortBinding]) -> Vec<PortConflict> {
+            let mut reserved = bindings.iter().map(|binding| binding.address.port()).collect::<HashSet<_>>();
+
+            bindings
+                .iter()
+                .filter(|binding| !is_available(&binding.address))
+                .map(|binding| {
+                    let alternative = find_alternative(&binding.address, &reserved);
+                    if let Some(port) = alternative {
+                        reserved.insert(port);
+                    }
+
+                    PortConflict {
+                        network,
+                        kind: binding.kind,
+                        address: binding.address,
+                        owner: find_owner(binding.address.port()),
+                        alternative,
+                    }
+                })
+                .collect()
+        }
+
+        fn find_alternative(address: &SocketAddr, reserved: &HashSet<u16>) -> Option<u16> {
+            let first = address.port().checked_add(1)?;
+            let last = address.port().saturating_add(ALTERNATIVE_PORT_SEARCH_RANGE);
+            (first..=last)
+                .filter(|port| !reserved.contains(port))
+                .find(|port| is_available(&SocketAddr::new(address.ip(), *port)))
+        }
+
+        /// Locate the process listening on `port` by matching
+        /// socket inodes from `/proc/net/tcp*` against the
+        /// file descriptors of running processes.
+        #[cfg(target_os = "linux")]
+        fn find_owner(port: u16) -> Option<PortOwner> {
+            const TCP_LISTEN: &str = "0A";
+
+            let inodes = ["/proc/net/tcp", "/proc/net/tcp6"]
+                .iter()
+                .filter_map(|path| std::fs::read_to_string(path).ok())
+                .flat_map(|content| {
+                    content
+                        .lines()
+                        .skip(1)
+                        .filter_map(|line| {
+                            let fields = line.split_whitespace().collect::<Vec<_>>();
+                            let local_port = fields.get(1)?.rsplit(':').next()?;
+                            let local_port = u16::from_str_radix(local_port, 16).ok()?;
+                            (local_port == port && *fields.get(3)? == TCP_LISTEN)
+                                .then(|| fields.get(9).map(|inode| format!("socket:[{inode}]")))
+                                .flatten()
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<HashSet<_>>();
+
+            if inodes.is_empty() {
+                return None;
+            }
+
+            std::fs::read_dir("/proc").ok()?.flatten().find_map(|entry| {
+                let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+                let holds_socket = std::fs::read_dir(entry.path().join("fd"))
+                    .ok()?
+                    .flatten()
+                    .filter_map(|fd| std::fs::read_link(fd.path()).ok())
+                    .any(|link| link.to_str().map(|link| inodes.contains(link)).unwrap_or(false));
+
+                holds_socket.then(|| {
+                    let name = std::fs::read_to_string(entry.path().join("comm"))
+                        .map(|name| name.trim().to_string())
+                        .unwrap_or_else(|_| i18n("unknown").to_string());
+                    PortOwner { pid, name }
+                })
+            })
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        fn find_owner(_port: u16) -> Option<PortOwner> {
+            None
+        }
+    }
+}
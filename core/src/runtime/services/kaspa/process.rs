@@ -0,0 +1,320 @@
+use crate::imports::*;
+use crate::settings::{NodeIoPriority, NodeProcessSettings};
+use tokio::process::Command;
+
+/// Resource usage of a node process.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessStats {
+    /// CPU usage in percent (100% per fully used core)
+    pub cpu_usage: f32,
+    /// Resident memory in bytes
+    pub memory: u64,
+    pub open_files: Option<usize>,
+}
+
+/// Samples resource usage of node processes.
+pub struct ProcessMonitor {
+    system: Mutex<sysinfo::System>,
+}
+
+impl Default for ProcessMonitor {
+    fn default() -> Self {
+        use sysinfo::SystemExt;
+        Self {
+            system: Mutex::new(sysinfo::System::new()),
+        }
+    }
+}
+
+impl ProcessMonitor {
+    /// Refresh and return the resource usage of the process `pid`.
+    /// CPU usage is computed relative to the previous sample.
+    pub fn sample(&self, pid: u32) -> Option<ProcessStats> {
+        use sysinfo::{PidExt, ProcessExt, SystemExt};
+
+        let pid_ = sysinfo::Pid::from_u32(pid);
+        let mut system = self.system.lock().unwrap();
+        if !system.refresh_process(pid_) {
+            return None;
+        }
+
+        system.process(pid_).map(|process| ProcessStats {
+            cpu_usage: process.cpu_usage(),
+            memory: process.memory(),
+            open_files: open_files(pid),
+        })
+    }
+}
+
+/// Configure `cmd` to set the priority, IO priority and CPU affinity
+/// of the node process before it is executed. On Linux these attributes
+/// are per-thread; set prior to `exec` they are inherited by all threads
+/// created by the node. Returns a list of controls that can not be set.
+pub fn configure_process_controls(
+    cmd: &mut Command,
+    settings: &NodeProcessSettings,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    let nice = settings.enable_nice.then_some(settings.nice);
+    if nice.is_some() && cfg!(not(unix)) {
+        issues.push(format!(
+            "{} {}",
+            i18n("Unable to set process priority:"),
+            not_supported()
+        ));
+    }
+
+    let io_priority =
+        (settings.io_priority != NodeIoPriority::Default).then_some(settings.io_priority);
+    if io_priority.is_some() && cfg!(not(target_os = "linux")) {
+        issues.push(format!(
+            "{} {}",
+            i18n("Unable to set IO priority:"),
+            not_supported()
+        ));
+    }
+
+    let cores = if settings.enable_cpu_affinity {
+        match settings.cpu_affinity_cores() {
+            Ok(_) if cfg!(not(target_os = "linux")) => {
+                issues.push(format!(
+                    "{} {}",
+                    i18n("Unable to set CPU affinity:"),
+                    not_supported()
+                ));
+                None
+            }
+            Ok(cores) => Some(cores),
+            Err(err) => {
+                issues.push(format!("{} {err}", i18n("Unable to set CPU affinity:")));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if let Err(err) = set_pre_exec(cmd, nice, io_priority, cores) {
+        issues.push(format!("{} {err}", i18n("Unable to set CPU affinity:")));
+    }
+
+    issues
+}
+
+/// Apply the memory limit to the process `pid` and verify the controls
+/// configured by [`configure_process_controls`]. Returns a list of
+/// controls that could not be applied.
+pub fn apply_process_controls(
+    pid: u32,
+    network: Network,
+    settings: &NodeProcessSettings,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    #[cfg(unix)]
+    if settings.enable_nice {
+        if let Err(err) = verify_nice(pid, settings.nice) {
+            issues.push(format!("{} {err}", i18n("Unable to set process priority:")));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    if settings.io_priority != NodeIoPriority::Default {
+        if let Err(err) = verify_io_priority(pid, settings.io_priority) {
+            issues.push(format!("{} {err}", i18n("Unable to set IO priority:")));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    if settings.enable_cpu_affinity {
+        if let Err(err) = settings
+            .cpu_affinity_cores()
+            .and_then(|cores| verify_cpu_affinity(pid, &cores))
+        {
+            issues.push(format!("{} {err}", i18n("Unable to set CPU affinity:")));
+        }
+    }
+
+    if settings.enable_memory_limit {
+        if let Err(err) = set_memory_limit(pid, network, settings.memory_limit) {
+            issues.push(format!("{} {err}", i18n("Unable to set memory limit:")));
+        }
+    }
+
+    issues
+}
+
+/// Release resources allocated by [`apply_process_controls`]
+/// once the node process has exited.
+pub fn release_process_controls(network: Network) {
+    #[cfg(target_os = "linux")]
+    if let Ok(cgroup) = cgroup::node_cgroup(network) {
+        if cgroup.exists() {
+            std::fs::remove_dir(&cgroup).ok();
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = network;
+}
+
+fn not_supported() -> Error {
+    Error::custom(i18n("not supported on this platform"))
+}
+
+cfg_if! {
+    if #[cfg(unix)] {
+        fn verify_nice(pid: u32, nice: i32) -> Result<()> {
+            let value = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t) };
+            if value != nice {
+                return Err(Error::custom(format!("{} {value}", i18n("the process priority is"))));
+            }
+            Ok(())
+        }
+    }
+}
+
+cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        use nix::sched::{sched_getaffinity, sched_setaffinity, CpuSet};
+        use nix::unistd::Pid;
+
+        const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+        const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+        const IOPRIO_CLASS_BE: libc::c_int = 2;
+        const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+        const IOPRIO_BE_LOWEST: libc::c_int = 7;
+
+        fn ioprio(priority: NodeIoPriority) -> libc::c_int {
+            match priority {
+                NodeIoPriority::Default => 0,
+                NodeIoPriority::Low => (IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT) | IOPRIO_BE_LOWEST,
+                NodeIoPriority::Idle => IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            }
+        }
+
+        fn to_cpu_set(cores: &[usize]) -> Result<CpuSet> {
+            let mut cpu_set = CpuSet::new();
+            for core in cores {
+                cpu_set.set(*core).map_err(|err| Error::custom(format!("core {core}: {err}")))?;
+            }
+            Ok(cpu_set)
+        }
+
+        fn set_pre_exec(cmd: &mut Command, nice: Option<i32>, io_priority: Option<NodeIoPriority>, cores: Option<Vec<usize>>) -> Result<()> {
+            let ioprio = io_priority.map(ioprio);
+            let cpu_set = cores.map(|cores| to_cpu_set(&cores)).transpose()?;
+
+            // runs in the forked child: only async-signal-safe system calls
+            // are performed; failures are detected by the `verify_*` functions
+            unsafe {
+                cmd.pre_exec(move || {
+                    if let Some(nice) = nice {
+                        libc::setpriority(libc::PRIO_PROCESS, 0, nice);
+                    }
+                    if let Some(ioprio) = ioprio {
+                        libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio);
+                    }
+                    if let Some(cpu_set) = cpu_set.as_ref() {
+                        sched_setaffinity(Pid::from_raw(0), cpu_set).ok();
+                    }
+                    Ok(())
+                });
+            }
+
+            Ok(())
+        }
+
+        fn verify_io_priority(pid: u32, priority: NodeIoPriority) -> Result<()> {
+            let result = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid as libc::c_int) };
+            if result < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            if result as libc::c_int != ioprio(priority) {
+                return Err(Error::custom(i18n("the IO priority has not been applied")));
+            }
+            Ok(())
+        }
+
+        fn verify_cpu_affinity(pid: u32, cores: &[usize]) -> Result<()> {
+            let cpu_set = sched_getaffinity(Pid::from_raw(pid as i32)).map_err(|err| Error::custom(err.to_string()))?;
+            if cpu_set != to_cpu_set(cores)? {
+                return Err(Error::custom(i18n("the CPU affinity has not been applied")));
+            }
+            Ok(())
+        }
+
+        fn set_memory_limit(pid: u32, network: Network, megabytes: u64) -> Result<()> {
+            cgroup::set_memory_limit(pid, network, megabytes)
+        }
+
+        fn open_files(pid: u32) -> Option<usize> {
+            std::fs::read_dir(format!("/proc/{pid}/fd")).ok().map(|entries| entries.count())
+        }
+
+        mod cgroup {
+            use super::*;
+
+            const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+            /// Node cgroup, created as a sibling of the cgroup of this
+            /// process (a cgroup containing processes can not have
+            /// controllers enabled for its children).
+            pub fn node_cgroup(network: Network) -> Result<PathBuf> {
+                let cgroup = std::fs::read_to_string("/proc/self/cgroup")?;
+                let path = cgroup
+                    .lines()
+                    .find_map(|line| line.strip_prefix("0::"))
+                    .ok_or_else(|| Error::custom(i18n("cgroup v2 is not available")))?;
+                let own = Path::new(CGROUP_ROOT).join(path.trim().trim_start_matches('/'));
+                let parent = own
+                    .parent()
+                    .filter(|parent| parent.starts_with(CGROUP_ROOT))
+                    .ok_or_else(|| Error::custom(i18n("cgroup v2 is not available")))?;
+                Ok(parent.join(format!("kaspa-ng-node-{network}")))
+            }
+
+            pub fn set_memory_limit(pid: u32, network: Network, megabytes: u64) -> Result<()> {
+                let cgroup = node_cgroup(network)?;
+                let parent = cgroup.parent().ok_or_else(|| Error::custom(i18n("cgroup v2 is not available")))?;
+                let controllers = std::fs::read_to_string(parent.join("cgroup.subtree_control")).unwrap_or_default();
+                if !controllers.split_whitespace().any(|controller| controller == "memory") {
+                    return Err(Error::custom(i18n("the cgroup memory controller is not available")));
+                }
+
+                std::fs::create_dir_all(&cgroup)?;
+                std::fs::write(cgroup.join("memory.max"), (megabytes * 1024 * 1024).to_string())?;
+                std::fs::write(cgroup.join("cgroup.procs"), pid.to_string())?;
+                Ok(())
+            }
+        }
+    } else {
+        #[cfg(unix)]
+        fn set_pre_exec(cmd: &mut Command, nice: Option<i32>, _io_priority: Option<NodeIoPriority>, _cores: Option<Vec<usize>>) -> Result<()> {
+            // runs in the forked child; failures are detected by `verify_nice`
+            if let Some(nice) = nice {
+                unsafe {
+                    cmd.pre_exec(move || {
+                        libc::setpriority(libc::PRIO_PROCESS, 0, nice);
+                        Ok(())
+                    });
+                }
+            }
+            Ok(())
+        }
+
+        #[cfg(not(unix))]
+        fn set_pre_exec(_cmd: &mut Command, _nice: Option<i32>, _io_priority: Option<NodeIoPriority>, _cores: Option<Vec<usize>>) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_memory_limit(_pid: u32, _network: Network, _megabytes: u64) -> Result<()> {
+            Err(not_supported())
+        }
+
+        fn open_files(_pid: u32) -> Option<usize> {
+            None
+        }
+    }
+}
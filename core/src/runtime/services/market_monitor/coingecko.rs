@@ -15,13 +15,30 @@ use std::collections::hash_map::Entry;
 //     }
 //   }
 
-// https://api.coingecko.com/api/v3/coins/list
+// https://api.coingecko.com/api/v3/simple/supported_vs_currencies
 // [
-//   {
-//     "id": "01coin",
-//     "symbol": "zoc",
-//     "name": "01coin"
-//   },
+//   "btc",
+//   "eth",
+//   "usd",
+//   "eur",
+
+// https://api.coingecko.com/api/v3/coins/kaspa/ohlc?vs_currency=usd&days=1
+// [
+//   [
+//     1709395200000,
+//     0.137395,
+//     0.139082,
+//     0.136914,
+//     0.138271
+//   ],
+
+// https://api.coingecko.com/api/v3/coins/kaspa/market_chart?vs_currency=usd&days=365&interval=daily
+// {
+//   "prices": [
+//     [
+//       1709337600000,
+//       0.137395
+//     ],
 
 #[derive(Default, Debug, Serialize, Deserialize)]
 struct CoinGeckoSimplePrice {
@@ -51,19 +68,59 @@ impl From<CoinGeckoSimplePrice> for MarketDataMap {
     }
 }
 
-pub async fn fetch_available_currencies() -> Result<CurrencyDescriptorList> {
-    let url = "https://api.coingecko.com/api/v3/coins/list";
-    let available_currencies = http::get_json::<CurrencyDescriptorList>(url).await?;
+async fn fetch_available_currencies() -> Result<CurrencyDescriptorList> {
+    let url = "https://api.coingecko.com/api/v3/simple/supported_vs_currencies";
+    let symbols = http::get_json::<Vec<String>>(url).await?;
+    let available_currencies = symbols
+        .into_iter()
+        .map(|symbol| CurrencyDescriptor {
+            id: symbol.to_lowercase(),
+            name: symbol.to_uppercase(),
+            symbol,
+        })
+        .collect();
     Ok(available_currencies)
 }
 
-pub async fn fetch_market_price_list(currencies: &[&str]) -> Result<MarketDataMap> {
+async fn fetch_market_price_list(currencies: &[&str]) -> Result<MarketDataMap> {
     let market_data = CoinGeckoSimplePrice::get(currencies).await?;
     // println!("market_data: {:?}", market_data);
 
     Ok(market_data.into())
 }
 
+async fn fetch_ohlc(currency: &str, range: OhlcRange) -> Result<Vec<Candle>> {
+    let currency = currency.to_lowercase();
+    let days = range.days();
+    let url = format!(
+        "https://api.coingecko.com/api/v3/coins/kaspa/ohlc?vs_currency={currency}&days={days}"
+    );
+    let data = http::get_json::<Vec<[f64; 5]>>(url).await?;
+    let candles = data
+        .into_iter()
+        .map(|[timestamp, open, high, low, close]| Candle {
+            timestamp,
+            open,
+            high,
+            low,
+            close,
+        })
+        .collect();
+    Ok(candles)
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+struct CoinGeckoMarketChart {
+    prices: Vec<(f64, f64)>,
+}
+
+async fn fetch_price_history(currency: &str, days: u32) -> Result<Vec<(f64, f64)>> {
+    let currency = currency.to_lowercase();
+    let url = format!("https://api.coingecko.com/api/v3/coins/kaspa/market_chart?vs_currency={currency}&days={days}&interval=daily");
+    let chart = http::get_json::<CoinGeckoMarketChart>(url).await?;
+    Ok(chart.prices)
+}
+
 fn group_by_currency_prefix(data: &AHashMap<String, f64>) -> MarketDataMap {
     let mut grouped_data: MarketDataMap = AHashMap::new();
 
@@ -90,3 +147,40 @@ fn group_by_currency_prefix(data: &AHashMap<String, f64>) -> MarketDataMap {
 
     grouped_data
 }
+
+pub struct CoinGecko;
+
+#[async_trait]
+impl MarketDataProvider for CoinGecko {
+    fn id(&self) -> String {
+        "coingecko".to_string()
+    }
+
+    fn name(&self) -> String {
+        "CoinGecko".to_string()
+    }
+
+    fn supports_ohlc(&self) -> bool {
+        true
+    }
+
+    fn supports_price_history(&self) -> bool {
+        true
+    }
+
+    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
+        fetch_available_currencies().await
+    }
+
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
+        fetch_market_price_list(currencies).await
+    }
+
+    async fn fetch_ohlc(&self, currency: &str, range: OhlcRange) -> Result<Vec<Candle>> {
+        fetch_ohlc(currency, range).await
+    }
+
+    async fn fetch_price_history(&self, currency: &str, days: u32) -> Result<Vec<(f64, f64)>> {
+        fetch_price_history(currency, days).await
+    }
+}
@@ -30,13 +30,21 @@ impl From<CoinGeckoSimplePrice> for MarketDataMap {
     }
 }
 
-pub async fn fetch_available_currencies() -> Result<CurrencyDescriptorList> {
-    let url = "https://api.coingecko.com/api/v3/coins/list";
-    let available_currencies = http::get_json::<CurrencyDescriptorList>(url).await?;
+async fn fetch_available_currencies() -> Result<CurrencyDescriptorList> {
+    let url = "https://api.coingecko.com/api/v3/simple/supported_vs_currencies";
+    let symbols = http::get_json::<Vec<String>>(url).await?;
+    let available_currencies = symbols
+        .into_iter()
+        .map(|symbol| CurrencyDescriptor {
+            id: symbol.to_lowercase(),
+            name: symbol.to_uppercase(),
+            symbol,
+        })
+        .collect();
     Ok(available_currencies)
 }
 
-pub async fn fetch_market_price_list(currencies: &[&str]) -> Result<MarketDataMap> {
+async fn fetch_market_price_list(currencies: &[&str]) -> Result<MarketDataMap> {
     let market_data = CoinGeckoSimplePrice::get(currencies).await?;
     Ok(market_data.into())
 }
@@ -64,3 +72,24 @@ fn group_by_currency_prefix(data: &AHashMap<String, f64>) -> MarketDataMap {
 
     grouped_data
 }
+
+pub struct CoinMarketCap;
+
+#[async_trait]
+impl MarketDataProvider for CoinMarketCap {
+    fn id(&self) -> String {
+        "coinmarketcap".to_string()
+    }
+
+    fn name(&self) -> String {
+        "CoinMarketCap".to_string()
+    }
+
+    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
+        fetch_available_currencies().await
+    }
+
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
+        fetch_market_price_list(currencies).await
+    }
+}
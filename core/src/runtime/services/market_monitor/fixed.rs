@@ -0,0 +1,58 @@
+use super::*;
+
+/// Market data provider returning user-defined prices,
+/// for offline use and testing.
+pub struct FixedPrice {
+    config: FixedPriceConfig,
+}
+
+impl FixedPrice {
+    pub fn new(config: FixedPriceConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for FixedPrice {
+    fn id(&self) -> String {
+        "fixed-price".to_string()
+    }
+
+    fn name(&self) -> String {
+        i18n("Fixed Price").to_string()
+    }
+
+    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
+        let available_currencies = self
+            .config
+            .prices
+            .keys()
+            .map(|currency| CurrencyDescriptor {
+                id: currency.clone(),
+                symbol: currency.clone(),
+                name: currency.to_uppercase(),
+            })
+            .collect();
+        Ok(available_currencies)
+    }
+
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
+        let market_data_map = currencies
+            .iter()
+            .filter_map(|currency| {
+                let currency = currency.to_lowercase();
+                self.config.prices.get(&currency).map(|price| {
+                    let mut market_data = MarketData::new(&currency);
+                    market_data.price = *price;
+                    (currency, market_data)
+                })
+            })
+            .collect::<MarketDataMap>();
+
+        if market_data_map.is_empty() {
+            Err(Error::custom("no fixed price for the selected currencies"))
+        } else {
+            Ok(market_data_map)
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use super::*;
+use serde_json::Value;
+
+/// Market data provider querying a generic JSON endpoint.
+pub struct JsonEndpoint {
+    config: JsonEndpointConfig,
+}
+
+impl JsonEndpoint {
+    pub fn new(config: JsonEndpointConfig) -> Self {
+        Self { config }
+    }
+
+    async fn fetch_market_data(&self, currency: &str) -> Result<MarketData> {
+        let url = substitute(&self.config.url, currency);
+        let json = http::get_json::<Value>(url).await?;
+
+        let price = number(&json, &self.config.price, currency).ok_or_else(|| {
+            Error::custom(format!(
+                "no `{currency}` price at `{}` in the response",
+                self.config.price
+            ))
+        })?;
+
+        let mut market_data = MarketData::new(currency);
+        market_data.price = price;
+        market_data.market_cap =
+            number(&json, &self.config.market_cap, currency).unwrap_or_default();
+        market_data.volume = number(&json, &self.config.volume, currency).unwrap_or_default();
+        market_data.change = number(&json, &self.config.change, currency).unwrap_or_default();
+        Ok(market_data)
+    }
+}
+
+fn substitute(template: &str, currency: &str) -> String {
+    template
+        .replace("{currency}", &currency.to_lowercase())
+        .replace("{CURRENCY}", &currency.to_uppercase())
+}
+
+/// Resolve a dot-separated path of object keys and array indices.
+fn resolve<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|key| !key.is_empty())
+        .try_fold(value, |value, key| match value {
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+            _ => value.get(key),
+        })
+}
+
+/// Numeric value at `path`, accepting numbers encoded as strings.
+fn number(value: &Value, path: &str, currency: &str) -> Option<f64> {
+    if path.is_empty() {
+        return None;
+    }
+
+    resolve(value, &substitute(path, currency)).and_then(|value| {
+        value
+            .as_f64()
+            .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+    })
+}
+
+#[async_trait]
+impl MarketDataProvider for JsonEndpoint {
+    fn id(&self) -> String {
+        format!("json:{}", self.config.url)
+    }
+
+    fn name(&self) -> String {
+        self.config.name.clone()
+    }
+
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
+        let mut market_data_map = MarketDataMap::new();
+
+        for currency in currencies {
+            let currency = currency.to_lowercase();
+            match self.fetch_market_data(&currency).await {
+                Ok(market_data) => {
+                    market_data_map.insert(currency, market_data);
+                }
+                Err(err) => {
+                    log_warn!("Market data provider `{}`: {err}", self.config.name);
+                }
+            }
+        }
+
+        if market_data_map.is_empty() {
+            Err(Error::custom(
+                "no price available for the selected currencies",
+            ))
+        } else {
+            Ok(market_data_map)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let json = serde_json::json!({
+            "data": {
+                "usd": { "price": 0.12, "volume": "1000.5" },
+                "quotes": [{ "close": 1.5 }, { "close": 2.5 }]
+            }
+        });
+
+        assert_eq!(resolve(&json, "data.usd.price"), Some(&Value::from(0.12)));
+        assert_eq!(
+            resolve(&json, "data.quotes.1.close"),
+            Some(&Value::from(2.5))
+        );
+        assert_eq!(resolve(&json, ""), Some(&json));
+        assert_eq!(resolve(&json, "data.missing"), None);
+        assert_eq!(resolve(&json, "data.quotes.2.close"), None);
+        assert_eq!(resolve(&json, "data.quotes.first"), None);
+    }
+
+    #[test]
+    fn test_number() {
+        let json = serde_json::json!({
+            "usd": { "price": 0.12, "volume": "1000.5", "name": "kaspa" }
+        });
+
+        assert_eq!(number(&json, "{currency}.price", "USD"), Some(0.12));
+        assert_eq!(number(&json, "{currency}.volume", "usd"), Some(1000.5));
+        assert_eq!(number(&json, "{currency}.name", "usd"), None);
+        assert_eq!(number(&json, "{CURRENCY}.price", "usd"), None);
+        assert_eq!(number(&json, "", "usd"), None);
+    }
+}
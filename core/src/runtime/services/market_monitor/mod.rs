@@ -1,41 +1,94 @@
 use crate::imports::*;
 use crate::market::*;
+use kaspa_wallet_core::storage::local::storage::Storage;
 
 mod coingecko;
+use coingecko::CoinGecko;
 mod coinmarketcap;
+use coinmarketcap::CoinMarketCap;
+mod fixed;
+use fixed::FixedPrice;
+mod json;
+use json::JsonEndpoint;
 
 pub const POLLING_INTERVAL_SECONDS: u64 = 60;
+/// Number of days of daily prices fetched into the price history
+pub const PRICE_HISTORY_DAYS: u32 = 365;
+/// Minimum time between price history updates from the providers
+pub const PRICE_HISTORY_UPDATE_INTERVAL_SECONDS: u64 = 60 * 60;
+/// Time before retrying a price history update that has failed
+pub const PRICE_HISTORY_RETRY_INTERVAL_SECONDS: u64 = 5 * 60;
+/// Maximum time allowed for a provider to respond to a request
+pub const PROVIDER_TIMEOUT_SECONDS: u64 = 20;
 
-#[derive(Default, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum MarketDataProvider {
-    #[default]
-    CoinGecko,
-    CoinMarketCap,
-}
+/// Source of market data. Providers are queried in the failover
+/// order configured in [`MarketMonitorSettings`], the first provider
+/// that responds successfully supplies the data.
+#[async_trait]
+pub trait MarketDataProvider: Send + Sync {
+    /// Unique provider identifier, matching [`MarketDataProviderConfig::id`]
+    fn id(&self) -> String;
+
+    fn name(&self) -> String;
+
+    /// Whether [`fetch_ohlc`](Self::fetch_ohlc) is implemented
+    fn supports_ohlc(&self) -> bool {
+        false
+    }
+
+    /// Whether [`fetch_price_history`](Self::fetch_price_history) is implemented
+    fn supports_price_history(&self) -> bool {
+        false
+    }
 
-impl MarketDataProvider {
-    #[allow(dead_code)]
     async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
-        match self {
-            Self::CoinGecko => coingecko::fetch_available_currencies().await,
-            Self::CoinMarketCap => coinmarketcap::fetch_available_currencies().await,
-        }
+        Err(Error::custom(format!(
+            "{} does not provide a list of currencies",
+            self.name()
+        )))
     }
 
-    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
-        match self {
-            Self::CoinGecko => coingecko::fetch_market_price_list(currencies).await,
-            Self::CoinMarketCap => coinmarketcap::fetch_market_price_list(currencies).await,
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap>;
+
+    async fn fetch_ohlc(&self, _currency: &str, _range: OhlcRange) -> Result<Vec<Candle>> {
+        Err(Error::custom(format!(
+            "OHLC data is not available from {}",
+            self.name()
+        )))
+    }
+
+    /// Daily prices of the past `days` as (unix time in milliseconds, price).
+    async fn fetch_price_history(&self, _currency: &str, _days: u32) -> Result<Vec<(f64, f64)>> {
+        Err(Error::custom(format!(
+            "price history is not available from {}",
+            self.name()
+        )))
+    }
+}
+
+pub fn create_provider(config: &MarketDataProviderConfig) -> Arc<dyn MarketDataProvider> {
+    match config {
+        MarketDataProviderConfig::CoinGecko => Arc::new(CoinGecko),
+        MarketDataProviderConfig::CoinMarketCap => Arc::new(CoinMarketCap),
+        MarketDataProviderConfig::JsonEndpoint(config) => {
+            Arc::new(JsonEndpoint::new(config.clone()))
         }
+        MarketDataProviderConfig::FixedPrice(config) => Arc::new(FixedPrice::new(config.clone())),
     }
 }
 
-#[derive(Default, Serialize, Deserialize)]
-struct MarketMonitorSettings {
-    enabled: bool,
-    provider: MarketDataProvider,
-    currencies: Vec<String>,
+/// Await a provider request, failing if it does not complete
+/// within [`PROVIDER_TIMEOUT_SECONDS`].
+async fn with_timeout<T>(
+    provider: &Arc<dyn MarketDataProvider>,
+    request: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    select! {
+        result = request.fuse() => result,
+        _ = task::sleep(Duration::from_secs(PROVIDER_TIMEOUT_SECONDS)).fuse() => {
+            Err(Error::custom(format!("{} did not respond in time", provider.name())))
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -47,9 +100,17 @@ pub struct CurrencyDescriptor {
 
 pub type CurrencyDescriptorList = Vec<CurrencyDescriptor>;
 
+/// OHLC candles cached per provider id, currency and range,
+/// along with the time they were fetched.
+type OhlcCache = AHashMap<(String, String, OhlcRange), (Instant, Vec<Candle>)>;
+
 pub enum MarketMonitorEvents {
     Enable,
     Disable,
+    OhlcRange(OhlcRange),
+    Currencies(Vec<String>),
+    Providers(Vec<MarketDataProviderConfig>),
+    ImportPriceHistory { csv: String, currency: String },
     Exit,
 }
 
@@ -59,27 +120,47 @@ pub struct MarketMonitorService {
     pub task_ctl: Channel<()>,
     pub is_enabled: AtomicBool,
     pub currencies: Mutex<Option<Vec<String>>>,
-    pub provider: Mutex<MarketDataProvider>,
+    pub providers: Mutex<Vec<Arc<dyn MarketDataProvider>>>,
+    /// Id of the provider that supplied the latest market prices
+    pub active_provider: Mutex<Option<String>>,
     pub available_currencies: Mutex<Option<Vec<CurrencyDescriptor>>>,
     pub market_price_list: Mutex<Option<Arc<MarketDataMap>>>,
+    pub ohlc_range: Mutex<OhlcRange>,
+    ohlc_cache: Mutex<OhlcCache>,
+    price_history: Mutex<PriceHistory>,
+    /// Time of the last price history update and the delay before the next one
+    price_history_update: Mutex<Option<(Instant, Duration)>>,
+    /// Set once the absence of OHLC data from all providers has been logged
+    ohlc_unavailable: AtomicBool,
+    /// Set once the absence of price history from all providers has been logged
+    price_history_unavailable: AtomicBool,
 }
 
 impl MarketMonitorService {
     pub fn new(application_events: ApplicationEventsChannel, settings: &Settings) -> Self {
-        let currencies = ["usd", "btc"]
-            .into_iter()
-            .map(String::from)
-            .collect::<Vec<_>>();
-
         Self {
             application_events,
             service_events: Channel::unbounded(),
             task_ctl: Channel::oneshot(),
             is_enabled: AtomicBool::new(settings.market_monitor),
-            provider: Mutex::new(MarketDataProvider::default()),
-            currencies: Mutex::new(Some(currencies)),
+            providers: Mutex::new(
+                settings
+                    .market
+                    .providers
+                    .iter()
+                    .map(create_provider)
+                    .collect(),
+            ),
+            active_provider: Mutex::new(None),
+            currencies: Mutex::new(Some(settings.market.currencies.clone())),
             available_currencies: Mutex::new(None),
             market_price_list: Mutex::new(None),
+            ohlc_range: Mutex::new(OhlcRange::default()),
+            ohlc_cache: Mutex::new(AHashMap::new()),
+            price_history: Mutex::new(PriceHistory::default()),
+            price_history_update: Mutex::new(None),
+            ohlc_unavailable: AtomicBool::new(false),
+            price_history_unavailable: AtomicBool::new(false),
         }
     }
 
@@ -87,8 +168,50 @@ impl MarketMonitorService {
         self.currencies.lock().unwrap().clone()
     }
 
-    pub fn provider(&self) -> MarketDataProvider {
-        self.provider.lock().unwrap().clone()
+    pub fn providers(&self) -> Vec<Arc<dyn MarketDataProvider>> {
+        self.providers.lock().unwrap().clone()
+    }
+
+    /// Id of the provider that supplied the latest market prices
+    pub fn active_provider(&self) -> Option<String> {
+        self.active_provider.lock().unwrap().clone()
+    }
+
+    pub fn set_providers(&self, providers: Vec<MarketDataProviderConfig>) {
+        self.service_events
+            .try_send(MarketMonitorEvents::Providers(providers))
+            .unwrap();
+    }
+
+    /// Currencies supported by the market data provider,
+    /// available once fetched by the enabled service.
+    pub fn available_currencies(&self) -> Option<Vec<CurrencyDescriptor>> {
+        self.available_currencies.lock().unwrap().clone()
+    }
+
+    pub fn set_currencies(&self, currencies: Vec<String>) {
+        self.service_events
+            .try_send(MarketMonitorEvents::Currencies(currencies))
+            .unwrap();
+    }
+
+    /// Merge daily prices from CSV text (see [`PriceHistory::from_csv`])
+    /// into the price history. `currency` applies to records without
+    /// a currency column.
+    pub fn import_price_history(&self, csv: String, currency: String) {
+        self.service_events
+            .try_send(MarketMonitorEvents::ImportPriceHistory { csv, currency })
+            .unwrap();
+    }
+
+    pub fn ohlc_range(&self) -> OhlcRange {
+        *self.ohlc_range.lock().unwrap()
+    }
+
+    pub fn set_ohlc_range(&self, range: OhlcRange) {
+        self.service_events
+            .try_send(MarketMonitorEvents::OhlcRange(range))
+            .unwrap();
     }
 
     pub fn enable(&self, enable: bool) {
@@ -103,32 +226,240 @@ impl MarketMonitorService {
         }
     }
 
-    #[allow(dead_code)]
     async fn update_available_currencies(&self) -> Result<()> {
-        let available_currencies = self.provider().fetch_available_currencies().await?;
-        self.available_currencies
-            .lock()
-            .unwrap()
-            .replace(available_currencies);
+        for provider in self.providers() {
+            if let Ok(available_currencies) =
+                with_timeout(&provider, provider.fetch_available_currencies()).await
+            {
+                self.available_currencies
+                    .lock()
+                    .unwrap()
+                    .replace(available_currencies);
+                break;
+            }
+        }
         Ok(())
     }
 
     async fn update_market_price_list(&self) -> Result<()> {
         if let Some(currencies) = self.currencies() {
             let currencies = currencies.iter().map(String::as_str).collect::<Vec<_>>();
-            if let Ok(market_price_list) =
-                self.provider().fetch_market_price_list(&currencies).await
-            {
-                self.application_events
-                    .sender
-                    .try_send(Events::Market(MarketUpdate::Price(Arc::new(
-                        market_price_list,
-                    ))))
-                    .unwrap();
+            for provider in self.providers() {
+                match with_timeout(&provider, provider.fetch_market_price_list(&currencies)).await {
+                    Ok(market_price_list) => {
+                        self.active_provider.lock().unwrap().replace(provider.id());
+                        self.application_events
+                            .sender
+                            .try_send(Events::Market(MarketUpdate::Price(Arc::new(
+                                market_price_list,
+                            ))))
+                            .unwrap();
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        log_warn!("Market data provider `{}`: {err}", provider.name());
+                    }
+                }
             }
+            self.active_provider.lock().unwrap().take();
         }
         Ok(())
     }
+
+    fn price_history_storage() -> Result<Storage> {
+        Ok(Storage::try_new("kaspa-ng.price-history")?)
+    }
+
+    async fn load_price_history(&self) -> Result<()> {
+        let storage = Self::price_history_storage()?;
+        if storage.exists().await.unwrap_or(false) {
+            match workflow_store::fs::read_json::<PriceHistory>(storage.filename()).await {
+                Ok(history) => {
+                    *self.price_history.lock().unwrap() = history;
+                    self.post_price_history();
+                }
+                Err(err) => {
+                    log_warn!("Unable to load price history: {err}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn store_price_history(&self) -> Result<()> {
+        let history = self.price_history.lock().unwrap().clone();
+        let storage = Self::price_history_storage()?;
+        storage.ensure_dir().await?;
+        workflow_store::fs::write_json(storage.filename(), &history).await?;
+        Ok(())
+    }
+
+    fn post_price_history(&self) {
+        let history = self.price_history.lock().unwrap().clone();
+        self.application_events
+            .sender
+            .try_send(Events::Market(MarketUpdate::History(Arc::new(history))))
+            .unwrap();
+    }
+
+    /// Fetch daily prices of currencies whose price history is
+    /// older than a day, at most once per update interval.
+    /// Failed updates are retried after a shorter interval.
+    async fn update_price_history(&self) -> Result<()> {
+        let Some(currencies) = self.currencies() else {
+            return Ok(());
+        };
+
+        if self
+            .price_history_update
+            .lock()
+            .unwrap()
+            .is_some_and(|(instant, delay)| instant.elapsed() < delay)
+        {
+            return Ok(());
+        }
+
+        let providers = self
+            .providers()
+            .into_iter()
+            .filter(|provider| provider.supports_price_history())
+            .collect::<Vec<_>>();
+        if providers.is_empty() {
+            if !self.price_history_unavailable.swap(true, Ordering::Relaxed) {
+                log_info!(
+                    "Price history is not available from the configured market data providers"
+                );
+            }
+            self.price_history_update.lock().unwrap().replace((
+                Instant::now(),
+                Duration::from_secs(PRICE_HISTORY_UPDATE_INTERVAL_SECONDS),
+            ));
+            return Ok(());
+        }
+
+        let yesterday = unixtime_as_millis_f64() - 24.0 * 60.0 * 60.0 * 1000.0;
+        let mut updated = false;
+        let mut failed = false;
+        for currency in currencies {
+            let last_timestamp = self.price_history.lock().unwrap().last_timestamp(&currency);
+            if last_timestamp.is_some_and(|timestamp| timestamp >= yesterday) {
+                continue;
+            }
+
+            let mut fetched = false;
+            for provider in providers.iter() {
+                match with_timeout(
+                    provider,
+                    provider.fetch_price_history(&currency, PRICE_HISTORY_DAYS),
+                )
+                .await
+                {
+                    Ok(prices) => {
+                        let mut history = self.price_history.lock().unwrap();
+                        for (timestamp, price) in prices {
+                            history.insert(&currency, timestamp, price);
+                        }
+                        fetched = true;
+                        break;
+                    }
+                    Err(err) => {
+                        log_warn!("Unable to fetch price history for `{currency}`: {err}");
+                    }
+                }
+            }
+            updated |= fetched;
+            failed |= !fetched;
+        }
+
+        let delay = if failed {
+            PRICE_HISTORY_RETRY_INTERVAL_SECONDS
+        } else {
+            PRICE_HISTORY_UPDATE_INTERVAL_SECONDS
+        };
+        self.price_history_update
+            .lock()
+            .unwrap()
+            .replace((Instant::now(), Duration::from_secs(delay)));
+
+        if updated {
+            self.post_price_history();
+            self.store_price_history().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn import_csv_price_history(&self, csv: &str, currency: &str) -> Result<()> {
+        let imported = PriceHistory::from_csv(csv, currency)?;
+        self.price_history.lock().unwrap().merge(imported);
+        self.post_price_history();
+        self.store_price_history().await
+    }
+
+    /// Post OHLC candles of the selected range for all currencies,
+    /// fetching those that are missing from the cache or have expired.
+    async fn update_ohlc(&self) -> Result<()> {
+        let Some(currencies) = self.currencies() else {
+            return Ok(());
+        };
+
+        let providers = self
+            .providers()
+            .into_iter()
+            .filter(|provider| provider.supports_ohlc())
+            .collect::<Vec<_>>();
+        if providers.is_empty() {
+            if !self.ohlc_unavailable.swap(true, Ordering::Relaxed) {
+                log_info!("OHLC data is not available from the configured market data providers");
+            }
+            return Ok(());
+        }
+
+        let range = self.ohlc_range();
+        let mut ohlc_map = OhlcMap::new();
+
+        for currency in currencies {
+            for provider in providers.iter() {
+                let key = (provider.id(), currency.clone(), range);
+                let cached = self
+                    .ohlc_cache
+                    .lock()
+                    .unwrap()
+                    .get(&key)
+                    .filter(|(timestamp, _)| timestamp.elapsed() < range.cache_duration())
+                    .map(|(_, candles)| candles.clone());
+
+                let candles = match cached {
+                    Some(candles) => candles,
+                    None => {
+                        match with_timeout(provider, provider.fetch_ohlc(&currency, range)).await {
+                            Ok(candles) => {
+                                self.ohlc_cache
+                                    .lock()
+                                    .unwrap()
+                                    .insert(key, (Instant::now(), candles.clone()));
+                                candles
+                            }
+                            Err(err) => {
+                                log_warn!("Unable to fetch OHLC data for `{currency}`: {err}");
+                                continue;
+                            }
+                        }
+                    }
+                };
+
+                ohlc_map.insert(currency, Ohlc::new(range, candles));
+                break;
+            }
+        }
+
+        self.application_events
+            .sender
+            .try_send(Events::Market(MarketUpdate::Ohlc(Arc::new(ohlc_map))))
+            .unwrap();
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -140,6 +471,9 @@ impl Service for MarketMonitorService {
     async fn spawn(self: Arc<Self>) -> Result<()> {
         let this = self.clone();
         let _application_events_sender = self.application_events.sender.clone();
+        if let Err(err) = this.load_price_history().await {
+            log_warn!("Unable to load price history: {err}");
+        }
         let interval = task::interval(Duration::from_secs(POLLING_INTERVAL_SECONDS));
         pin_mut!(interval);
 
@@ -147,6 +481,13 @@ impl Service for MarketMonitorService {
             select! {
                 _ = interval.next().fuse() => {
                     this.update_market_price_list().await?;
+                    if this.is_enabled.load(Ordering::SeqCst) {
+                        this.update_ohlc().await?;
+                        if this.available_currencies.lock().unwrap().is_none() {
+                            this.update_available_currencies().await.ok();
+                        }
+                        this.update_price_history().await.ok();
+                    }
                 },
 
                 msg = this.as_ref().service_events.receiver.recv().fuse() => {
@@ -156,11 +497,59 @@ impl Service for MarketMonitorService {
                                 if !this.is_enabled.load(Ordering::SeqCst) {
                                     this.is_enabled.store(true, Ordering::SeqCst);
                                     this.update_market_price_list().await?;
+                                    this.update_ohlc().await?;
+                                    if this.available_currencies.lock().unwrap().is_none() {
+                                        this.update_available_currencies().await.ok();
+                                    }
+                                    this.update_price_history().await.ok();
                                 }
                             }
                             MarketMonitorEvents::Disable => {
                                 this.is_enabled.store(false, Ordering::SeqCst);
                             }
+                            MarketMonitorEvents::Currencies(currencies) => {
+                                this.currencies.lock().unwrap().replace(currencies);
+                                this.price_history_update.lock().unwrap().take();
+                                if this.is_enabled.load(Ordering::SeqCst) {
+                                    this.update_market_price_list().await?;
+                                    this.update_ohlc().await?;
+                                    this.update_price_history().await.ok();
+                                }
+                            }
+                            MarketMonitorEvents::ImportPriceHistory { csv, currency } => {
+                                match this.import_csv_price_history(&csv, &currency).await {
+                                    Ok(()) => {
+                                        runtime().notify(UserNotification::success(i18n("Price history imported")));
+                                    }
+                                    Err(err) => {
+                                        runtime().notify(UserNotification::error(format!(
+                                            "{}: {err}",
+                                            i18n("Unable to import price history")
+                                        )));
+                                    }
+                                }
+                            }
+                            MarketMonitorEvents::Providers(providers) => {
+                                *this.providers.lock().unwrap() =
+                                    providers.iter().map(create_provider).collect();
+                                this.active_provider.lock().unwrap().take();
+                                this.available_currencies.lock().unwrap().take();
+                                this.ohlc_cache.lock().unwrap().clear();
+                                this.price_history_update.lock().unwrap().take();
+                                this.ohlc_unavailable.store(false, Ordering::Relaxed);
+                                this.price_history_unavailable.store(false, Ordering::Relaxed);
+                                if this.is_enabled.load(Ordering::SeqCst) {
+                                    this.update_market_price_list().await?;
+                                    this.update_ohlc().await?;
+                                    this.update_available_currencies().await.ok();
+                                }
+                            }
+                            MarketMonitorEvents::OhlcRange(range) => {
+                                *this.ohlc_range.lock().unwrap() = range;
+                                if this.is_enabled.load(Ordering::SeqCst) {
+                                    this.update_ohlc().await?;
+                                }
+                            }
                             MarketMonitorEvents::Exit => {
                                 break;
                             }
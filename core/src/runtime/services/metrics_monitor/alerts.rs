@@ -0,0 +1,214 @@
+use crate::imports::*;
+use kaspa_metrics_core::MetricsSnapshot;
+
+/// Maximum number of entries retained in the alert log
+const MAX_ALERT_LOG_ENTRIES: usize = 256;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AlertState {
+    Raised,
+    Resolved,
+}
+
+/// Entry of the alert log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Alert {
+    /// Unix time in milliseconds
+    pub timestamp: f64,
+    pub rule: AlertRule,
+    pub value: f64,
+    pub state: AlertState,
+}
+
+impl Alert {
+    pub fn message(&self) -> String {
+        let value = self.rule.metric.format(self.value, true, false);
+        match self.state {
+            AlertState::Raised => format!("{}: {} - {value}", i18n("Alert"), self.rule),
+            AlertState::Resolved => format!("{}: {} - {value}", i18n("Resolved"), self.rule),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct RuleState {
+    /// Time since which the rule condition has been holding
+    since: Option<f64>,
+    /// Whether the alert has been raised and not yet resolved
+    active: bool,
+}
+
+/// Evaluates alert rules against metric snapshots and keeps
+/// a log of raised and resolved alerts.
+#[derive(Debug, Default)]
+pub struct AlertMonitor {
+    rules: Vec<(AlertRule, RuleState)>,
+    log: VecDeque<Alert>,
+}
+
+impl AlertMonitor {
+    pub fn new(rules: &[AlertRule]) -> Self {
+        let mut monitor = Self::default();
+        monitor.set_rules(rules);
+        monitor
+    }
+
+    /// Replace the rules, retaining the state of rules that did not change.
+    pub fn set_rules(&mut self, rules: &[AlertRule]) {
+        let mut previous = std::mem::take(&mut self.rules);
+        self.rules = rules
+            .iter()
+            .map(|rule| {
+                let state = previous
+                    .iter()
+                    .position(|(previous, _)| previous == rule)
+                    .map(|index| previous.remove(index).1)
+                    .unwrap_or_default();
+                (rule.clone(), state)
+            })
+            .collect();
+    }
+
+    /// Rules whose alert is currently raised.
+    pub fn active(&self) -> impl Iterator<Item = &AlertRule> {
+        self.rules
+            .iter()
+            .filter(|(_, state)| state.active)
+            .map(|(rule, _)| rule)
+    }
+
+    pub fn log(&self) -> &VecDeque<Alert> {
+        &self.log
+    }
+
+    pub fn clear_log(&mut self) {
+        self.log.clear();
+    }
+
+    /// Replace the log (e.g. with the log stored by a previous session).
+    pub fn set_log(&mut self, log: Vec<Alert>) {
+        let excess = log.len().saturating_sub(MAX_ALERT_LOG_ENTRIES);
+        self.log = log.into_iter().skip(excess).collect();
+    }
+
+    /// Forget pending and raised alerts, e.g. when the node disconnects.
+    pub fn reset(&mut self) {
+        for (_, state) in self.rules.iter_mut() {
+            *state = RuleState::default();
+        }
+    }
+
+    /// Evaluate all enabled rules against the snapshot, returning
+    /// alerts that have been raised or resolved by it.
+    pub fn evaluate(&mut self, snapshot: &MetricsSnapshot) -> Vec<Alert> {
+        let timestamp = snapshot.unixtime_millis;
+        let mut alerts = Vec::new();
+
+        for (rule, state) in self.rules.iter_mut() {
+            let value = snapshot.get(&rule.metric);
+            if !rule.enabled || !value.is_finite() {
+                *state = RuleState::default();
+                continue;
+            }
+
+            if rule.comparison.test(value, rule.threshold) {
+                let since = *state.since.get_or_insert(timestamp);
+                if !state.active && timestamp - since >= rule.duration_secs as f64 * 1000.0 {
+                    state.active = true;
+                    alerts.push(Alert {
+                        timestamp,
+                        rule: rule.clone(),
+                        value,
+                        state: AlertState::Raised,
+                    });
+                }
+            } else {
+                state.since = None;
+                if state.active {
+                    state.active = false;
+                    alerts.push(Alert {
+                        timestamp,
+                        rule: rule.clone(),
+                        value,
+                        state: AlertState::Resolved,
+                    });
+                }
+            }
+        }
+
+        for alert in alerts.iter() {
+            if self.log.len() >= MAX_ALERT_LOG_ENTRIES {
+                self.log.pop_front();
+            }
+            self.log.push_back(alert.clone());
+        }
+
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kaspa_metrics_core::Metric;
+
+    fn snapshot(seconds: u64, peers: f64) -> MetricsSnapshot {
+        MetricsSnapshot {
+            unixtime_millis: seconds as f64 * 1000.0,
+            node_active_peers: peers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let rule = AlertRule {
+            enabled: true,
+            metric: Metric::NodeActivePeers,
+            comparison: AlertComparison::Below,
+            threshold: 1.0,
+            duration_secs: 60,
+        };
+        let mut monitor = AlertMonitor::new(&[rule]);
+
+        // the alert is raised only once the condition held for the rule duration
+        assert!(monitor.evaluate(&snapshot(0, 0.0)).is_empty());
+        assert!(monitor.evaluate(&snapshot(59, 0.0)).is_empty());
+        let alerts = monitor.evaluate(&snapshot(60, 0.0));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].state, AlertState::Raised);
+        assert_eq!(monitor.active().count(), 1);
+
+        // a raised alert is not repeated while the condition holds
+        assert!(monitor.evaluate(&snapshot(120, 0.0)).is_empty());
+
+        let alerts = monitor.evaluate(&snapshot(121, 8.0));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].state, AlertState::Resolved);
+        assert_eq!(alerts[0].value, 8.0);
+        assert_eq!(monitor.active().count(), 0);
+        assert_eq!(monitor.log().len(), 2);
+
+        // an interruption restarts the duration
+        assert!(monitor.evaluate(&snapshot(200, 0.0)).is_empty());
+        assert!(monitor.evaluate(&snapshot(230, 8.0)).is_empty());
+        assert!(monitor.evaluate(&snapshot(240, 0.0)).is_empty());
+        assert!(monitor.evaluate(&snapshot(290, 0.0)).is_empty());
+        assert_eq!(monitor.evaluate(&snapshot(300, 0.0)).len(), 1);
+    }
+
+    #[test]
+    fn test_evaluate_disabled_rule() {
+        let rule = AlertRule {
+            enabled: false,
+            duration_secs: 0,
+            ..Default::default()
+        };
+        let mut monitor = AlertMonitor::new(&[rule]);
+        assert!(monitor.evaluate(&snapshot(0, 0.0)).is_empty());
+        assert!(monitor.evaluate(&snapshot(60, 0.0)).is_empty());
+        assert!(monitor.log().is_empty());
+    }
+}
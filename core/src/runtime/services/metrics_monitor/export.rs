@@ -0,0 +1,190 @@
+use super::history::{MetricSample, MetricsHistory, MetricsTier};
+use crate::imports::*;
+use chrono::DateTime;
+use kaspa_metrics_core::Metric;
+use std::fmt::Write;
+
+/// File format of exported metrics.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MetricsExportFormat {
+    Csv,
+    Json,
+}
+
+impl MetricsExportFormat {
+    pub fn list() -> &'static [MetricsExportFormat] {
+        &[MetricsExportFormat::Csv, MetricsExportFormat::Json]
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            MetricsExportFormat::Csv => "CSV",
+            MetricsExportFormat::Json => "JSON",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MetricsExportFormat::Csv => "csv",
+            MetricsExportFormat::Json => "json",
+        }
+    }
+
+    /// Format matching the file extension, CSV by default.
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => MetricsExportFormat::Json,
+            _ => MetricsExportFormat::Csv,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ExportSample {
+    timestamp: f64,
+    min: f64,
+    max: f64,
+    avg: f64,
+}
+
+impl From<&MetricSample> for ExportSample {
+    fn from(sample: &MetricSample) -> Self {
+        Self {
+            timestamp: sample.timestamp,
+            min: sample.min,
+            max: sample.max,
+            avg: sample.avg,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ExportSeries {
+    metric: &'static str,
+    title: String,
+    resolution_secs: f64,
+    samples: Vec<ExportSample>,
+}
+
+/// Serialize samples of the given metrics and tiers within the
+/// time range (unix time in milliseconds). CSV output has one row
+/// per sample: `metric,resolution_secs,timestamp,datetime,min,max,avg`.
+pub fn export_metrics(
+    history: &MetricsHistory,
+    metrics: &[Metric],
+    tiers: &[MetricsTier],
+    range: std::ops::Range<f64>,
+    format: MetricsExportFormat,
+) -> Result<String> {
+    let series = metrics
+        .iter()
+        .filter_map(|metric| history.get(metric).map(|history| (metric, history)))
+        .flat_map(|(metric, history)| {
+            tiers.iter().map(move |tier| ExportSeries {
+                metric: metric.as_str(),
+                title: metric.title().0.to_string(),
+                resolution_secs: tier.resolution_millis() / 1000.0,
+                samples: history
+                    .samples(*tier, range.clone())
+                    .iter()
+                    .map(ExportSample::from)
+                    .collect(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    match format {
+        MetricsExportFormat::Json => Ok(serde_json::to_string_pretty(&series)?),
+        MetricsExportFormat::Csv => {
+            let mut csv = String::from("metric,resolution_secs,timestamp,datetime,min,max,avg\n");
+            for series in series.iter() {
+                for sample in series.samples.iter() {
+                    let datetime = DateTime::from_timestamp_millis(sample.timestamp as i64)
+                        .map(|datetime| datetime.to_rfc3339())
+                        .unwrap_or_default();
+                    writeln!(
+                        csv,
+                        "{},{},{},{},{},{},{}",
+                        series.metric,
+                        series.resolution_secs,
+                        sample.timestamp,
+                        datetime,
+                        sample.min,
+                        sample.max,
+                        sample.avg
+                    )
+                    .ok();
+                }
+            }
+            Ok(csv)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_export_csv() {
+        let metric = Metric::NetworkMempoolSize;
+        let mut history = MetricsHistory::default();
+        history.ingest(metric, 60_000.0, 2.0);
+        history.ingest(metric, 90_000.0, 4.0);
+        history.ingest(metric, 120_000.0, 8.0);
+
+        let csv = export_metrics(
+            &history,
+            &[metric],
+            &[MetricsTier::Minutes],
+            f64::NEG_INFINITY..f64::INFINITY,
+            MetricsExportFormat::Csv,
+        )
+        .unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "metric,resolution_secs,timestamp,datetime,min,max,avg".to_string(),
+                format!(
+                    "{},60,60000,1970-01-01T00:01:00+00:00,2,4,3",
+                    metric.as_str()
+                ),
+                format!(
+                    "{},60,120000,1970-01-01T00:02:00+00:00,8,8,8",
+                    metric.as_str()
+                ),
+            ]
+        );
+
+        // the time range and the selected metrics restrict the exported rows
+        let csv = export_metrics(
+            &history,
+            &[metric],
+            &[MetricsTier::Minutes],
+            100_000.0..f64::INFINITY,
+            MetricsExportFormat::Csv,
+        )
+        .unwrap();
+        assert_eq!(csv.lines().count(), 2);
+
+        let csv = export_metrics(
+            &history,
+            &[],
+            MetricsTier::list(),
+            f64::NEG_INFINITY..f64::INFINITY,
+            MetricsExportFormat::Csv,
+        )
+        .unwrap();
+        assert_eq!(csv.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_export_format_from_path() {
+        let format = |path: &str| MetricsExportFormat::from_path(std::path::Path::new(path));
+        assert_eq!(format("metrics.json"), MetricsExportFormat::Json);
+        assert_eq!(format("metrics.JSON"), MetricsExportFormat::Json);
+        assert_eq!(format("metrics.csv"), MetricsExportFormat::Csv);
+        assert_eq!(format("metrics"), MetricsExportFormat::Csv);
+    }
+}
@@ -0,0 +1,381 @@
+use crate::imports::*;
+use kaspa_metrics_core::Metric;
+
+/// Resolution tiers of the metrics history. Every sample is
+/// ingested into all tiers; coarser tiers aggregate the samples
+/// falling within their resolution into min/max/avg values.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MetricsTier {
+    /// 1 second resolution, last hour
+    Seconds,
+    /// 1 minute resolution, last week
+    Minutes,
+    /// 1 hour resolution, last year
+    Hours,
+}
+
+impl MetricsTier {
+    pub fn list() -> &'static [MetricsTier] {
+        &[
+            MetricsTier::Seconds,
+            MetricsTier::Minutes,
+            MetricsTier::Hours,
+        ]
+    }
+
+    pub fn resolution_millis(&self) -> f64 {
+        match self {
+            MetricsTier::Seconds => 1000.0,
+            MetricsTier::Minutes => 60.0 * 1000.0,
+            MetricsTier::Hours => 60.0 * 60.0 * 1000.0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        match self {
+            MetricsTier::Seconds => 60 * 60,
+            MetricsTier::Minutes => 60 * 24 * 7,
+            MetricsTier::Hours => 24 * 365,
+        }
+    }
+
+    /// Time span covered by a full tier
+    pub fn span_millis(&self) -> f64 {
+        self.resolution_millis() * self.capacity() as f64
+    }
+
+    /// Finest tier that still retains samples of the given age
+    pub fn for_age(age_millis: f64) -> MetricsTier {
+        MetricsTier::list()
+            .iter()
+            .find(|tier| tier.span_millis() >= age_millis)
+            .copied()
+            .unwrap_or(MetricsTier::Hours)
+    }
+
+    /// Tiers written to disk; the seconds tier only covers
+    /// the last hour and is rebuilt after a restart.
+    pub fn is_persistent(&self) -> bool {
+        !matches!(self, MetricsTier::Seconds)
+    }
+
+    /// Time between two consecutive samples beyond which
+    /// the samples are considered to be missing
+    pub fn gap_millis(&self) -> f64 {
+        self.resolution_millis() * 5.0
+    }
+}
+
+/// Maximum age of the metrics history in seconds
+pub const METRICS_HISTORY_SECONDS: usize = 60 * 60 * 24 * 365;
+
+/// Aggregated metric value over a tier resolution period.
+#[derive(Debug, Clone, Copy, BorshSerialize, BorshDeserialize)]
+pub struct MetricSample {
+    /// Start of the period (unix time in milliseconds)
+    pub timestamp: f64,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+}
+
+impl MetricSample {
+    pub fn avg_point(&self) -> PlotPoint {
+        PlotPoint {
+            x: self.timestamp,
+            y: self.avg,
+        }
+    }
+
+    pub fn min_point(&self) -> PlotPoint {
+        PlotPoint {
+            x: self.timestamp,
+            y: self.min,
+        }
+    }
+
+    pub fn max_point(&self) -> PlotPoint {
+        PlotPoint {
+            x: self.timestamp,
+            y: self.max,
+        }
+    }
+}
+
+/// Period currently being aggregated.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+struct Bucket {
+    start: f64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: u32,
+}
+
+impl Bucket {
+    fn new(start: f64, value: f64) -> Self {
+        Self {
+            start,
+            min: value,
+            max: value,
+            sum: value,
+            count: 1,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn sample(&self) -> MetricSample {
+        MetricSample {
+            timestamp: self.start,
+            min: self.min,
+            max: self.max,
+            avg: self.sum / self.count as f64,
+        }
+    }
+}
+
+/// Ring buffer of aggregated samples of a single tier.
+#[derive(Debug, Clone, Default, BorshSerialize, BorshDeserialize)]
+struct TierBuffer {
+    samples: VecDeque<MetricSample>,
+    bucket: Option<Bucket>,
+}
+
+impl TierBuffer {
+    fn ingest(&mut self, tier: MetricsTier, timestamp: f64, value: f64) {
+        let resolution = tier.resolution_millis();
+        let start = (timestamp / resolution).floor() * resolution;
+        match self.bucket.as_mut() {
+            Some(bucket) if bucket.start == start => bucket.add(value),
+            _ => {
+                if let Some(bucket) = self.bucket.replace(Bucket::new(start, value)) {
+                    if self.samples.len() >= tier.capacity() {
+                        self.samples.pop_front();
+                    }
+                    self.samples.push_back(bucket.sample());
+                }
+            }
+        }
+    }
+
+    fn samples(&self, range: std::ops::Range<f64>) -> Vec<MetricSample> {
+        let start = self
+            .samples
+            .partition_point(|sample| sample.timestamp < range.start);
+        self.samples
+            .range(start..)
+            .copied()
+            .chain(self.bucket.as_ref().map(Bucket::sample))
+            .take_while(|sample| sample.timestamp < range.end)
+            .collect()
+    }
+}
+
+/// Tiered history of a single metric.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct MetricHistory {
+    tiers: Vec<TierBuffer>,
+}
+
+impl Default for MetricHistory {
+    fn default() -> Self {
+        Self {
+            tiers: MetricsTier::list()
+                .iter()
+                .map(|_| TierBuffer::default())
+                .collect(),
+        }
+    }
+}
+
+impl MetricHistory {
+    pub fn ingest(&mut self, timestamp: f64, value: f64) {
+        for (tier, buffer) in MetricsTier::list().iter().zip(self.tiers.iter_mut()) {
+            buffer.ingest(*tier, timestamp, value);
+        }
+    }
+
+    /// Copy of the history containing only the persistent tiers.
+    pub fn to_persistent(&self) -> MetricHistory {
+        MetricHistory {
+            tiers: MetricsTier::list()
+                .iter()
+                .zip(self.tiers.iter())
+                .map(|(tier, buffer)| {
+                    if tier.is_persistent() {
+                        buffer.clone()
+                    } else {
+                        TierBuffer::default()
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Samples of the given tier with timestamps within the range,
+    /// including the period currently being aggregated.
+    pub fn samples(&self, tier: MetricsTier, range: std::ops::Range<f64>) -> Vec<MetricSample> {
+        MetricsTier::list()
+            .iter()
+            .position(|t| *t == tier)
+            .and_then(|index| self.tiers.get(index))
+            .map(|buffer| buffer.samples(range))
+            .unwrap_or_default()
+    }
+
+    /// Most recent sample, provided it is not older than `max_age_millis`.
+    pub fn latest(&self, max_age_millis: f64) -> Option<MetricSample> {
+        self.samples(
+            MetricsTier::Seconds,
+            (unixtime_as_millis_f64() - max_age_millis)..f64::INFINITY,
+        )
+        .pop()
+    }
+
+    /// Samples of the last `duration_millis` at the finest tier covering them.
+    pub fn recent(&self, duration_millis: f64) -> Vec<MetricSample> {
+        let now = unixtime_as_millis_f64();
+        self.samples(
+            MetricsTier::for_age(duration_millis),
+            (now - duration_millis)..f64::INFINITY,
+        )
+    }
+}
+
+/// Tiered history of all metrics.
+#[derive(Debug, Clone)]
+pub struct MetricsHistory {
+    metrics: HashMap<Metric, MetricHistory>,
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self {
+            metrics: Metric::into_iter()
+                .map(|metric| (metric, MetricHistory::default()))
+                .collect(),
+        }
+    }
+}
+
+impl MetricsHistory {
+    pub fn get(&self, metric: &Metric) -> Option<&MetricHistory> {
+        self.metrics.get(metric)
+    }
+
+    pub fn insert(&mut self, metric: Metric, history: MetricHistory) {
+        self.metrics.insert(metric, history);
+    }
+
+    pub fn ingest(&mut self, metric: Metric, timestamp: f64, value: f64) {
+        self.metrics
+            .entry(metric)
+            .or_default()
+            .ingest(timestamp, value);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Metric, &MetricHistory)> {
+        self.metrics.iter()
+    }
+}
+
+/// Split samples into contiguous runs, breaking where samples are
+/// missing (e.g. while the application was closed or disconnected).
+pub fn split_gaps(samples: &[MetricSample], tier: MetricsTier) -> Vec<&[MetricSample]> {
+    let gap = tier.gap_millis();
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for (index, pair) in samples.windows(2).enumerate() {
+        if pair[1].timestamp - pair[0].timestamp > gap {
+            runs.push(&samples[start..=index]);
+            start = index + 1;
+        }
+    }
+    if start < samples.len() {
+        runs.push(&samples[start..]);
+    }
+    runs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample(timestamp: f64) -> MetricSample {
+        MetricSample {
+            timestamp,
+            min: 0.0,
+            max: 0.0,
+            avg: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_tier_buffer() {
+        let tier = MetricsTier::Minutes;
+        let mut buffer = TierBuffer::default();
+        buffer.ingest(tier, 60_000.0, 1.0);
+        buffer.ingest(tier, 90_000.0, 3.0);
+        buffer.ingest(tier, 119_999.0, 5.0);
+        buffer.ingest(tier, 120_000.0, 10.0);
+
+        // the first period is closed, the second one is still being aggregated
+        assert_eq!(buffer.samples.len(), 1);
+        let samples = buffer.samples(f64::NEG_INFINITY..f64::INFINITY);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].timestamp, 60_000.0);
+        assert_eq!(samples[0].min, 1.0);
+        assert_eq!(samples[0].max, 5.0);
+        assert_eq!(samples[0].avg, 3.0);
+        assert_eq!(samples[1].timestamp, 120_000.0);
+        assert_eq!(samples[1].avg, 10.0);
+
+        let samples = buffer.samples(100_000.0..f64::INFINITY);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].timestamp, 120_000.0);
+        assert!(buffer.samples(0.0..60_000.0).is_empty());
+    }
+
+    #[test]
+    fn test_tier_buffer_capacity() {
+        let tier = MetricsTier::Hours;
+        let resolution = tier.resolution_millis();
+        let mut buffer = TierBuffer::default();
+        for index in 0..tier.capacity() + 10 {
+            buffer.ingest(tier, index as f64 * resolution, index as f64);
+        }
+
+        assert_eq!(buffer.samples.len(), tier.capacity());
+        assert_eq!(buffer.samples.front().unwrap().timestamp, 9.0 * resolution);
+    }
+
+    #[test]
+    fn test_split_gaps() {
+        let tier = MetricsTier::Seconds;
+        assert!(split_gaps(&[], tier).is_empty());
+
+        let samples = [0.0, 1_000.0, 2_000.0, 60_000.0, 61_000.0, 120_000.0]
+            .into_iter()
+            .map(sample)
+            .collect::<Vec<_>>();
+        let runs = split_gaps(&samples, tier);
+        assert_eq!(
+            runs.iter().map(|run| run.len()).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(runs[1][0].timestamp, 60_000.0);
+
+        // samples within the gap threshold form a single run
+        let samples = [0.0, 5_000.0, 10_000.0]
+            .into_iter()
+            .map(sample)
+            .collect::<Vec<_>>();
+        assert_eq!(split_gaps(&samples, tier).len(), 1);
+    }
+}
@@ -0,0 +1,458 @@
+use crate::imports::*;
+use crate::runtime::Service;
+pub use futures::{future::FutureExt, select, Future};
+use kaspa_metrics_core::{Metric, Metrics, MetricsSnapshot};
+use kaspa_rpc_core::GetSystemInfoResponse;
+#[allow(unused_imports)]
+use kaspa_wallet_core::rpc::{NotificationMode, Rpc, RpcCtl, WrpcEncoding};
+#[cfg(not(target_arch = "wasm32"))]
+use kaspa_wallet_core::storage::local::storage::Storage;
+
+pub mod alerts;
+pub use alerts::{Alert, AlertMonitor, AlertState};
+
+pub mod export;
+pub use export::{export_metrics, MetricsExportFormat};
+
+pub mod history;
+pub use history::{
+    split_gaps, MetricHistory, MetricSample, MetricsHistory, MetricsTier, METRICS_HISTORY_SECONDS,
+};
+
+/// Interval at which the metrics history is written to disk
+pub const METRICS_STORE_INTERVAL_SECONDS: u64 = 5 * 60;
+/// Version of the on-disk metrics history format
+const METRICS_STORE_VERSION: u32 = 1;
+
+pub enum MetricsServiceEvents {
+    Exit,
+}
+
+/// On-disk metrics history. Metrics are stored by name
+/// so that the store survives changes to the metric list.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(BorshSerialize, BorshDeserialize)]
+struct MetricsStore {
+    version: u32,
+    metrics: Vec<(String, MetricHistory)>,
+}
+
+pub struct MetricsService {
+    pub application_events: ApplicationEventsChannel,
+    pub service_events: Channel<MetricsServiceEvents>,
+    pub task_ctl: Channel<()>,
+    pub metrics: Arc<Metrics>,
+    pub metrics_history: Mutex<MetricsHistory>,
+    pub samples_since_connection: Arc<AtomicUsize>,
+    pub rpc_api: Mutex<Option<Arc<dyn RpcApi>>>,
+    /// Network the metrics history belongs to
+    network: Mutex<Option<Network>>,
+    /// Set once the stored history has been loaded for the connected network;
+    /// snapshots received before that are discarded.
+    history_ready: AtomicBool,
+    alerts: Mutex<AlertMonitor>,
+    /// Set when the alert log changes; cleared once it is written to disk.
+    alert_log_changed: AtomicBool,
+}
+
+impl MetricsService {
+    pub fn new(application_events: ApplicationEventsChannel, settings: &Settings) -> Self {
+        let metrics = Arc::new(Metrics::default());
+
+        Self {
+            application_events,
+            service_events: Channel::unbounded(),
+            task_ctl: Channel::oneshot(),
+            metrics,
+            metrics_history: Mutex::new(MetricsHistory::default()),
+            samples_since_connection: Arc::new(AtomicUsize::new(0)),
+            rpc_api: Mutex::new(None),
+            network: Mutex::new(None),
+            history_ready: AtomicBool::new(false),
+            alerts: Mutex::new(AlertMonitor::new(&settings.user_interface.metrics.alerts)),
+            alert_log_changed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn rpc_api(&self) -> Option<Arc<dyn RpcApi>> {
+        self.rpc_api.lock().unwrap().clone()
+    }
+
+    /// In-memory metrics history of the current network.
+    pub fn metrics_data(&self) -> MutexGuard<'_, MetricsHistory> {
+        self.metrics_history.lock().unwrap()
+    }
+
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    /// Number of alerts currently raised.
+    pub fn active_alerts(&self) -> usize {
+        self.alerts.lock().unwrap().active().count()
+    }
+
+    /// Copy of the alert log, oldest entry first.
+    pub fn alert_log(&self) -> Vec<Alert> {
+        self.alerts.lock().unwrap().log().iter().cloned().collect()
+    }
+
+    pub fn clear_alert_log(&self) {
+        self.alerts.lock().unwrap().clear_log();
+        self.alert_log_changed.store(true, Ordering::SeqCst);
+    }
+
+    /// Update the alert rules evaluated against incoming metrics.
+    pub fn set_alert_rules(&self, rules: &[AlertRule]) {
+        self.alerts.lock().unwrap().set_rules(rules);
+    }
+
+    /// Export samples of the given metrics and tiers within the time range.
+    pub fn export(
+        &self,
+        metrics: &[Metric],
+        tiers: &[MetricsTier],
+        range: std::ops::Range<f64>,
+        format: MetricsExportFormat,
+    ) -> Result<String> {
+        export_metrics(&self.metrics_data(), metrics, tiers, range, format)
+    }
+
+    /// Write the entire in-memory metrics history to a file,
+    /// as JSON or CSV depending on the file extension.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn dump(&self, path: &std::path::Path) -> Result<()> {
+        let data = self.export(
+            &Metric::into_iter().collect::<Vec<_>>(),
+            MetricsTier::list(),
+            f64::NEG_INFINITY..f64::INFINITY,
+            MetricsExportFormat::from_path(path),
+        )?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    pub fn reset_metrics_data(&self) -> Result<()> {
+        *self.metrics_history.lock().unwrap() = MetricsHistory::default();
+        Ok(())
+    }
+
+    fn network(&self) -> Option<Network> {
+        *self.network.lock().unwrap()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn storage(network: Network) -> Result<Storage> {
+        Ok(Storage::try_new(&format!("kaspa-ng.metrics.{network}"))?)
+    }
+
+    /// Load the stored metrics history of the given network.
+    async fn load(&self, network: Network) -> Result<()> {
+        cfg_if! {
+            if #[cfg(not(target_arch = "wasm32"))] {
+                let storage = Self::storage(network)?;
+                if !storage.exists().await.unwrap_or(false) {
+                    return Ok(());
+                }
+
+                let store: MetricsStore =
+                    borsh::from_slice(&std::fs::read(storage.filename())?)?;
+                if store.version != METRICS_STORE_VERSION {
+                    return Ok(());
+                }
+
+                let mut metrics_history = self.metrics_history.lock().unwrap();
+                for (name, history) in store.metrics {
+                    let metric = Metric::into_iter().find(|metric| metric.as_str() == name);
+                    if let Some(metric) = metric {
+                        metrics_history.insert(metric, history);
+                    }
+                }
+            } else {
+                let _ = network;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the metrics history of the current network to disk.
+    pub async fn store(&self) -> Result<()> {
+        cfg_if! {
+            if #[cfg(not(target_arch = "wasm32"))] {
+                let Some(network) = self.network() else {
+                    return Ok(());
+                };
+
+                let metrics = self
+                    .metrics_history
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(metric, history)| (metric.as_str().to_string(), history.to_persistent()))
+                    .collect::<Vec<_>>();
+
+                let storage = Self::storage(network)?;
+                storage.ensure_dir().await?;
+                let filename = storage.filename().clone();
+                tokio::task::spawn_blocking(move || -> Result<()> {
+                    let store = MetricsStore {
+                        version: METRICS_STORE_VERSION,
+                        metrics,
+                    };
+                    let data = borsh::to_vec(&store)?;
+                    std::fs::write(filename, data)?;
+                    Ok(())
+                })
+                .await
+                .map_err(|err| Error::custom(err.to_string()))??;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn alert_log_storage() -> Result<Storage> {
+        Ok(Storage::try_new("kaspa-ng.alerts")?)
+    }
+
+    /// Load the alert log stored by a previous session.
+    async fn load_alert_log(&self) -> Result<()> {
+        cfg_if! {
+            if #[cfg(not(target_arch = "wasm32"))] {
+                let storage = Self::alert_log_storage()?;
+                if storage.exists().await.unwrap_or(false) {
+                    let log = workflow_store::fs::read_json::<Vec<Alert>>(storage.filename()).await?;
+                    self.alerts.lock().unwrap().set_log(log);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the alert log to disk if it has changed since it was last written.
+    async fn store_alert_log(&self) -> Result<()> {
+        cfg_if! {
+            if #[cfg(not(target_arch = "wasm32"))] {
+                if !self.alert_log_changed.swap(false, Ordering::SeqCst) {
+                    return Ok(());
+                }
+
+                let log = self.alert_log();
+                let storage = Self::alert_log_storage()?;
+                storage.ensure_dir().await?;
+                workflow_store::fs::write_json(storage.filename(), &log).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Identify the network of the connected node and load its
+    /// metrics history if it differs from the one in memory.
+    async fn load_history(&self) -> Result<()> {
+        let Some(rpc_api) = self.rpc_api() else {
+            return Ok(());
+        };
+
+        let network = Network::from(rpc_api.get_server_info().await?.network_id);
+        if self.network() != Some(network) {
+            self.store().await?;
+            self.reset_metrics_data()?;
+            self.network.lock().unwrap().replace(network);
+            self.load(network).await?;
+        }
+
+        Ok(())
+    }
+
+    pub fn ingest_metrics_snapshot(&self, snapshot: Box<MetricsSnapshot>) -> Result<()> {
+        if !self.history_ready.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        // the first snapshot has no preceding one to derive rates from
+        if snapshot.duration_millis >= 0.0 {
+            let timestamp = snapshot.unixtime_millis;
+            let mut metrics_history = self.metrics_history.lock().unwrap();
+            for metric in Metric::into_iter() {
+                let y = snapshot.get(&metric);
+                let y = if y.is_finite() { y } else { 0.0 };
+                metrics_history.ingest(metric, timestamp, y);
+            }
+            drop(metrics_history);
+
+            let alerts = self.alerts.lock().unwrap().evaluate(&snapshot);
+            if !alerts.is_empty() {
+                self.alert_log_changed.store(true, Ordering::SeqCst);
+            }
+            for alert in alerts {
+                let user_notification = match alert.state {
+                    AlertState::Raised => UserNotification::warning(alert.message()),
+                    AlertState::Resolved => UserNotification::info(alert.message()),
+                };
+                self.application_events
+                    .sender
+                    .try_send(crate::events::Events::Notify { user_notification })
+                    .ok();
+            }
+        }
+
+        if snapshot.node_cpu_cores > 0.0 {
+            self.application_events
+                .sender
+                .try_send(crate::events::Events::MempoolSize {
+                    mempool_size: snapshot.get(&Metric::NetworkMempoolSize) as usize,
+                })
+                .unwrap();
+
+            self.application_events
+                .sender
+                .try_send(crate::events::Events::Metrics { snapshot })
+                .unwrap();
+        }
+
+        self.samples_since_connection.fetch_add(1, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    pub fn samples_since_connection(&self) -> usize {
+        self.samples_since_connection.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Service for MetricsService {
+    fn name(&self) -> &'static str {
+        "metrics-service"
+    }
+
+    async fn attach_rpc(self: Arc<Self>, rpc_api: &Arc<dyn RpcApi>) -> Result<()> {
+        self.rpc_api.lock().unwrap().replace(rpc_api.clone());
+
+        let this = self.clone();
+        self.metrics
+            .register_sink(Arc::new(Box::new(move |snapshot: MetricsSnapshot| {
+                if let Err(err) = this.ingest_metrics_snapshot(Box::new(snapshot)) {
+                    println!("Error ingesting metrics snapshot: {}", err);
+                }
+                None
+            })));
+
+        self.history_ready.store(false, Ordering::SeqCst);
+        if let Err(err) = self.store().await {
+            log_warn!("Unable to store metrics history: {err}");
+        }
+        self.reset_metrics_data()?;
+        self.network.lock().unwrap().take();
+        self.metrics.start_task().await?;
+        self.metrics.bind_rpc(Some(rpc_api.clone()));
+        Ok(())
+    }
+    async fn detach_rpc(self: Arc<Self>) -> Result<()> {
+        self.rpc_api.lock().unwrap().take();
+
+        self.metrics.unregister_sink();
+        self.metrics.stop_task().await?;
+        self.metrics.bind_rpc(None);
+
+        Ok(())
+    }
+
+    async fn connect_rpc(self: Arc<Self>) -> Result<()> {
+        self.samples_since_connection.store(0, Ordering::SeqCst);
+
+        if let Err(err) = self.load_history().await {
+            log_warn!("Unable to load metrics history: {err}");
+        }
+        self.history_ready.store(true, Ordering::SeqCst);
+
+        if let Some(rpc_api) = self.rpc_api() {
+            if let Ok(system_info) = rpc_api.get_system_info().await {
+                let GetSystemInfoResponse {
+                    version, system_id, ..
+                } = system_info;
+
+                let system_id = system_id
+                    .map(|id| format!(" - {}", id[0..8].to_vec().to_hex()))
+                    .unwrap_or_else(|| "".to_string());
+
+                self.application_events
+                    .sender
+                    .try_send(crate::events::Events::NodeInfo {
+                        node_info: Some(Box::new(format!("{}{}", version, system_id))),
+                    })
+                    .unwrap();
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn disconnect_rpc(self: Arc<Self>) -> Result<()> {
+        self.application_events
+            .sender
+            .try_send(crate::events::Events::NodeInfo { node_info: None })
+            .unwrap();
+
+        self.alerts.lock().unwrap().reset();
+
+        if let Err(err) = self.store().await {
+            log_warn!("Unable to store metrics history: {err}");
+        }
+        Ok(())
+    }
+
+    async fn spawn(self: Arc<Self>) -> Result<()> {
+        if let Err(err) = self.load_alert_log().await {
+            log_warn!("Unable to load alert log: {err}");
+        }
+
+        let interval = task::interval(Duration::from_secs(METRICS_STORE_INTERVAL_SECONDS));
+        pin_mut!(interval);
+
+        loop {
+            select! {
+                _ = interval.next().fuse() => {
+                    if let Err(err) = self.store().await {
+                        log_warn!("Unable to store metrics history: {err}");
+                    }
+                    if let Err(err) = self.store_alert_log().await {
+                        log_warn!("Unable to store alert log: {err}");
+                    }
+                },
+                msg = self.as_ref().service_events.receiver.recv().fuse() => {
+                    match msg {
+                        Ok(MetricsServiceEvents::Exit) | Err(_) => {
+                            if let Err(err) = self.store().await {
+                                log_warn!("Unable to store metrics history: {err}");
+                            }
+                            if let Err(err) = self.store_alert_log().await {
+                                log_warn!("Unable to store alert log: {err}");
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.task_ctl.send(()).await.unwrap();
+        Ok(())
+    }
+
+    fn terminate(self: Arc<Self>) {
+        self.service_events
+            .sender
+            .try_send(MetricsServiceEvents::Exit)
+            .unwrap();
+    }
+
+    async fn join(self: Arc<Self>) -> Result<()> {
+        self.task_ctl.recv().await.unwrap();
+        Ok(())
+    }
+}
@@ -20,11 +20,22 @@ pub use update_monitor::UpdateMonitorService;
 
 pub mod metrics_monitor;
 pub use metrics_monitor::MetricsService;
+
+cfg_if! {
+    if #[cfg(not(target_arch = "wasm32"))] {
+        pub mod prometheus;
+        pub use prometheus::PrometheusService;
+    }
+}
+
 cfg_if! {
     if #[cfg(not(feature = "lean"))] {
 
         pub mod blockdag_monitor;
         pub use blockdag_monitor::BlockDagMonitorService;
+
+        pub mod network_monitor;
+        pub use network_monitor::NetworkMonitorService;
     }
 }
 
@@ -0,0 +1,380 @@
+use crate::imports::*;
+use kaspa_metrics_core::Metric;
+use kaspa_wallet_core::storage::local::storage::Storage;
+
+pub const NETWORK_STATS_SAMPLING_INTERVAL_SECONDS: u64 = 60;
+/// Retain 30 days of samples
+pub const MAX_NETWORK_STATS_SAMPLES: usize = 60 * 24 * 30;
+/// Number of samples collected between writes to the local storage
+const NETWORK_STATS_STORE_INTERVAL: usize = 10;
+/// Number of blocks used by the node to estimate the network hashrate
+const HASHRATE_ESTIMATION_WINDOW: u32 = 1000;
+
+/// Network-level figures tracked by the [`NetworkMonitorService`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum NetworkStat {
+    Hashrate,
+    Difficulty,
+    DaaScoreRate,
+    BlocksPerSecond,
+    TransactionsPerSecond,
+}
+
+impl NetworkStat {
+    pub fn list() -> &'static [NetworkStat] {
+        &[
+            NetworkStat::Hashrate,
+            NetworkStat::Difficulty,
+            NetworkStat::DaaScoreRate,
+            NetworkStat::BlocksPerSecond,
+            NetworkStat::TransactionsPerSecond,
+        ]
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            NetworkStat::Hashrate => i18n("Network Hashrate"),
+            NetworkStat::Difficulty => i18n("Difficulty"),
+            NetworkStat::DaaScoreRate => i18n("DAA Score Rate"),
+            NetworkStat::BlocksPerSecond => i18n("Blocks per Second"),
+            NetworkStat::TransactionsPerSecond => i18n("Transactions per Second"),
+        }
+    }
+
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            NetworkStat::Hashrate => format!("{}H/s", as_si_units(value)),
+            NetworkStat::Difficulty => as_si_units(value),
+            NetworkStat::DaaScoreRate
+            | NetworkStat::BlocksPerSecond
+            | NetworkStat::TransactionsPerSecond => format!("{value:.2}"),
+        }
+    }
+}
+
+fn as_si_units(value: f64) -> String {
+    const UNITS: [&str; 7] = ["", "K", "M", "G", "T", "P", "E"];
+    let mut value = value;
+    let mut unit = 0;
+    while value.abs() >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{value:.2} {}", UNITS[unit])
+}
+
+/// Network figures sampled at a point in time. Rates are
+/// derived from the previous sample and are absent for the
+/// first sample following a connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkStatsSample {
+    /// Unix time in milliseconds
+    pub timestamp: f64,
+    pub hashrate: f64,
+    pub difficulty: f64,
+    #[serde(default)]
+    pub daa_score_rate: Option<f64>,
+    #[serde(default)]
+    pub bps: Option<f64>,
+    #[serde(default)]
+    pub tps: Option<f64>,
+}
+
+impl NetworkStatsSample {
+    pub fn get(&self, stat: NetworkStat) -> Option<f64> {
+        match stat {
+            NetworkStat::Hashrate => Some(self.hashrate),
+            NetworkStat::Difficulty => Some(self.difficulty),
+            NetworkStat::DaaScoreRate => self.daa_score_rate,
+            NetworkStat::BlocksPerSecond => self.bps,
+            NetworkStat::TransactionsPerSecond => self.tps,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct NetworkStatsStore {
+    samples: Vec<NetworkStatsSample>,
+}
+
+/// DAG state at the time of the previous sample.
+struct Cursor {
+    timestamp: f64,
+    daa_score: u64,
+    block_count: u64,
+}
+
+pub enum NetworkMonitorEvents {
+    Connect,
+    Disconnect,
+    Exit,
+}
+
+pub struct NetworkMonitorService {
+    pub application_events: ApplicationEventsChannel,
+    pub service_events: Channel<NetworkMonitorEvents>,
+    pub task_ctl: Channel<()>,
+    pub rpc_api: Mutex<Option<Arc<dyn RpcApi>>>,
+    network: Mutex<Option<Network>>,
+    samples: Mutex<Arc<Vec<NetworkStatsSample>>>,
+    cursor: Mutex<Option<Cursor>>,
+    unsaved_samples: AtomicUsize,
+}
+
+impl NetworkMonitorService {
+    pub fn new(application_events: ApplicationEventsChannel, _settings: &Settings) -> Self {
+        Self {
+            application_events,
+            service_events: Channel::unbounded(),
+            task_ctl: Channel::oneshot(),
+            rpc_api: Mutex::new(None),
+            network: Mutex::new(None),
+            samples: Mutex::new(Arc::new(Vec::new())),
+            cursor: Mutex::new(None),
+            unsaved_samples: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn rpc_api(&self) -> Option<Arc<dyn RpcApi>> {
+        self.rpc_api.lock().unwrap().clone()
+    }
+
+    /// Network the samples belong to.
+    pub fn network(&self) -> Option<Network> {
+        *self.network.lock().unwrap()
+    }
+
+    /// Snapshot of the collected samples. The snapshot is shared
+    /// and copied only when a new sample is collected while it is held.
+    pub fn samples(&self) -> Arc<Vec<NetworkStatsSample>> {
+        self.samples.lock().unwrap().clone()
+    }
+
+    fn storage(network: Network) -> Result<Storage> {
+        Ok(Storage::try_new(&format!(
+            "kaspa-ng.network-stats.{network}"
+        ))?)
+    }
+
+    async fn load(&self, network: Network) -> Result<()> {
+        let storage = Self::storage(network)?;
+        let store = if storage.exists().await.unwrap_or(false) {
+            workflow_store::fs::read_json::<NetworkStatsStore>(storage.filename())
+                .await
+                .unwrap_or_else(|err| {
+                    log_warn!("Unable to load network statistics: {err}");
+                    NetworkStatsStore::default()
+                })
+        } else {
+            NetworkStatsStore::default()
+        };
+
+        *self.samples.lock().unwrap() = Arc::new(store.samples);
+        self.unsaved_samples.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn store(&self) -> Result<()> {
+        let Some(network) = self.network() else {
+            return Ok(());
+        };
+
+        if self.unsaved_samples.swap(0, Ordering::Relaxed) == 0 {
+            return Ok(());
+        }
+
+        let store = NetworkStatsStore {
+            samples: self.samples().as_ref().clone(),
+        };
+
+        let storage = Self::storage(network)?;
+        storage.ensure_dir().await?;
+        workflow_store::fs::write_json(storage.filename(), &store).await?;
+        Ok(())
+    }
+
+    /// Identify the network of the connected node and
+    /// load the samples previously collected for it.
+    async fn connect(&self) -> Result<()> {
+        let Some(rpc_api) = self.rpc_api() else {
+            return Ok(());
+        };
+
+        let server_info = rpc_api.get_server_info().await?;
+        let network = Network::from(server_info.network_id);
+        if self.network() != Some(network) {
+            self.store().await?;
+            self.load(network).await?;
+            self.network.lock().unwrap().replace(network);
+        }
+        self.cursor.lock().unwrap().take();
+
+        self.sample().await
+    }
+
+    async fn disconnect(&self) -> Result<()> {
+        self.cursor.lock().unwrap().take();
+        self.store().await
+    }
+
+    /// Average transactions per second reported by the
+    /// metrics service over the sampling interval.
+    fn transactions_per_second(&self) -> Option<f64> {
+        let metrics_history = runtime().metrics_service().metrics_data();
+        let values = metrics_history
+            .get(&Metric::NetworkTransactionsPerSecond)?
+            .recent(NETWORK_STATS_SAMPLING_INTERVAL_SECONDS as f64 * 1000.0)
+            .into_iter()
+            .map(|sample| sample.avg)
+            .collect::<Vec<_>>();
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    }
+
+    async fn sample(&self) -> Result<()> {
+        let Some(rpc_api) = self.rpc_api() else {
+            return Ok(());
+        };
+
+        if self.network().is_none() {
+            return Ok(());
+        }
+
+        let dag_info = rpc_api.get_block_dag_info().await?;
+        let hashrate = rpc_api
+            .estimate_network_hashes_per_second(HASHRATE_ESTIMATION_WINDOW, None)
+            .await?;
+
+        let timestamp = unixtime_as_millis_f64();
+        let cursor = Cursor {
+            timestamp,
+            daa_score: dag_info.virtual_daa_score,
+            block_count: dag_info.block_count,
+        };
+
+        let (daa_score_rate, bps) = match self.cursor.lock().unwrap().replace(cursor) {
+            Some(previous) if timestamp > previous.timestamp => {
+                let elapsed = (timestamp - previous.timestamp) / 1000.0;
+                let daa_score_rate = dag_info
+                    .virtual_daa_score
+                    .checked_sub(previous.daa_score)
+                    .map(|delta| delta as f64 / elapsed);
+                // block count decreases when the node prunes
+                let bps = dag_info
+                    .block_count
+                    .checked_sub(previous.block_count)
+                    .map(|delta| delta as f64 / elapsed);
+                (daa_score_rate, bps)
+            }
+            _ => (None, None),
+        };
+
+        let sample = NetworkStatsSample {
+            timestamp,
+            hashrate: hashrate as f64,
+            difficulty: dag_info.difficulty,
+            daa_score_rate,
+            bps,
+            tps: self.transactions_per_second(),
+        };
+
+        {
+            let mut samples = self.samples.lock().unwrap();
+            let samples = Arc::make_mut(&mut samples);
+            samples.push(sample);
+            if samples.len() > MAX_NETWORK_STATS_SAMPLES {
+                let excess = samples.len() - MAX_NETWORK_STATS_SAMPLES;
+                samples.drain(0..excess);
+            }
+        }
+
+        if self.unsaved_samples.fetch_add(1, Ordering::Relaxed) + 1 >= NETWORK_STATS_STORE_INTERVAL
+        {
+            self.store().await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Service for NetworkMonitorService {
+    fn name(&self) -> &'static str {
+        "network-monitor"
+    }
+
+    async fn attach_rpc(self: Arc<Self>, rpc_api: &Arc<dyn RpcApi>) -> Result<()> {
+        self.rpc_api.lock().unwrap().replace(rpc_api.clone());
+        Ok(())
+    }
+
+    async fn detach_rpc(self: Arc<Self>) -> Result<()> {
+        self.rpc_api.lock().unwrap().take();
+        Ok(())
+    }
+
+    async fn connect_rpc(self: Arc<Self>) -> Result<()> {
+        self.service_events
+            .send(NetworkMonitorEvents::Connect)
+            .await?;
+        Ok(())
+    }
+
+    async fn disconnect_rpc(self: Arc<Self>) -> Result<()> {
+        self.service_events
+            .send(NetworkMonitorEvents::Disconnect)
+            .await?;
+        Ok(())
+    }
+
+    async fn spawn(self: Arc<Self>) -> Result<()> {
+        let interval = task::interval(Duration::from_secs(NETWORK_STATS_SAMPLING_INTERVAL_SECONDS));
+        pin_mut!(interval);
+
+        loop {
+            select! {
+                _ = interval.next().fuse() => {
+                    if let Err(err) = self.sample().await {
+                        log_warn!("Network statistics: {err}");
+                    }
+                },
+                msg = self.as_ref().service_events.receiver.recv().fuse() => {
+                    if let Ok(event) = msg {
+                        match event {
+                            NetworkMonitorEvents::Connect => {
+                                if let Err(err) = self.connect().await {
+                                    log_warn!("Network statistics: {err}");
+                                }
+                            }
+                            NetworkMonitorEvents::Disconnect => {
+                                if let Err(err) = self.disconnect().await {
+                                    log_warn!("Network statistics: {err}");
+                                }
+                            }
+                            NetworkMonitorEvents::Exit => {
+                                self.store().await.ok();
+                                break;
+                            }
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.task_ctl.send(()).await.unwrap();
+        Ok(())
+    }
+
+    fn terminate(self: Arc<Self>) {
+        self.service_events
+            .sender
+            .try_send(NetworkMonitorEvents::Exit)
+            .unwrap();
+    }
+
+    async fn join(self: Arc<Self>) -> Result<()> {
+        self.task_ctl.recv().await.unwrap();
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+use crate::imports::*;
+use kaspa_metrics_core::Metric;
+use kaspa_rpc_core::GetServerInfoResponse;
+use std::fmt::Write;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+/// Metric samples older than this are not exported
+const MAX_SAMPLE_AGE_MILLIS: f64 = 10_000.0;
+/// Maximum size of an accepted HTTP request header
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+/// Time allowed for a client to send the request header
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time allowed for the node to respond to the server info query
+const RPC_TIMEOUT: Duration = Duration::from_secs(2);
+/// Interval at which the cached node server info is refreshed
+const SERVER_INFO_POLLING_INTERVAL_SECONDS: u64 = 5;
+/// Maximum number of scrape connections served concurrently
+const MAX_CONNECTIONS: usize = 8;
+
+pub enum PrometheusServiceEvents {
+    Configure(PrometheusSettings),
+    Exit,
+}
+
+/// Serves node, network and application state in the Prometheus
+/// text exposition format at `http://<bind>/metrics`.
+pub struct PrometheusService {
+    pub application_events: ApplicationEventsChannel,
+    pub service_events: Channel<PrometheusServiceEvents>,
+    pub task_ctl: Channel<()>,
+    settings: Mutex<PrometheusSettings>,
+    server: Mutex<Option<JoinHandle<()>>>,
+    server_info: Mutex<Option<GetServerInfoResponse>>,
+}
+
+impl PrometheusService {
+    pub fn new(application_events: ApplicationEventsChannel, settings: &Settings) -> Self {
+        Self {
+            application_events,
+            service_events: Channel::unbounded(),
+            task_ctl: Channel::oneshot(),
+            settings: Mutex::new(settings.prometheus.clone()),
+            server: Mutex::new(None),
+            server_info: Mutex::new(None),
+        }
+    }
+
+    /// Server info of the connected node, refreshed periodically
+    /// so that scrapes do not issue RPC calls.
+    pub fn server_info(&self) -> Option<GetServerInfoResponse> {
+        self.server_info.lock().unwrap().clone()
+    }
+
+    async fn update_server_info(&self) {
+        let server_info = match runtime().kaspa_service().rpc_api() {
+            Some(rpc_api) if self.settings.lock().unwrap().enable => {
+                tokio::time::timeout(RPC_TIMEOUT, rpc_api.get_server_info())
+                    .await
+                    .ok()
+                    .and_then(|server_info| server_info.ok())
+            }
+            _ => None,
+        };
+        *self.server_info.lock().unwrap() = server_info;
+    }
+
+    /// Apply new endpoint settings, restarting the listener if needed.
+    pub fn configure(&self, settings: PrometheusSettings) {
+        self.service_events
+            .sender
+            .try_send(PrometheusServiceEvents::Configure(settings))
+            .ok();
+    }
+
+    fn start(&self) {
+        self.stop();
+
+        let settings = self.settings.lock().unwrap().clone();
+        if !settings.enable {
+            return;
+        }
+
+        let handle = tokio::spawn(async move {
+            let listener = match TcpListener::bind(settings.bind.as_str()).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    log_error!("Prometheus: unable to listen on '{}': {err}", settings.bind);
+                    return;
+                }
+            };
+
+            log_info!(
+                "Prometheus: serving metrics at http://{}/metrics",
+                settings.bind
+            );
+
+            let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+            loop {
+                // further connections wait in the listen backlog
+                let Ok(permit) = connections.clone().acquire_owned().await else {
+                    break;
+                };
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(async move {
+                            if let Err(err) = serve(stream).await {
+                                log_trace!("Prometheus: {err}");
+                            }
+                            drop(permit);
+                        });
+                    }
+                    Err(err) => {
+                        log_warn!("Prometheus: {err}");
+                    }
+                }
+            }
+        });
+
+        self.server.lock().unwrap().replace(handle);
+    }
+
+    fn stop(&self) {
+        if let Some(handle) = self.server.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Read the HTTP request header, returning `None` if the
+/// connection is closed or the header exceeds [`MAX_REQUEST_SIZE`].
+async fn read_request(stream: &mut TcpStream) -> Result<Option<Vec<u8>>> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let len = stream.read(&mut buffer).await?;
+        if len == 0 || request.len() + len > MAX_REQUEST_SIZE {
+            return Ok(None);
+        }
+        request.extend_from_slice(&buffer[..len]);
+    }
+    Ok(Some(request))
+}
+
+async fn serve(mut stream: TcpStream) -> Result<()> {
+    let Ok(request) = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await else {
+        return Ok(());
+    };
+    let Some(request) = request? else {
+        return Ok(());
+    };
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line.next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            render().await,
+        ),
+        ("GET", _) => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method Not Allowed\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Prometheus name of a metric: the CamelCase metric
+/// name converted to snake_case with a `kaspa_` prefix.
+fn metric_name(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut metric_name = String::from("kaspa");
+    for (index, c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            metric_name.push('_');
+            continue;
+        }
+
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+        let next = chars.get(index + 1);
+        let is_word_start = index == 0
+            || (c.is_ascii_uppercase()
+                && previous.is_some_and(|previous| {
+                    previous.is_ascii_lowercase()
+                        || previous.is_ascii_digit()
+                        || (previous.is_ascii_uppercase()
+                            && next.is_some_and(|next| next.is_ascii_lowercase()))
+                }));
+        if is_word_start {
+            metric_name.push('_');
+        }
+        metric_name.push(c.to_ascii_lowercase());
+    }
+    metric_name
+}
+
+fn gauge(text: &mut String, name: &str, help: &str, values: &[(Option<(&str, &str)>, f64)]) {
+    writeln!(text, "# HELP {name} {help}").ok();
+    writeln!(text, "# TYPE {name} gauge").ok();
+    for (label, value) in values {
+        match label {
+            Some((key, label)) => writeln!(text, "{name}{{{key}=\"{label}\"}} {value}").ok(),
+            None => writeln!(text, "{name} {value}").ok(),
+        };
+    }
+}
+
+/// Collect current values in the Prometheus text format.
+async fn render() -> String {
+    let runtime = runtime();
+    let mut text = String::new();
+
+    let server_info = runtime.prometheus_service().server_info();
+
+    gauge(
+        &mut text,
+        "kaspa_node_connected",
+        "Whether kaspa-ng is connected to a node",
+        &[(None, server_info.is_some() as u8 as f64)],
+    );
+
+    if let Some(server_info) = server_info.as_ref() {
+        gauge(
+            &mut text,
+            "kaspa_node_synced",
+            "Whether the connected node is synced",
+            &[(None, server_info.is_synced as u8 as f64)],
+        );
+        gauge(
+            &mut text,
+            "kaspa_node_virtual_daa_score",
+            "Virtual DAA score of the connected node",
+            &[(None, server_info.virtual_daa_score as f64)],
+        );
+    }
+
+    {
+        let metrics_history = runtime.metrics_service().metrics_data();
+        for metric in Metric::into_iter() {
+            let Some(sample) = metrics_history
+                .get(&metric)
+                .and_then(|history| history.latest(MAX_SAMPLE_AGE_MILLIS))
+            else {
+                continue;
+            };
+            gauge(
+                &mut text,
+                &metric_name(metric.as_str()),
+                metric.title().0,
+                &[(None, sample.avg)],
+            );
+        }
+    }
+
+    if let Some(peer_info) = runtime.peer_monitor_service().peer_info() {
+        let outbound = peer_info.iter().filter(|peer| peer.is_outbound).count();
+        let inbound = peer_info.len() - outbound;
+        gauge(
+            &mut text,
+            "kaspa_peers",
+            "Number of peers connected to the node",
+            &[
+                (Some(("direction", "inbound")), inbound as f64),
+                (Some(("direction", "outbound")), outbound as f64),
+            ],
+        );
+    }
+
+    if let Some(feerate) = runtime.feerate_monitor_service().feerate() {
+        let mut values = vec![(
+            Some(("bucket", "priority")),
+            feerate.priority_bucket.feerate,
+        )];
+        if let Some(bucket) = feerate.normal_buckets.first() {
+            values.push((Some(("bucket", "normal")), bucket.feerate));
+        }
+        if let Some(bucket) = feerate.low_buckets.first() {
+            values.push((Some(("bucket", "low")), bucket.feerate));
+        }
+        gauge(
+            &mut text,
+            "kaspa_feerate",
+            "Estimated feerate (sompi per gram of mass)",
+            &values,
+        );
+    }
+
+    text
+}
+
+#[async_trait]
+impl Service for PrometheusService {
+    fn name(&self) -> &'static str {
+        "prometheus"
+    }
+
+    async fn spawn(self: Arc<Self>) -> Result<()> {
+        self.start();
+
+        let interval = task::interval(Duration::from_secs(SERVER_INFO_POLLING_INTERVAL_SECONDS));
+        pin_mut!(interval);
+
+        loop {
+            select! {
+                _ = interval.next().fuse() => {
+                    self.update_server_info().await;
+                }
+                msg = self.service_events.receiver.recv().fuse() => {
+                    match msg {
+                        Ok(PrometheusServiceEvents::Configure(settings)) => {
+                            *self.settings.lock().unwrap() = settings;
+                            self.start();
+                            self.update_server_info().await;
+                        }
+                        Ok(PrometheusServiceEvents::Exit) | Err(_) => {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.stop();
+        self.task_ctl.send(()).await.unwrap();
+        Ok(())
+    }
+
+    fn terminate(self: Arc<Self>) {
+        self.service_events
+            .sender
+            .try_send(PrometheusServiceEvents::Exit)
+            .unwrap();
+    }
+
+    async fn join(self: Arc<Self>) -> Result<()> {
+        self.task_ctl.recv().await.unwrap();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_metric_name() {
+        assert_eq!(metric_name("NodeCpuUsage"), "kaspa_node_cpu_usage");
+        assert_eq!(metric_name("NodeP2pBytesTx"), "kaspa_node_p2p_bytes_tx");
+        assert_eq!(metric_name("NetworkTPS"), "kaspa_network_tps");
+        assert_eq!(metric_name("RPCRequests"), "kaspa_rpc_requests");
+
+        assert_eq!(
+            metric_name(Metric::NodeCpuUsage.as_str()),
+            "kaspa_node_cpu_usage"
+        );
+        assert_eq!(
+            metric_name(Metric::NodeResidentSetSizeBytes.as_str()),
+            "kaspa_node_resident_set_size_bytes"
+        );
+        assert_eq!(
+            metric_name(Metric::NetworkMempoolSize.as_str()),
+            "kaspa_network_mempool_size"
+        );
+    }
+}
@@ -3,6 +3,7 @@ use kaspa_metrics_core::Metric;
 use kaspa_utils::networking::ContextualNetAddress;
 use kaspa_wallet_core::storage::local::storage::Storage;
 use kaspa_wrpc_client::WrpcEncoding;
+use std::collections::BTreeMap;
 use workflow_core::{runtime, task::spawn};
 
 const SETTINGS_REVISION: &str = "0.0.0";
@@ -102,6 +103,23 @@ impl KaspadNodeKind {
         }
     }
 
+    /// Node kinds running as a separate process that can remain
+    /// active while the wallet is connected to another network.
+    pub fn is_background_capable(&self) -> bool {
+        match self {
+            KaspadNodeKind::Disable => false,
+            KaspadNodeKind::Remote => false,
+            #[cfg(not(target_arch = "wasm32"))]
+            KaspadNodeKind::IntegratedInProc => false,
+            #[cfg(not(target_arch = "wasm32"))]
+            KaspadNodeKind::IntegratedAsDaemon => true,
+            #[cfg(not(target_arch = "wasm32"))]
+            KaspadNodeKind::IntegratedAsPassiveSync => false,
+            #[cfg(not(target_arch = "wasm32"))]
+            KaspadNodeKind::ExternalAsDaemon => true,
+        }
+    }
+
     pub fn is_local(&self) -> bool {
         match self {
             KaspadNodeKind::Disable => false,
@@ -356,6 +374,195 @@ impl NodeMemoryScale {
     }
 }
 
+/// Default number of outbound p2p connections maintained by the node
+pub const DEFAULT_P2P_OUTBOUND_TARGET: usize = 8;
+/// Default maximum number of inbound p2p connections accepted by the node
+pub const DEFAULT_P2P_INBOUND_LIMIT: usize = 128;
+/// Maximum accepted number of outbound p2p connections
+pub const MAX_P2P_OUTBOUND_TARGET: usize = 128;
+/// Maximum accepted number of inbound p2p connections
+pub const MAX_P2P_INBOUND_LIMIT: usize = 1024;
+
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NodePeerMode {
+    #[default]
+    Default,
+    AddPeers,
+    ConnectOnly,
+}
+
+impl NodePeerMode {
+    pub fn iter() -> impl Iterator<Item = &'static NodePeerMode> {
+        [
+            NodePeerMode::Default,
+            NodePeerMode::AddPeers,
+            NodePeerMode::ConnectOnly,
+        ]
+        .iter()
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            NodePeerMode::Default => i18n("Discover peers automatically"),
+            NodePeerMode::AddPeers => {
+                i18n("Connect to the listed peers in addition to discovered peers")
+            }
+            NodePeerMode::ConnectOnly => i18n("Connect only to the listed peers"),
+        }
+    }
+}
+
+impl std::fmt::Display for NodePeerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodePeerMode::Default => write!(f, "{}", i18n("Default")),
+            NodePeerMode::AddPeers => write!(f, "{}", i18n("Add Peers")),
+            NodePeerMode::ConnectOnly => write!(f, "{}", i18n("Connect Only")),
+        }
+    }
+}
+
+fn default_p2p_network_interface() -> NetworkInterfaceConfig {
+    NetworkInterfaceConfig {
+        kind: NetworkInterfaceKind::Any,
+        custom: ContextualNetAddress::unspecified(),
+    }
+}
+
+fn default_p2p_outbound_target() -> usize {
+    DEFAULT_P2P_OUTBOUND_TARGET
+}
+
+fn default_p2p_inbound_limit() -> usize {
+    DEFAULT_P2P_INBOUND_LIMIT
+}
+
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NodeIoPriority {
+    #[default]
+    Default,
+    Low,
+    Idle,
+}
+
+impl NodeIoPriority {
+    pub fn iter() -> impl Iterator<Item = &'static NodeIoPriority> {
+        [
+            NodeIoPriority::Default,
+            NodeIoPriority::Low,
+            NodeIoPriority::Idle,
+        ]
+        .iter()
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            NodeIoPriority::Default => i18n("Managed by the operating system"),
+            NodeIoPriority::Low => i18n("Lowest best-effort disk priority"),
+            NodeIoPriority::Idle => i18n("Disk access only when no other process needs it"),
+        }
+    }
+}
+
+impl std::fmt::Display for NodeIoPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeIoPriority::Default => write!(f, "{}", i18n("Default")),
+            NodeIoPriority::Low => write!(f, "{}", i18n("Low")),
+            NodeIoPriority::Idle => write!(f, "{}", i18n("Idle")),
+        }
+    }
+}
+
+/// Operating system resource controls applied to
+/// the node process when it runs as a daemon.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct NodeProcessSettings {
+    pub enable_nice: bool,
+    pub nice: i32,
+    pub io_priority: NodeIoPriority,
+    pub enable_cpu_affinity: bool,
+    /// List of CPU cores, e.g. `0-3,6`
+    pub cpu_affinity: String,
+    pub enable_memory_limit: bool,
+    /// Memory ceiling in megabytes
+    pub memory_limit: u64,
+}
+
+impl Default for NodeProcessSettings {
+    fn default() -> Self {
+        Self {
+            enable_nice: false,
+            nice: 10,
+            io_priority: NodeIoPriority::default(),
+            enable_cpu_affinity: false,
+            cpu_affinity: String::default(),
+            enable_memory_limit: false,
+            memory_limit: 8 * 1024,
+        }
+    }
+}
+
+impl NodeProcessSettings {
+    /// Parse the CPU affinity list (e.g. `0-3,6`) into core indexes.
+    pub fn cpu_affinity_cores(&self) -> Result<Vec<usize>> {
+        // the list is validated by the settings UI on every frame
+        static CORE_COUNT: OnceLock<usize> = OnceLock::new();
+        let core_count = *CORE_COUNT.get_or_init(|| {
+            std::thread::available_parallelism()
+                .map(|count| count.get())
+                .unwrap_or(1)
+        });
+        self.cpu_affinity_cores_within(core_count)
+    }
+
+    /// Parse the CPU affinity list, rejecting cores that are not
+    /// below `core_count`. The returned indexes are sorted and unique.
+    fn cpu_affinity_cores_within(&self, core_count: usize) -> Result<Vec<usize>> {
+        let mut cores = Vec::new();
+        for range in self
+            .cpu_affinity
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let invalid = || Error::custom(format!("{} {range}", i18n("Invalid CPU core:")));
+            let (first, last) = if let Some((first, last)) = range.split_once('-') {
+                let first = first.trim().parse::<usize>().map_err(|_| invalid())?;
+                let last = last.trim().parse::<usize>().map_err(|_| invalid())?;
+                (first, last)
+            } else {
+                let core = range.parse::<usize>().map_err(|_| invalid())?;
+                (core, core)
+            };
+
+            if first > last {
+                return Err(invalid());
+            }
+            if last >= core_count {
+                return Err(Error::custom(format!(
+                    "{} {range} ({} {core_count})",
+                    i18n("CPU core is not available:"),
+                    i18n("cores:")
+                )));
+            }
+            cores.extend(first..=last);
+        }
+
+        cores.sort_unstable();
+        cores.dedup();
+
+        if cores.is_empty() {
+            Err(Error::custom(i18n("Please specify at least one CPU core")))
+        } else {
+            Ok(cores)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct NodeSettings {
@@ -372,6 +579,18 @@ pub struct NodeSettings {
     pub enable_grpc: bool,
     pub grpc_network_interface: NetworkInterfaceConfig,
     pub enable_upnp: bool,
+    #[serde(default = "default_p2p_network_interface")]
+    pub p2p_network_interface: NetworkInterfaceConfig,
+    #[serde(default = "default_p2p_outbound_target")]
+    pub p2p_outbound_target: usize,
+    #[serde(default = "default_p2p_inbound_limit")]
+    pub p2p_inbound_limit: usize,
+    #[serde(default)]
+    pub p2p_peer_mode: NodePeerMode,
+    #[serde(default)]
+    pub p2p_peers: Vec<ContextualNetAddress>,
+    #[serde(default)]
+    pub archival: bool,
     pub memory_scale: NodeMemoryScale,
 
     pub network: Network,
@@ -383,6 +602,10 @@ pub struct NodeSettings {
     pub kaspad_daemon_storage_folder_enable: bool,
     #[serde(default)]
     pub kaspad_daemon_storage_folder: String,
+    #[serde(default)]
+    pub run_in_background: bool,
+    #[serde(default)]
+    pub process: NodeProcessSettings,
 }
 
 impl Default for NodeSettings {
@@ -399,6 +622,12 @@ impl Default for NodeSettings {
             enable_grpc: false,
             grpc_network_interface: NetworkInterfaceConfig::default(),
             enable_upnp: true,
+            p2p_network_interface: default_p2p_network_interface(),
+            p2p_outbound_target: DEFAULT_P2P_OUTBOUND_TARGET,
+            p2p_inbound_limit: DEFAULT_P2P_INBOUND_LIMIT,
+            p2p_peer_mode: NodePeerMode::default(),
+            p2p_peers: Vec::new(),
+            archival: false,
             memory_scale: NodeMemoryScale::default(),
             network: Network::default(),
             node_kind: KaspadNodeKind::default(),
@@ -407,6 +636,8 @@ impl Default for NodeSettings {
             kaspad_daemon_args_enable: false,
             kaspad_daemon_storage_folder_enable: false,
             kaspad_daemon_storage_folder: String::default(),
+            run_in_background: false,
+            process: NodeProcessSettings::default(),
         }
     }
 }
@@ -436,8 +667,19 @@ impl NodeSettings {
                     || self.enable_wrpc_json != other.enable_wrpc_json
                     || self.wrpc_json_network_interface != other.wrpc_json_network_interface
                     || self.enable_upnp != other.enable_upnp
+                    || self.wrpc_borsh_network_interface != other.wrpc_borsh_network_interface
                 {
                     Some(self.node_kind != KaspadNodeKind::IntegratedInProc)
+                } else if self.p2p_network_interface != other.p2p_network_interface
+                    || self.p2p_outbound_target != other.p2p_outbound_target
+                    || self.p2p_inbound_limit != other.p2p_inbound_limit
+                    || self.p2p_peer_mode != other.p2p_peer_mode
+                    || self.p2p_peers != other.p2p_peers
+                    || self.archival != other.archival
+                    || self.run_in_background != other.run_in_background
+                    || self.process != other.process
+                {
+                    Some(self.node_kind.is_config_capable())
                 } else if self.kaspad_daemon_args != other.kaspad_daemon_args
                     || self.kaspad_daemon_args_enable != other.kaspad_daemon_args_enable
                 {
@@ -471,6 +713,43 @@ impl NodeSettings {
     }
 }
 
+impl NodeSettings {
+    /// Validate p2p related settings, returning a user-facing
+    /// error message if the configuration can not be applied.
+    pub fn p2p_settings_error(&self) -> Option<&'static str> {
+        if self.p2p_outbound_target == 0 && self.p2p_peer_mode != NodePeerMode::ConnectOnly {
+            Some(i18n("Outbound peer target must be at least 1"))
+        } else if self.p2p_outbound_target > MAX_P2P_OUTBOUND_TARGET {
+            Some(i18n("Outbound peer target is too high"))
+        } else if self.p2p_inbound_limit > MAX_P2P_INBOUND_LIMIT {
+            Some(i18n("Inbound peer limit is too high"))
+        } else if self.p2p_peer_mode != NodePeerMode::Default && self.p2p_peers.is_empty() {
+            Some(i18n("Please specify at least one peer address"))
+        } else if self.enable_grpc
+            && self.p2p_network_interface.kind == NetworkInterfaceKind::Custom
+            && self.grpc_network_interface.kind == NetworkInterfaceKind::Custom
+            && self.p2p_network_interface.custom.normalize(0).port != 0
+            && self.p2p_network_interface.custom.normalize(0).port
+                == self.grpc_network_interface.custom.normalize(0).port
+        {
+            Some(i18n("p2p listen port conflicts with the gRPC port"))
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse a list of peer addresses separated by whitespace or commas.
+pub fn parse_peer_list(text: &str) -> Result<Vec<ContextualNetAddress>> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            ContextualNetAddress::from_str(s)
+                .map_err(|_| Error::custom(format!("{} {s}", i18n("Invalid peer address:"))))
+        })
+        .collect()
+}
+
 impl RpcConfig {
     pub fn from_node_settings(settings: &NodeSettings, _options: Option<RpcOptions>) -> Self {
         match settings.connection_config_kind {
@@ -494,6 +773,107 @@ impl RpcConfig {
     }
 }
 
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AlertComparison {
+    #[default]
+    Above,
+    Below,
+}
+
+impl AlertComparison {
+    pub fn list() -> &'static [AlertComparison] {
+        &[AlertComparison::Above, AlertComparison::Below]
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            AlertComparison::Above => ">",
+            AlertComparison::Below => "<",
+        }
+    }
+
+    pub fn test(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertComparison::Above => value > threshold,
+            AlertComparison::Below => value < threshold,
+        }
+    }
+}
+
+/// User-defined alert raised when a metric stays above or
+/// below the threshold for at least `duration_secs` seconds.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct AlertRule {
+    pub enabled: bool,
+    pub metric: Metric,
+    pub comparison: AlertComparison,
+    pub threshold: f64,
+    pub duration_secs: u64,
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            metric: Metric::NodeActivePeers,
+            comparison: AlertComparison::Below,
+            threshold: 1.0,
+            duration_secs: 60,
+        }
+    }
+}
+
+impl std::fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} ({} {}s)",
+            i18n(self.metric.title().0),
+            self.comparison.symbol(),
+            self.metric.format(self.threshold, true, false),
+            i18n("for"),
+            self.duration_secs
+        )
+    }
+}
+
+/// Graph of a metrics dashboard; multiple metrics
+/// are overlaid on the same plot, each with its own axis.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetricsGraph {
+    pub metrics: Vec<Metric>,
+    pub height: usize,
+}
+
+impl MetricsGraph {
+    pub fn new(metric: Metric) -> Self {
+        Self {
+            metrics: vec![metric],
+            height: 160,
+        }
+    }
+}
+
+/// Named, user-defined selection and ordering of metrics graphs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetricsDashboard {
+    pub name: String,
+    pub graphs: Vec<MetricsGraph>,
+}
+
+impl MetricsDashboard {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            graphs: vec![],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct MetricsSettings {
@@ -502,6 +882,23 @@ pub struct MetricsSettings {
     pub graph_range_from: isize,
     pub graph_range_to: isize,
     pub disabled: AHashSet<Metric>,
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    #[serde(default)]
+    pub dashboards: Vec<MetricsDashboard>,
+    /// Name of the selected dashboard, all metrics are shown if `None`
+    #[serde(default)]
+    pub dashboard: Option<String>,
+}
+
+impl MetricsSettings {
+    pub fn dashboard(&self) -> Option<&MetricsDashboard> {
+        self.dashboard.as_ref().and_then(|name| {
+            self.dashboards
+                .iter()
+                .find(|dashboard| &dashboard.name == name)
+        })
+    }
 }
 
 impl Default for MetricsSettings {
@@ -512,6 +909,9 @@ impl Default for MetricsSettings {
             graph_range_from: -15 * 60,
             graph_range_to: 0,
             disabled: AHashSet::default(),
+            alerts: vec![],
+            dashboards: vec![],
+            dashboard: None,
         }
     }
 }
@@ -591,6 +991,114 @@ impl DeveloperSettings {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PrometheusSettings {
+    pub enable: bool,
+    /// Address the Prometheus metrics endpoint listens on
+    pub bind: String,
+}
+
+impl Default for PrometheusSettings {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            bind: "127.0.0.1:9464".to_string(),
+        }
+    }
+}
+
+/// Generic JSON price endpoint, queried once per currency.
+/// `{currency}` (or `{CURRENCY}` for uppercase) in the URL and
+/// paths is replaced with the currency ticker. Paths are
+/// dot-separated object keys or array indices (`data.0.price`);
+/// optional paths may be left empty.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonEndpointConfig {
+    pub name: String,
+    pub url: String,
+    pub price: String,
+    pub market_cap: String,
+    pub volume: String,
+    pub change: String,
+}
+
+impl Default for JsonEndpointConfig {
+    fn default() -> Self {
+        Self {
+            name: "Price Service".to_string(),
+            url: "http://127.0.0.1:8080/price/kaspa?currency={currency}".to_string(),
+            price: "price".to_string(),
+            market_cap: String::new(),
+            volume: String::new(),
+            change: String::new(),
+        }
+    }
+}
+
+/// Fixed prices per currency, for offline use and testing.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FixedPriceConfig {
+    pub prices: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum MarketDataProviderConfig {
+    CoinGecko,
+    CoinMarketCap,
+    JsonEndpoint(JsonEndpointConfig),
+    FixedPrice(FixedPriceConfig),
+}
+
+impl MarketDataProviderConfig {
+    /// Unique provider identifier. JSON endpoints are identified by their URL.
+    pub fn id(&self) -> String {
+        match self {
+            Self::CoinGecko => "coingecko".to_string(),
+            Self::CoinMarketCap => "coinmarketcap".to_string(),
+            Self::JsonEndpoint(config) => format!("json:{}", config.url),
+            Self::FixedPrice(_) => "fixed-price".to_string(),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            Self::CoinGecko => "CoinGecko".to_string(),
+            Self::CoinMarketCap => "CoinMarketCap".to_string(),
+            Self::JsonEndpoint(config) => config.name.clone(),
+            Self::FixedPrice(_) => i18n("Fixed Price").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct MarketMonitorSettings {
+    /// Currencies market data is fetched in (lowercase tickers).
+    /// The first currency is the primary display currency.
+    pub currencies: Vec<String>,
+    /// Market data providers in failover order
+    pub providers: Vec<MarketDataProviderConfig>,
+}
+
+impl Default for MarketMonitorSettings {
+    fn default() -> Self {
+        Self {
+            currencies: vec!["usd".to_string(), "btc".to_string()],
+            providers: vec![MarketDataProviderConfig::CoinGecko],
+        }
+    }
+}
+
+impl MarketMonitorSettings {
+    pub fn primary_currency(&self) -> Option<&str> {
+        self.currencies.first().map(String::as_str)
+    }
+}
+
 #[derive(Describe, Default, Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum EstimatorMode {
@@ -633,10 +1141,18 @@ pub struct Settings {
     #[serde(default)]
     pub estimator: EstimatorSettings,
     pub node: NodeSettings,
+    /// Node settings of networks other than the currently
+    /// selected network, restored when switching networks.
+    #[serde(default)]
+    pub network_node_settings: HashMap<Network, NodeSettings>,
     pub user_interface: UserInterfaceSettings,
     pub language_code: String,
     pub update_monitor: bool,
     pub market_monitor: bool,
+    #[serde(default)]
+    pub market: MarketMonitorSettings,
+    #[serde(default)]
+    pub prometheus: PrometheusSettings,
     // #[serde(default)]
     // pub disable_frame: bool,
 }
@@ -653,16 +1169,39 @@ impl Default for Settings {
             developer: DeveloperSettings::default(),
             estimator: EstimatorSettings::default(),
             node: NodeSettings::default(),
+            network_node_settings: HashMap::default(),
             user_interface: UserInterfaceSettings::default(),
             language_code: "en".to_string(),
             update_monitor: true,
             market_monitor: true,
+            market: MarketMonitorSettings::default(),
+            prometheus: PrometheusSettings::default(),
             // disable_frame: false,
         }
     }
 }
 
-impl Settings {}
+impl Settings {
+    /// Retain the node settings of the current network and
+    /// restore the node settings previously used with `network`.
+    /// Networks without stored settings inherit the current
+    /// node settings.
+    pub fn change_network(&mut self, network: Network) {
+        if self.node.network == network {
+            return;
+        }
+
+        let mut node = self
+            .network_node_settings
+            .remove(&network)
+            .unwrap_or_else(|| self.node.clone());
+        node.network = network;
+
+        let previous = std::mem::replace(&mut self.node, node);
+        self.network_node_settings
+            .insert(previous.network, previous);
+    }
+}
 
 fn storage() -> Result<Storage> {
     Ok(Storage::try_new("kaspa-ng.settings")?)
@@ -730,3 +1269,72 @@ impl Settings {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_peer_list() {
+        let peers = parse_peer_list("127.0.0.1:16111, 10.0.0.2\n 192.168.1.10:16112").unwrap();
+        assert_eq!(peers.len(), 3);
+        assert_eq!(peers[0].to_string(), "127.0.0.1:16111");
+        assert_eq!(peers[2].to_string(), "192.168.1.10:16112");
+
+        assert!(parse_peer_list("").unwrap().is_empty());
+        assert!(parse_peer_list(" ,\n\t, ").unwrap().is_empty());
+        assert!(parse_peer_list("127.0.0.1:16111 not-an-address:").is_err());
+    }
+
+    #[test]
+    fn test_change_network() {
+        let mut settings = Settings::default();
+        settings.node.network = Network::Mainnet;
+        settings.node.archival = true;
+
+        // a network without stored settings inherits the current node settings
+        settings.change_network(Network::Testnet10);
+        assert_eq!(settings.node.network, Network::Testnet10);
+        assert!(settings.node.archival);
+        assert!(settings
+            .network_node_settings
+            .contains_key(&Network::Mainnet));
+
+        settings.node.archival = false;
+        settings.change_network(Network::Mainnet);
+        assert_eq!(settings.node.network, Network::Mainnet);
+        assert!(settings.node.archival);
+
+        settings.change_network(Network::Testnet10);
+        assert_eq!(settings.node.network, Network::Testnet10);
+        assert!(!settings.node.archival);
+        assert_eq!(settings.network_node_settings.len(), 1);
+
+        // switching to the current network is a no-op
+        settings.change_network(Network::Testnet10);
+        assert_eq!(settings.network_node_settings.len(), 1);
+    }
+
+    #[test]
+    fn test_cpu_affinity_cores() {
+        let cores = |cpu_affinity: &str| {
+            NodeProcessSettings {
+                cpu_affinity: cpu_affinity.to_string(),
+                ..Default::default()
+            }
+            .cpu_affinity_cores_within(8)
+        };
+
+        assert_eq!(cores("0-3,6").unwrap(), vec![0, 1, 2, 3, 6]);
+        assert_eq!(cores(" 2 , 4 - 5 ").unwrap(), vec![2, 4, 5]);
+        assert_eq!(cores("7").unwrap(), vec![7]);
+        assert_eq!(cores("3,0-3,1").unwrap(), vec![0, 1, 2, 3]);
+        assert!(cores("8").is_err());
+        assert!(cores("0-18446744073709551615").is_err());
+        assert!(cores("").is_err());
+        assert!(cores(" , ").is_err());
+        assert!(cores("3-1").is_err());
+        assert!(cores("a").is_err());
+        assert!(cores("1-").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::runtime::services::kaspa::{MaintenanceStatus, PortConflict};
 use kaspa_consensus_core::network::NetworkId;
 use kaspa_metrics_core::MetricsSnapshot;
 use kaspa_wallet_core::events::SyncState;
@@ -16,6 +17,8 @@ pub struct State {
     pub node_peers: Option<usize>,
     pub node_mempool_size: Option<usize>,
     pub network_tps: Option<f64>,
+    pub maintenance: Option<MaintenanceStatus>,
+    pub port_conflicts: Vec<PortConflict>,
 
     pub error: Option<String>,
 }
@@ -74,4 +77,12 @@ impl State {
     pub fn mempool_size(&self) -> Option<usize> {
         self.node_mempool_size
     }
+
+    pub fn maintenance(&self) -> &Option<MaintenanceStatus> {
+        &self.maintenance
+    }
+
+    pub fn port_conflicts(&self) -> &[PortConflict] {
+        &self.port_conflicts
+    }
 }
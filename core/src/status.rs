@@ -14,6 +14,9 @@ enum ConnectionStatus {
         sync_status: Option<SyncStatus>,
         peers: Option<usize>,
     },
+    Maintenance {
+        sync_status: SyncStatus,
+    },
     Error {
         error: String,
     },
@@ -50,7 +53,20 @@ impl<'core> Status<'core> {
 
     pub fn render(&mut self, ui: &mut egui::Ui) {
         menu::bar(ui, |ui| {
-            if !self.state().is_connected() {
+            if let Some(status) = self.state().maintenance() {
+                let sync_status = SyncStatus::from_maintenance(status);
+                self.render_connected_state(ui, ConnectionStatus::Maintenance { sync_status });
+            } else if !self.state().port_conflicts().is_empty() {
+                let ports = self
+                    .state()
+                    .port_conflicts()
+                    .iter()
+                    .map(|conflict| format!("{} ({})", conflict.kind, conflict.address.port()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let error = format!("{} {ports}", i18n("Node ports are in use:"));
+                self.render_connected_state(ui, ConnectionStatus::Error { error });
+            } else if !self.state().is_connected() {
                 self.render_connected_state(ui, ConnectionStatus::Disconnected);
             } else {
                 let peers = self.state().peers();
@@ -415,6 +431,21 @@ impl<'core> Status<'core> {
                 });
             }
 
+            ConnectionStatus::Maintenance { sync_status } => {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.add_space(left_padding);
+                        ui.add(egui::Spinner::new());
+                        ui.separator();
+                        sync_status.render_text_state(ui);
+                    });
+
+                    sync_status
+                        .progress_bar(ui)
+                        .map(|bar| ui.add(bar.desired_width(status_area_width)));
+                });
+            }
+
             ConnectionStatus::Error { error } => {
                 ui.add_space(left_padding);
 
@@ -1,4 +1,5 @@
 use crate::imports::*;
+use crate::runtime::services::kaspa::NodeMaintenance;
 
 #[derive(PartialEq, Eq)]
 pub struct StorageFolder {
@@ -8,6 +9,7 @@ pub struct StorageFolder {
     pub folder_size: u64,
     pub folder_size_string: String,
     pub confirm_deletion: bool,
+    pub confirm_maintenance: Option<NodeMaintenance>,
 }
 
 impl Ord for StorageFolder {
@@ -137,6 +139,7 @@ impl Storage {
                 folder_size,
                 folder_size_string,
                 confirm_deletion: false,
+                confirm_maintenance: None,
             });
 
             folders.sort();
@@ -209,6 +212,7 @@ impl Storage {
         let mut folders = self.folders.lock().unwrap();
         for folder in folders.iter_mut() {
             folder.confirm_deletion = false;
+            folder.confirm_maintenance = None;
         }
     }
 
@@ -221,7 +225,7 @@ impl Storage {
                 .show(ui, |ui| {
                     ui.vertical(|ui| {
                         for folder in folders.iter_mut() {
-                            let StorageFolder { network, folder_size_string, path, confirm_deletion, .. } = folder;
+                            let StorageFolder { network, folder_size_string, path, confirm_deletion, confirm_maintenance, .. } = folder;
 
                             CollapsingHeader::new(format!("{}: {folder_size_string}", network.to_string().to_uppercase()))
                             .default_open(false)
@@ -242,6 +246,35 @@ impl Storage {
                                 if is_running {
                                     ui.label(i18n("Cannot delete data folder while the node is running"));
                                     ui.label(i18n("Please set node to 'Disabled' to delete the data folder"));
+
+                                    let maintenance_enabled = !*confirm_deletion && confirm_maintenance.is_none() && core.state().maintenance().is_none();
+                                    ui.add_space(4.);
+                                    ui.horizontal(|ui|{
+                                        NodeMaintenance::iter().for_each(|operation| {
+                                            if ui.medium_button_enabled(maintenance_enabled, operation.to_string()).clicked() {
+                                                *confirm_maintenance = Some(*operation);
+                                            }
+                                        });
+                                    });
+                                }
+
+                                if let Some(operation) = *confirm_maintenance {
+                                    ui.add_sized(vec2(260.,4.), Separator::default());
+                                    ui.label(operation.describe());
+                                    ui.label("");
+                                    ui.colored_label(theme_color().alert_color, format!("{}: {operation}", i18n("Please Confirm")));
+                                    if let Some(response) = ui.confirm_medium_apply_cancel(Align::Min) {
+                                        match response {
+                                            Confirm::Ack => {
+                                                *confirm_maintenance = None;
+                                                runtime().kaspa_service().maintenance(operation, &core.settings.node);
+                                            },
+                                            Confirm::Nack => {
+                                                *confirm_maintenance = None;
+                                            }
+                                        }
+                                    }
+                                    ui.add_sized(vec2(260.,4.), Separator::default());
                                 }
 
                                 if *confirm_deletion {
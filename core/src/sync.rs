@@ -1,4 +1,5 @@
 use crate::imports::*;
+use crate::runtime::services::kaspa::MaintenanceStatus;
 use kaspa_wallet_core::events::SyncState;
 
 const SYNC_STAGES: usize = 5;
@@ -102,6 +103,18 @@ impl SyncStatus {
         }
     }
 
+    pub fn from_maintenance(status: &MaintenanceStatus) -> Self {
+        SyncStatus {
+            caption: format!("{}: {}", status.operation, status.network),
+            text_status: Some(status.caption.clone()),
+            progress_bar_percentage: status.progress,
+            progress_bar_text: status
+                .progress
+                .map(|progress| format!("{:.0}%", progress * 100.0)),
+            ..Default::default()
+        }
+    }
+
     pub fn progress_bar(&self, ui: &mut egui::Ui) -> Option<egui::ProgressBar> {
         let progress_color = theme_color().progress_color;
         if let Some(progress_bar_percentage) = self.progress_bar_percentage {